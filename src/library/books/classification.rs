@@ -0,0 +1,258 @@
+// books/classification.rs
+
+use super::models::BookError;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Classification {
+    Dewey,
+    LibraryOfCongress,
+}
+
+impl fmt::Display for Classification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Classification::Dewey => write!(f, "Dewey"),
+            Classification::LibraryOfCongress => write!(f, "LCC"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CallNumber {
+    pub scheme: Classification,
+    pub value: String,
+}
+
+/// One filing element of a call number. Numbers compare as decimals, so
+/// "100.15" files before "100.2" and cutter "R87" files before "R9".
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum SortPart {
+    Number { integer: u64, fraction: String },
+    Letters(String),
+}
+
+impl CallNumber {
+    pub fn parse(scheme: Classification, value: &str) -> Result<Self, BookError> {
+        let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+        let valid = match scheme {
+            Classification::Dewey => is_dewey_class(&value),
+            Classification::LibraryOfCongress => is_lcc_class(&value),
+        };
+
+        if !valid {
+            return Err(BookError::InvalidCallNumber(value));
+        }
+
+        Ok(Self { scheme, value })
+    }
+
+    fn sort_key(&self) -> Vec<SortPart> {
+        let mut parts = Vec::new();
+        let mut rest = self.value.as_str();
+
+        if self.scheme == Classification::LibraryOfCongress {
+            let end = rest
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(rest.len());
+            parts.push(SortPart::Letters(rest[..end].to_ascii_uppercase()));
+            rest = rest[end..].trim_start();
+        }
+
+        let end = class_number_len(rest);
+        parts.push(class_number(&rest[..end]));
+
+        for chunk in rest[end..].split(|c: char| c.is_whitespace() || c == '.') {
+            parts.extend(cutter_parts(chunk));
+        }
+
+        parts
+    }
+
+    /// Whether this call number files under `prefix`, e.g. "823.912" under "823".
+    fn files_under(&self, prefix: &CallNumber) -> bool {
+        let own = self.sort_key();
+        let prefix = prefix.sort_key();
+
+        prefix.len() <= own.len()
+            && prefix.iter().zip(&own).all(|(p, o)| match (p, o) {
+                (
+                    SortPart::Number {
+                        integer: pi,
+                        fraction: pf,
+                    },
+                    SortPart::Number {
+                        integer: oi,
+                        fraction: of,
+                    },
+                ) => pi == oi && of.starts_with(pf.as_str()),
+                _ => p == o,
+            })
+    }
+
+    /// Inclusive shelf range check; the upper bound also covers everything filed under it.
+    pub fn is_within(&self, from: &CallNumber, to: &CallNumber) -> bool {
+        self.scheme == from.scheme
+            && self.scheme == to.scheme
+            && self >= from
+            && (self <= to || self.files_under(to))
+    }
+}
+
+fn is_dewey_class(value: &str) -> bool {
+    let class = value.split_whitespace().next().unwrap_or("");
+    let (integer, fraction) = class.split_once('.').unwrap_or((class, ""));
+
+    integer.len() == 3
+        && integer.chars().all(|c| c.is_ascii_digit())
+        && fraction.chars().all(|c| c.is_ascii_digit())
+}
+
+fn is_lcc_class(value: &str) -> bool {
+    let letters: String = value
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect();
+    let after = &value[letters.len()..];
+
+    (1..=3).contains(&letters.len()) && after.trim_start().starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Length of the leading class number, stopping before a cutter such as ".R87".
+fn class_number_len(text: &str) -> usize {
+    let mut seen_point = false;
+
+    for (i, c) in text.char_indices() {
+        let continues = c.is_ascii_digit()
+            || (c == '.' && !seen_point && text[i + 1..].starts_with(|n: char| n.is_ascii_digit()));
+        if !continues {
+            return i;
+        }
+        seen_point |= c == '.';
+    }
+
+    text.len()
+}
+
+fn class_number(text: &str) -> SortPart {
+    let (integer, fraction) = text.split_once('.').unwrap_or((text, ""));
+
+    SortPart::Number {
+        integer: integer.parse().unwrap_or(0),
+        fraction: fraction.trim_end_matches('0').to_string(),
+    }
+}
+
+/// Cutters ("R87") file as a letter followed by a decimal fraction, plain
+/// numbers such as years file as integers.
+fn cutter_parts(chunk: &str) -> Vec<SortPart> {
+    if chunk.is_empty() {
+        return Vec::new();
+    }
+
+    if chunk.chars().all(|c| c.is_ascii_digit()) {
+        return vec![SortPart::Number {
+            integer: chunk.parse().unwrap_or(u64::MAX),
+            fraction: String::new(),
+        }];
+    }
+
+    let letters: String = chunk.chars().take_while(|c| !c.is_ascii_digit()).collect();
+    let digits = &chunk[letters.len()..];
+
+    let mut parts = vec![SortPart::Letters(letters.to_uppercase())];
+    if !digits.is_empty() {
+        parts.push(SortPart::Number {
+            integer: 0,
+            fraction: digits.trim_end_matches('0').to_string(),
+        });
+    }
+    parts
+}
+
+impl PartialEq for CallNumber {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for CallNumber {}
+
+impl PartialOrd for CallNumber {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CallNumber {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.scheme
+            .cmp(&other.scheme)
+            .then_with(|| self.sort_key().cmp(&other.sort_key()))
+    }
+}
+
+impl fmt::Display for CallNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dewey(value: &str) -> CallNumber {
+        CallNumber::parse(Classification::Dewey, value).expect("Número de chamada inválido")
+    }
+
+    fn lcc(value: &str) -> CallNumber {
+        CallNumber::parse(Classification::LibraryOfCongress, value)
+            .expect("Número de chamada inválido")
+    }
+
+    #[test]
+    fn test_dewey_sorts_decimals_numerically() {
+        assert!(dewey("100.15") < dewey("100.2"));
+        assert!(dewey("099.9") < dewey("100"));
+        assert!(dewey("100.2") == dewey("100.20"));
+    }
+
+    #[test]
+    fn test_dewey_cutter_sorts_as_decimal() {
+        assert!(dewey("823.912 O79") < dewey("823.912 O8"));
+        assert!(dewey("823.912 O79 1949") > dewey("823.912 O79"));
+    }
+
+    #[test]
+    fn test_lcc_sort_order() {
+        assert!(lcc("QA9") < lcc("QA76"));
+        assert!(lcc("QA76.73.R87") < lcc("QA76.9"));
+        assert!(lcc("QA76.73.R87 2018") < lcc("QA76.73.R9"));
+        assert!(lcc("PS3545") < lcc("QA1"));
+    }
+
+    #[test]
+    fn test_invalid_call_numbers() {
+        assert!(matches!(
+            CallNumber::parse(Classification::Dewey, "12.5"),
+            Err(BookError::InvalidCallNumber(_))
+        ));
+        assert!(matches!(
+            CallNumber::parse(Classification::LibraryOfCongress, "76.73"),
+            Err(BookError::InvalidCallNumber(_))
+        ));
+    }
+
+    #[test]
+    fn test_is_within_includes_items_under_upper_bound() {
+        let from = dewey("800");
+        let to = dewey("823");
+        assert!(dewey("823.912 O79").is_within(&from, &to));
+        assert!(dewey("800").is_within(&from, &to));
+        assert!(!dewey("824").is_within(&from, &to));
+        assert!(!lcc("PR6029").is_within(&from, &to));
+    }
+}
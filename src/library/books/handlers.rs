@@ -1,5 +1,8 @@
 use super::service;
-use crate::library::books::models::{Book, BookError};
+use crate::library::books::classification::CallNumber;
+use crate::library::books::models::{Book, BookError, MaterialDetails};
+use crate::library::fines::service as fines_service;
+use chrono::NaiveDate;
 
 pub(crate) fn save_books(books: &[Book]) -> Result<(), BookError> {
     service::save_to_json("books.json", books)
@@ -13,14 +16,18 @@ pub(crate) fn search_books(books: &[Book], query: &str) -> Vec<Book> {
     service::search_books(books, query)
 }
 
+pub(crate) fn find_by_identifier<'a>(books: &'a [Book], identifier: &str) -> Option<&'a Book> {
+    service::find_by_identifier(books, identifier)
+}
+
 pub(crate) fn update_book(
-    books: &mut Vec<Book>,
+    books: &mut [Book],
     book_id: u32,
     title: Option<String>,
     author: Option<String>,
-    pages: Option<u32>,
+    details: Option<MaterialDetails>,
 ) -> Result<(), BookError> {
-    service::update_book(books, book_id, title, author, pages)
+    service::update_book(books, book_id, title, author, details)
 }
 
 pub(crate) fn add_book(books: &mut Vec<Book>, book: Book) -> Result<(), BookError> {
@@ -31,13 +38,90 @@ pub(crate) fn delete_book_by_id(books: &mut Vec<Book>, book_id: u32) -> Result<(
     service::delete_book_by_id(books, book_id)
 }
 
+pub(crate) fn set_location(
+    books: &mut [Book],
+    book_id: u32,
+    call_number: Option<CallNumber>,
+    shelf_location: Option<String>,
+) -> Result<(), BookError> {
+    service::set_location(books, book_id, call_number, shelf_location)
+}
+
+pub(crate) fn shelf_list(books: &[Book], from: &CallNumber, to: &CallNumber) -> Vec<Book> {
+    service::shelf_list(books, from, to)
+}
+
+pub(crate) fn send_to_repair(
+    books: &mut [Book],
+    book_id: u32,
+    reason: &str,
+    date: NaiveDate,
+) -> Result<(), BookError> {
+    service::send_to_repair(books, book_id, reason, date)
+}
+
+pub(crate) fn declare_lost(
+    books: &mut [Book],
+    book_id: u32,
+    reason: &str,
+    date: NaiveDate,
+) -> Result<(), BookError> {
+    service::declare_lost(books, book_id, reason, date)
+}
+
+pub(crate) fn withdraw_book(
+    books: &mut [Book],
+    book_id: u32,
+    reason: &str,
+    date: NaiveDate,
+) -> Result<(), BookError> {
+    service::withdraw_book(books, book_id, reason, date)
+}
+
+pub(crate) fn restore_to_shelf(
+    books: &mut [Book],
+    book_id: u32,
+    reason: &str,
+    date: NaiveDate,
+) -> Result<(), BookError> {
+    service::restore_to_shelf(books, book_id, reason, date)
+}
+
 pub(crate) fn print_books(books: &[Book]) {
     for book in books {
         println!("ID: {}", book.id);
         println!("Title: {}", book.title);
         println!("Author: {}", book.author);
-        println!("Pages: {}", book.pages);
-        println!("Borrowed: {}", book.is_borrowed);
+        println!("Format: {}", book.format());
+        println!("Details: {}", book.details);
+        if let Some(barcode) = &book.barcode {
+            println!("Barcode: {}", barcode);
+        }
+        if let Some(call_number) = &book.call_number {
+            println!("Call Number: {} ({})", call_number, call_number.scheme);
+        }
+        if let Some(location) = &book.shelf_location {
+            println!("Location: {}", location);
+        }
+        if let Some(price) = book.price_cents {
+            println!("Replacement Price: {}", fines_service::format_amount(price));
+        }
+        println!("Status: {}", book.status);
         println!();
     }
 }
+
+pub(crate) fn print_shelf_list(books: &[Book]) {
+    for book in books {
+        let call_number = book
+            .call_number
+            .as_ref()
+            .map(|c| c.to_string())
+            .unwrap_or_default();
+        let location = book.shelf_location.as_deref().unwrap_or("-");
+        println!(
+            "{:<24} {:<12} {} ({}) [{}]",
+            call_number, location, book.title, book.author, book.status
+        );
+    }
+}
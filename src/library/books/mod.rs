@@ -1,3 +1,4 @@
+pub mod classification;
 pub mod handlers;
 pub mod models;
 pub mod service;
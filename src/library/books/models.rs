@@ -1,27 +1,309 @@
 // books/models.rs
 
+use super::classification::CallNumber;
+use crate::library::ids::models::IdError;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::io;
 
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ItemStatus {
+    #[default]
+    Available,
+    OnLoan,
+    OnHoldShelf,
+    InTransit,
+    InRepair,
+    Damaged,
+    Lost,
+    Missing,
+    Withdrawn,
+}
+
+impl ItemStatus {
+    /// Returns whether a copy in this state may be moved to `next`.
+    pub fn can_transition_to(self, next: ItemStatus) -> bool {
+        use ItemStatus::*;
+
+        match self {
+            Available => matches!(
+                next,
+                OnLoan | OnHoldShelf | InTransit | InRepair | Damaged | Lost | Missing | Withdrawn
+            ),
+            OnLoan => matches!(
+                next,
+                Available | OnHoldShelf | InTransit | InRepair | Damaged | Lost | Missing
+            ),
+            OnHoldShelf => matches!(next, Available | OnLoan | InTransit | Missing),
+            InTransit => matches!(next, Available | OnHoldShelf | Lost | Missing),
+            InRepair => matches!(next, Available | Lost | Withdrawn),
+            Damaged => matches!(next, Available | InRepair | Lost | Withdrawn),
+            Lost => matches!(next, Available | Withdrawn),
+            Missing => matches!(next, Available | Lost | Withdrawn),
+            Withdrawn => false,
+        }
+    }
+}
+
+impl fmt::Display for ItemStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            ItemStatus::Available => "Available",
+            ItemStatus::OnLoan => "On loan",
+            ItemStatus::OnHoldShelf => "On hold shelf",
+            ItemStatus::InTransit => "In transit",
+            ItemStatus::InRepair => "In repair",
+            ItemStatus::Damaged => "Damaged",
+            ItemStatus::Lost => "Lost",
+            ItemStatus::Missing => "Missing",
+            ItemStatus::Withdrawn => "Withdrawn",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum MaterialFormat {
+    #[default]
+    Book,
+    Periodical,
+    Dvd,
+    Audiobook,
+    BoardGame,
+    Equipment,
+}
+
+impl fmt::Display for MaterialFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            MaterialFormat::Book => "Book",
+            MaterialFormat::Periodical => "Periodical",
+            MaterialFormat::Dvd => "DVD",
+            MaterialFormat::Audiobook => "Audiobook",
+            MaterialFormat::BoardGame => "Board game",
+            MaterialFormat::Equipment => "Equipment",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// What kind of item a catalogue record describes, with the fields that
+/// only make sense for that kind.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum MaterialDetails {
+    Book {
+        pages: u32,
+    },
+    Periodical {
+        #[serde(default)]
+        pages: u32,
+    },
+    Dvd {
+        runtime_minutes: u32,
+        discs: u32,
+    },
+    Audiobook {
+        runtime_minutes: u32,
+        discs: u32,
+    },
+    BoardGame {
+        min_players: u32,
+        max_players: u32,
+    },
+    Equipment {
+        serial_number: String,
+        /// Parts that go out with the device, such as a charger or a case.
+        #[serde(default)]
+        accessories: Vec<String>,
+    },
+}
+
+impl MaterialDetails {
+    pub fn format(&self) -> MaterialFormat {
+        match self {
+            MaterialDetails::Book { .. } => MaterialFormat::Book,
+            MaterialDetails::Periodical { .. } => MaterialFormat::Periodical,
+            MaterialDetails::Dvd { .. } => MaterialFormat::Dvd,
+            MaterialDetails::Audiobook { .. } => MaterialFormat::Audiobook,
+            MaterialDetails::BoardGame { .. } => MaterialFormat::BoardGame,
+            MaterialDetails::Equipment { .. } => MaterialFormat::Equipment,
+        }
+    }
+
+    /// Details with every type-specific field left empty.
+    pub fn blank(format: MaterialFormat) -> Self {
+        match format {
+            MaterialFormat::Book => MaterialDetails::Book { pages: 0 },
+            MaterialFormat::Periodical => MaterialDetails::Periodical { pages: 0 },
+            MaterialFormat::Dvd => MaterialDetails::Dvd {
+                runtime_minutes: 0,
+                discs: 1,
+            },
+            MaterialFormat::Audiobook => MaterialDetails::Audiobook {
+                runtime_minutes: 0,
+                discs: 1,
+            },
+            MaterialFormat::BoardGame => MaterialDetails::BoardGame {
+                min_players: 1,
+                max_players: 1,
+            },
+            MaterialFormat::Equipment => MaterialDetails::Equipment {
+                serial_number: String::new(),
+                accessories: Vec::new(),
+            },
+        }
+    }
+
+    /// What staff check when equipment goes out: the device itself, then
+    /// each accessory. Other material has no checklist.
+    pub fn checklist_items(&self) -> Vec<String> {
+        match self {
+            MaterialDetails::Equipment { accessories, .. } => {
+                let mut items = vec!["Device".to_string()];
+                items.extend(accessories.iter().cloned());
+                items
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for MaterialDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MaterialDetails::Book { pages } | MaterialDetails::Periodical { pages } => {
+                write!(f, "{} pages", pages)
+            }
+            MaterialDetails::Dvd {
+                runtime_minutes,
+                discs,
+            }
+            | MaterialDetails::Audiobook {
+                runtime_minutes,
+                discs,
+            } => write!(f, "{} min, {} disc(s)", runtime_minutes, discs),
+            MaterialDetails::BoardGame {
+                min_players,
+                max_players,
+            } => write!(f, "{}-{} players", min_players, max_players),
+            MaterialDetails::Equipment {
+                serial_number,
+                accessories,
+            } => {
+                write!(f, "S/N {}", serial_number)?;
+                if !accessories.is_empty() {
+                    write!(f, ", with {}", accessories.join(", "))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StatusChange {
+    pub from: ItemStatus,
+    pub to: ItemStatus,
+    pub at: NaiveDateTime,
+    pub reason: String,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Book {
     pub id: u32,
     pub title: String,
+    /// Author, director or manufacturer; may be empty.
+    #[serde(default)]
     pub author: String,
-    pub pages: u32,
-    pub is_borrowed: bool,
+    pub details: MaterialDetails,
+    /// Scanned at the desk to identify the copy.
+    #[serde(default)]
+    pub barcode: Option<String>,
+    #[serde(default)]
+    pub call_number: Option<CallNumber>,
+    #[serde(default)]
+    pub shelf_location: Option<String>,
+    /// Replacement cost billed when a borrower loses or damages the copy.
+    #[serde(default)]
+    pub price_cents: Option<i64>,
+    #[serde(default)]
+    pub status: ItemStatus,
+    #[serde(default)]
+    pub status_history: Vec<StatusChange>,
+    /// The branch the copy belongs to and is returned to.
+    #[serde(default)]
+    pub home_branch: Option<u32>,
+    /// The branch the copy is at now; while in transit, the one it left.
+    #[serde(default)]
+    pub current_branch: Option<u32>,
 }
 
 impl Book {
+    /// A printed book with `pages` pages.
+    #[cfg(test)]
     pub fn new(id: u32, title: String, author: String, pages: u32) -> Self {
+        Self::with_details(id, title, author, MaterialDetails::Book { pages })
+    }
+
+    pub fn with_details(id: u32, title: String, author: String, details: MaterialDetails) -> Self {
         Self {
             id,
             title,
             author,
-            pages,
-            is_borrowed: false,
+            details,
+            barcode: None,
+            call_number: None,
+            shelf_location: None,
+            price_cents: None,
+            status: ItemStatus::Available,
+            status_history: Vec::new(),
+            home_branch: None,
+            current_branch: None,
+        }
+    }
+
+    pub fn format(&self) -> MaterialFormat {
+        self.details.format()
+    }
+
+    /// Whether a barcode or serial number tells this copy apart from other
+    /// copies of the same title.
+    pub fn is_marked(&self) -> bool {
+        self.barcode.is_some()
+            || matches!(
+                &self.details,
+                MaterialDetails::Equipment { serial_number, .. } if !serial_number.is_empty()
+            )
+    }
+
+    pub fn is_available(&self) -> bool {
+        self.status == ItemStatus::Available
+    }
+
+    /// Moves the copy to `to` on `date`, recording the change in its status
+    /// history.
+    pub fn transition(
+        &mut self,
+        to: ItemStatus,
+        reason: &str,
+        date: NaiveDate,
+    ) -> Result<(), BookError> {
+        if !self.status.can_transition_to(to) {
+            return Err(BookError::InvalidTransition {
+                from: self.status,
+                to,
+            });
         }
+
+        self.status_history.push(StatusChange {
+            from: self.status,
+            to,
+            at: date.and_time(NaiveTime::MIN),
+            reason: reason.to_string(),
+        });
+        self.status = to;
+        Ok(())
     }
 }
 
@@ -31,6 +313,17 @@ pub enum BookError {
     JsonError(serde_json::Error),
     BookNotFound,
     BookAlreadyExists,
+    BookIdAlreadyExists(u32),
+    BarcodeAlreadyExists(String),
+    InvalidTransition {
+        from: ItemStatus,
+        to: ItemStatus,
+    },
+    InvalidCallNumber(String),
+    /// The copy is out on the loan with this id and has to go through
+    /// check-in or the lost-loan workflow instead.
+    OnLoan(u32),
+    IdError(IdError),
 }
 
 impl fmt::Display for BookError {
@@ -38,8 +331,22 @@ impl fmt::Display for BookError {
         match self {
             BookError::IoError(err) => write!(f, "IO Error: {}", err),
             BookError::JsonError(err) => write!(f, "JSON Error: {}", err),
+            BookError::IdError(err) => write!(f, "{}", err),
             BookError::BookNotFound => write!(f, "Book not found"),
             BookError::BookAlreadyExists => write!(f, "Book already exists"),
+            BookError::BookIdAlreadyExists(id) => write!(f, "A book with ID {} already exists", id),
+            BookError::BarcodeAlreadyExists(barcode) => {
+                write!(f, "A book with barcode {} already exists", barcode)
+            }
+            BookError::InvalidTransition { from, to } => {
+                write!(f, "Cannot change book status from {} to {}", from, to)
+            }
+            BookError::InvalidCallNumber(value) => write!(f, "Invalid call number: {}", value),
+            BookError::OnLoan(loan_id) => write!(
+                f,
+                "Item is out on loan {}; check it in or declare the loan lost",
+                loan_id
+            ),
         }
     }
 }
@@ -57,3 +364,9 @@ impl From<serde_json::Error> for BookError {
         BookError::JsonError(err)
     }
 }
+
+impl From<IdError> for BookError {
+    fn from(err: IdError) -> Self {
+        BookError::IdError(err)
+    }
+}
@@ -0,0 +1,99 @@
+use super::service;
+use crate::library::books::models::Book;
+use crate::library::branches::models::{
+    Branch, BranchAvailability, BranchError, Branches, Transfer,
+};
+use crate::library::ids::models::IdAllocator;
+use chrono::NaiveDate;
+
+pub(crate) fn save_branches(branches: &Branches) -> Result<(), BranchError> {
+    service::save_to_json("branches.json", branches)
+}
+
+pub(crate) fn read_branches() -> Result<Branches, BranchError> {
+    service::read_from_json("branches.json")
+}
+
+pub(crate) fn add_branch(
+    branches: &mut Vec<Branch>,
+    ids: &mut IdAllocator,
+    name: String,
+    address: String,
+) -> Result<u32, BranchError> {
+    service::add_branch(branches, ids, name, address)
+}
+
+pub(crate) fn branch_name(branches: &[Branch], branch_id: u32) -> String {
+    service::branch_name(branches, branch_id)
+}
+
+pub(crate) fn send_transfer(
+    records: &mut Branches,
+    books: &mut [Book],
+    ids: &mut IdAllocator,
+    book_id: u32,
+    to_branch: u32,
+    date: NaiveDate,
+    reason: String,
+) -> Result<u32, BranchError> {
+    service::send_transfer(records, books, ids, book_id, to_branch, date, reason)
+}
+
+pub(crate) fn receive_transfer(
+    transfers: &mut [Transfer],
+    books: &mut [Book],
+    transfer_id: u32,
+    date: NaiveDate,
+) -> Result<Transfer, BranchError> {
+    service::receive_transfer(transfers, books, transfer_id, date)
+}
+
+pub(crate) fn get_in_transit(transfers: &[Transfer]) -> Vec<Transfer> {
+    service::get_in_transit(transfers)
+}
+
+pub(crate) fn availability(books: &[Book], branches: &[Branch]) -> Vec<BranchAvailability> {
+    service::availability(books, branches)
+}
+
+pub(crate) fn print_branches(branches: &[Branch]) {
+    for branch in branches {
+        println!("Branch ID: {}", branch.id);
+        println!("Name: {}", branch.name);
+        if !branch.address.is_empty() {
+            println!("Address: {}", branch.address);
+        }
+        println!();
+    }
+}
+
+pub(crate) fn print_transfers(transfers: &[Transfer], branches: &[Branch], books: &[Book]) {
+    for transfer in transfers {
+        let title = books
+            .iter()
+            .find(|b| b.id == transfer.book_id)
+            .map(|b| b.title.as_str())
+            .unwrap_or("Unknown book");
+        println!(
+            "Transfer {}: {} (ID {}) from {} to {}, sent {}",
+            transfer.id,
+            title,
+            transfer.book_id,
+            service::branch_name(branches, transfer.from_branch),
+            service::branch_name(branches, transfer.to_branch),
+            transfer.sent_on
+        );
+        if !transfer.reason.is_empty() {
+            println!("Reason: {}", transfer.reason);
+        }
+    }
+}
+
+pub(crate) fn print_availability(availability: &[BranchAvailability]) {
+    for branch in availability {
+        println!(
+            "{}: {} of {} available",
+            branch.name, branch.available, branch.total
+        );
+    }
+}
@@ -0,0 +1,3 @@
+pub mod handlers;
+pub mod models;
+pub mod service;
@@ -0,0 +1,120 @@
+use crate::library::books::models::BookError;
+use crate::library::ids::models::IdError;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Branch {
+    pub id: u32,
+    pub name: String,
+    #[serde(default)]
+    pub address: String,
+}
+
+/// An item sent from one branch to another. While `received_on` is empty
+/// the item is in transit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transfer {
+    pub id: u32,
+    pub book_id: u32,
+    pub from_branch: u32,
+    pub to_branch: u32,
+    pub sent_on: NaiveDate,
+    #[serde(default)]
+    pub received_on: Option<NaiveDate>,
+    #[serde(default)]
+    pub reason: String,
+}
+
+impl Transfer {
+    pub fn is_in_transit(&self) -> bool {
+        self.received_on.is_none()
+    }
+}
+
+/// Copies of a set of items available at one branch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchAvailability {
+    pub branch_id: u32,
+    pub name: String,
+    pub available: usize,
+    pub total: usize,
+}
+
+/// Branches and transfers, persisted in `branches.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Branches {
+    #[serde(default)]
+    pub branches: Vec<Branch>,
+    #[serde(default)]
+    pub transfers: Vec<Transfer>,
+}
+
+#[derive(Debug)]
+pub enum BranchError {
+    IoError(io::Error),
+    JsonError(serde_json::Error),
+    BookError(BookError),
+    BranchNotFound,
+    BranchAlreadyExists(String),
+    BookNotFound,
+    TransferNotFound,
+    /// The item has no current branch to send it from.
+    NoCurrentBranch,
+    AlreadyAtBranch,
+    /// Only items on the shelf can be sent to another branch.
+    ItemNotAvailable,
+    TransferAlreadyReceived,
+    IdError(IdError),
+}
+
+impl fmt::Display for BranchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BranchError::IoError(err) => write!(f, "IO Error: {}", err),
+            BranchError::JsonError(err) => write!(f, "JSON Error: {}", err),
+            BranchError::IdError(err) => write!(f, "{}", err),
+            BranchError::BookError(err) => write!(f, "{}", err),
+            BranchError::BranchNotFound => write!(f, "Branch not found"),
+            BranchError::BranchAlreadyExists(name) => {
+                write!(f, "Branch '{}' already exists", name)
+            }
+            BranchError::BookNotFound => write!(f, "Book not found"),
+            BranchError::TransferNotFound => write!(f, "Transfer not found"),
+            BranchError::NoCurrentBranch => write!(f, "Item is not assigned to a branch"),
+            BranchError::AlreadyAtBranch => write!(f, "Item is already at that branch"),
+            BranchError::ItemNotAvailable => write!(f, "Item is not on the shelf"),
+            BranchError::TransferAlreadyReceived => {
+                write!(f, "Transfer has already been received")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BranchError {}
+
+impl From<io::Error> for BranchError {
+    fn from(err: io::Error) -> Self {
+        BranchError::IoError(err)
+    }
+}
+
+impl From<serde_json::Error> for BranchError {
+    fn from(err: serde_json::Error) -> Self {
+        BranchError::JsonError(err)
+    }
+}
+
+impl From<IdError> for BranchError {
+    fn from(err: IdError) -> Self {
+        BranchError::IdError(err)
+    }
+}
+
+impl From<BookError> for BranchError {
+    fn from(err: BookError) -> Self {
+        BranchError::BookError(err)
+    }
+}
@@ -0,0 +1,302 @@
+use super::models::{Branch, BranchAvailability, BranchError, Branches, Transfer};
+use crate::library::books::models::{Book, ItemStatus};
+use crate::library::ids::models::IdAllocator;
+use chrono::NaiveDate;
+use std::fs::File;
+use std::io::ErrorKind;
+
+pub fn read_from_json(file_path: &str) -> Result<Branches, BranchError> {
+    let file = match File::open(file_path) {
+        Ok(file) => file,
+        Err(err) => {
+            if err.kind() == ErrorKind::NotFound {
+                return Ok(Branches::default());
+            } else {
+                return Err(BranchError::IoError(err));
+            }
+        }
+    };
+
+    let branches: Branches = serde_json::from_reader(file).map_err(BranchError::JsonError)?;
+
+    Ok(branches)
+}
+
+pub fn save_to_json(file_path: &str, branches: &Branches) -> Result<(), BranchError> {
+    let file = File::create(file_path)?;
+    serde_json::to_writer_pretty(file, branches)?;
+    Ok(())
+}
+
+pub fn add_branch(
+    branches: &mut Vec<Branch>,
+    ids: &mut IdAllocator,
+    name: String,
+    address: String,
+) -> Result<u32, BranchError> {
+    if branches.iter().any(|b| b.name.eq_ignore_ascii_case(&name)) {
+        return Err(BranchError::BranchAlreadyExists(name));
+    }
+
+    let id = ids.allocate()?;
+    branches.push(Branch { id, name, address });
+    Ok(id)
+}
+
+pub fn branch_name(branches: &[Branch], branch_id: u32) -> String {
+    branches
+        .iter()
+        .find(|b| b.id == branch_id)
+        .map(|b| b.name.clone())
+        .unwrap_or_else(|| format!("Branch {}", branch_id))
+}
+
+/// Sends an available item from its current branch to `to_branch`, putting
+/// it in transit. Returns the transfer id.
+pub fn send_transfer(
+    records: &mut Branches,
+    books: &mut [Book],
+    ids: &mut IdAllocator,
+    book_id: u32,
+    to_branch: u32,
+    date: NaiveDate,
+    reason: String,
+) -> Result<u32, BranchError> {
+    if !records.branches.iter().any(|b| b.id == to_branch) {
+        return Err(BranchError::BranchNotFound);
+    }
+
+    let book = books
+        .iter_mut()
+        .find(|b| b.id == book_id)
+        .ok_or(BranchError::BookNotFound)?;
+    let from_branch = book.current_branch.ok_or(BranchError::NoCurrentBranch)?;
+    if from_branch == to_branch {
+        return Err(BranchError::AlreadyAtBranch);
+    }
+    if !book.is_available() {
+        return Err(BranchError::ItemNotAvailable);
+    }
+
+    book.transition(
+        ItemStatus::InTransit,
+        &format!("Sent to {}", branch_name(&records.branches, to_branch)),
+        date,
+    )?;
+
+    let id = ids.allocate()?;
+    records.transfers.push(Transfer {
+        id,
+        book_id,
+        from_branch,
+        to_branch,
+        sent_on: date,
+        received_on: None,
+        reason,
+    });
+    Ok(id)
+}
+
+/// Books the item in at its destination and puts it back on the shelf.
+pub fn receive_transfer(
+    transfers: &mut [Transfer],
+    books: &mut [Book],
+    transfer_id: u32,
+    date: NaiveDate,
+) -> Result<Transfer, BranchError> {
+    let transfer = transfers
+        .iter_mut()
+        .find(|t| t.id == transfer_id)
+        .ok_or(BranchError::TransferNotFound)?;
+    if !transfer.is_in_transit() {
+        return Err(BranchError::TransferAlreadyReceived);
+    }
+
+    let book = books
+        .iter_mut()
+        .find(|b| b.id == transfer.book_id)
+        .ok_or(BranchError::BookNotFound)?;
+    book.transition(ItemStatus::Available, "Received from transfer", date)?;
+    book.current_branch = Some(transfer.to_branch);
+
+    transfer.received_on = Some(date);
+    Ok(transfer.clone())
+}
+
+pub fn get_in_transit(transfers: &[Transfer]) -> Vec<Transfer> {
+    transfers
+        .iter()
+        .filter(|t| t.is_in_transit())
+        .cloned()
+        .collect()
+}
+
+/// How many of `books` each branch has, and how many of those are on its
+/// shelf right now.
+pub fn availability(books: &[Book], branches: &[Branch]) -> Vec<BranchAvailability> {
+    branches
+        .iter()
+        .filter_map(|branch| {
+            let here: Vec<&Book> = books
+                .iter()
+                .filter(|b| b.current_branch == Some(branch.id))
+                .collect();
+            if here.is_empty() {
+                return None;
+            }
+            Some(BranchAvailability {
+                branch_id: branch.id,
+                name: branch.name.clone(),
+                available: here.iter().filter(|b| b.is_available()).count(),
+                total: here.len(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).expect("Data inválida")
+    }
+
+    fn records() -> Branches {
+        let mut records = Branches::default();
+        let mut ids = IdAllocator::default();
+        for name in ["Central", "Norte"] {
+            add_branch(
+                &mut records.branches,
+                &mut ids,
+                name.to_string(),
+                String::new(),
+            )
+            .expect("Falha ao adicionar filial");
+        }
+        records
+    }
+
+    fn book_at(id: u32, branch_id: u32) -> Book {
+        Book {
+            home_branch: Some(branch_id),
+            current_branch: Some(branch_id),
+            ..Book::new(id, "Rust Book".to_string(), "Steve".to_string(), 300)
+        }
+    }
+
+    #[test]
+    fn test_transfer_moves_item_between_branches() {
+        let mut records = records();
+        let mut books = vec![book_at(1, 1)];
+        let mut ids = IdAllocator::default();
+
+        let id = send_transfer(
+            &mut records,
+            &mut books,
+            &mut ids,
+            1,
+            2,
+            date(2024, 3, 1),
+            String::new(),
+        )
+        .expect("Falha ao enviar transferência");
+        assert_eq!(books[0].status, ItemStatus::InTransit);
+        assert_eq!(get_in_transit(&records.transfers).len(), 1);
+
+        let transfer = receive_transfer(&mut records.transfers, &mut books, id, date(2024, 3, 2))
+            .expect("Falha ao receber transferência");
+        assert_eq!(transfer.from_branch, 1);
+        assert!(books[0].is_available());
+        assert_eq!(books[0].current_branch, Some(2));
+        assert_eq!(books[0].home_branch, Some(1));
+        assert!(matches!(
+            receive_transfer(&mut records.transfers, &mut books, id, date(2024, 3, 2)),
+            Err(BranchError::TransferAlreadyReceived)
+        ));
+    }
+
+    #[test]
+    fn test_transfer_errors() {
+        let mut records = records();
+        let mut books = vec![
+            book_at(1, 1),
+            Book::new(2, "B".to_string(), "A".to_string(), 1),
+            Book {
+                status: ItemStatus::OnLoan,
+                ..book_at(3, 1)
+            },
+        ];
+        let mut ids = IdAllocator::default();
+        let mut send = |book_id, to_branch| {
+            send_transfer(
+                &mut records,
+                &mut books,
+                &mut ids,
+                book_id,
+                to_branch,
+                date(2024, 3, 1),
+                String::new(),
+            )
+        };
+
+        assert!(matches!(send(1, 9), Err(BranchError::BranchNotFound)));
+        assert!(matches!(send(1, 1), Err(BranchError::AlreadyAtBranch)));
+        assert!(matches!(send(2, 1), Err(BranchError::NoCurrentBranch)));
+        assert!(matches!(send(9, 1), Err(BranchError::BookNotFound)));
+        assert!(matches!(send(3, 2), Err(BranchError::ItemNotAvailable)));
+    }
+
+    #[test]
+    fn test_availability_per_branch() {
+        let records = records();
+        let mut books = vec![book_at(1, 1), book_at(2, 1), book_at(3, 2)];
+        books[1].status = ItemStatus::OnLoan;
+
+        let availability = availability(&books, &records.branches);
+        assert_eq!(
+            availability,
+            vec![
+                BranchAvailability {
+                    branch_id: 1,
+                    name: "Central".to_string(),
+                    available: 1,
+                    total: 2,
+                },
+                BranchAvailability {
+                    branch_id: 2,
+                    name: "Norte".to_string(),
+                    available: 1,
+                    total: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_save_and_read_branches() {
+        let temp_file = NamedTempFile::new().expect("Não foi possível criar arquivo temporário");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let records = records();
+        assert!(save_to_json(file_path, &records).is_ok());
+
+        let loaded = read_from_json(file_path).expect("Falha ao ler filiais");
+        assert_eq!(loaded.branches.len(), 2);
+        assert_eq!(loaded.branches[1].name, "Norte");
+    }
+
+    #[test]
+    fn test_read_from_malformed_json() {
+        let mut temp_file =
+            NamedTempFile::new().expect("Não foi possível criar arquivo temporário");
+        writeln!(temp_file, "isto não é um JSON válido")
+            .expect("Falha ao escrever no arquivo temporário");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let result = read_from_json(file_path);
+        assert!(matches!(result, Err(BranchError::JsonError(_))));
+    }
+}
@@ -0,0 +1,67 @@
+use super::service;
+use crate::library::calendar::models::{CalendarError, LibraryCalendar};
+use chrono::{NaiveDate, Weekday};
+
+pub(crate) fn save_calendar(calendar: &LibraryCalendar) -> Result<(), CalendarError> {
+    service::save_to_json("calendar.json", calendar)
+}
+
+pub(crate) fn read_calendar() -> Result<LibraryCalendar, CalendarError> {
+    service::read_from_json("calendar.json")
+}
+
+pub(crate) fn due_date(
+    calendar: &LibraryCalendar,
+    start: NaiveDate,
+    period_days: u32,
+) -> Option<NaiveDate> {
+    service::due_date(calendar, start, period_days)
+}
+
+pub(crate) fn open_days_between(calendar: &LibraryCalendar, from: NaiveDate, to: NaiveDate) -> i64 {
+    service::open_days_between(calendar, from, to)
+}
+
+pub(crate) fn set_open_weekdays(
+    calendar: &mut LibraryCalendar,
+    weekdays: Vec<Weekday>,
+) -> Result<(), CalendarError> {
+    service::set_open_weekdays(calendar, weekdays)
+}
+
+pub(crate) fn add_closure(
+    calendar: &mut LibraryCalendar,
+    date: NaiveDate,
+    reason: String,
+) -> Result<(), CalendarError> {
+    service::add_closure(calendar, date, reason)
+}
+
+pub(crate) fn remove_closure(
+    calendar: &mut LibraryCalendar,
+    date: NaiveDate,
+) -> Result<(), CalendarError> {
+    service::remove_closure(calendar, date)
+}
+
+pub(crate) fn print_calendar(calendar: &LibraryCalendar) {
+    let weekdays: Vec<String> = calendar
+        .open_weekdays
+        .iter()
+        .map(|w| w.to_string())
+        .collect();
+    println!("Open: {}", weekdays.join(", "));
+
+    if calendar.closures.is_empty() {
+        println!("Closures: None");
+    } else {
+        println!("Closures:");
+        for closure in &calendar.closures {
+            if closure.reason.is_empty() {
+                println!("  {}", closure.date);
+            } else {
+                println!("  {} - {}", closure.date, closure.reason);
+            }
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io;
+
+/// A one-off day the library is closed, such as a public holiday.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Closure {
+    pub date: NaiveDate,
+    #[serde(default)]
+    pub reason: String,
+}
+
+/// When the library is open: the weekly opening days minus one-off closures.
+/// Due dates never fall on a closed day and fines only count open days.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LibraryCalendar {
+    pub open_weekdays: Vec<Weekday>,
+    #[serde(default)]
+    pub closures: Vec<Closure>,
+}
+
+impl Default for LibraryCalendar {
+    fn default() -> Self {
+        Self {
+            open_weekdays: vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+                Weekday::Sat,
+                Weekday::Sun,
+            ],
+            closures: Vec::new(),
+        }
+    }
+}
+
+impl LibraryCalendar {
+    pub fn is_open(&self, date: NaiveDate) -> bool {
+        self.open_weekdays.contains(&date.weekday())
+            && !self.closures.iter().any(|c| c.date == date)
+    }
+}
+
+#[derive(Debug)]
+pub enum CalendarError {
+    IoError(io::Error),
+    JsonError(serde_json::Error),
+    ClosureAlreadyExists(NaiveDate),
+    ClosureNotFound(NaiveDate),
+    NoOpeningDays,
+}
+
+impl fmt::Display for CalendarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalendarError::IoError(err) => write!(f, "IO Error: {}", err),
+            CalendarError::JsonError(err) => write!(f, "JSON Error: {}", err),
+            CalendarError::ClosureAlreadyExists(date) => {
+                write!(f, "The library is already closed on {}", date)
+            }
+            CalendarError::ClosureNotFound(date) => write!(f, "No closure on {}", date),
+            CalendarError::NoOpeningDays => {
+                write!(f, "The library must open on at least one day of the week")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CalendarError {}
+
+impl From<io::Error> for CalendarError {
+    fn from(err: io::Error) -> Self {
+        CalendarError::IoError(err)
+    }
+}
+
+impl From<serde_json::Error> for CalendarError {
+    fn from(err: serde_json::Error) -> Self {
+        CalendarError::JsonError(err)
+    }
+}
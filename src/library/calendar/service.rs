@@ -0,0 +1,229 @@
+use super::models::{CalendarError, Closure, LibraryCalendar};
+use chrono::{Days, NaiveDate, Weekday};
+use std::fs::File;
+use std::io::ErrorKind;
+
+pub fn read_from_json(file_path: &str) -> Result<LibraryCalendar, CalendarError> {
+    let file = match File::open(file_path) {
+        Ok(file) => file,
+        Err(err) => {
+            if err.kind() == ErrorKind::NotFound {
+                return Ok(LibraryCalendar::default());
+            } else {
+                return Err(CalendarError::IoError(err));
+            }
+        }
+    };
+
+    let calendar: LibraryCalendar =
+        serde_json::from_reader(file).map_err(CalendarError::JsonError)?;
+
+    Ok(calendar)
+}
+
+pub fn save_to_json(file_path: &str, calendar: &LibraryCalendar) -> Result<(), CalendarError> {
+    let file = File::create(file_path)?;
+    serde_json::to_writer_pretty(file, calendar)?;
+    Ok(())
+}
+
+/// `date` itself when the library is open, otherwise the next open day.
+pub fn next_open_day(calendar: &LibraryCalendar, date: NaiveDate) -> NaiveDate {
+    // Every week has an open day unless a closure covers it, so this many
+    // days always reaches one.
+    let horizon = 7 * (calendar.closures.len() + 1);
+
+    date.iter_days()
+        .take(horizon)
+        .find(|d| calendar.is_open(*d))
+        .unwrap_or(date)
+}
+
+/// The due date for a loan period of `period_days` starting on `start`,
+/// rolled forward to the next open day.
+pub fn due_date(
+    calendar: &LibraryCalendar,
+    start: NaiveDate,
+    period_days: u32,
+) -> Option<NaiveDate> {
+    start
+        .checked_add_days(Days::new(period_days.into()))
+        .map(|date| next_open_day(calendar, date))
+}
+
+/// Open days after `from`, up to and including `to`.
+pub fn open_days_between(calendar: &LibraryCalendar, from: NaiveDate, to: NaiveDate) -> i64 {
+    from.iter_days()
+        .skip(1)
+        .take_while(|d| *d <= to)
+        .filter(|d| calendar.is_open(*d))
+        .count() as i64
+}
+
+pub fn set_open_weekdays(
+    calendar: &mut LibraryCalendar,
+    mut weekdays: Vec<Weekday>,
+) -> Result<(), CalendarError> {
+    weekdays.sort_by_key(|w| w.num_days_from_monday());
+    weekdays.dedup();
+    if weekdays.is_empty() {
+        return Err(CalendarError::NoOpeningDays);
+    }
+
+    calendar.open_weekdays = weekdays;
+    Ok(())
+}
+
+pub fn add_closure(
+    calendar: &mut LibraryCalendar,
+    date: NaiveDate,
+    reason: String,
+) -> Result<(), CalendarError> {
+    if calendar.closures.iter().any(|c| c.date == date) {
+        return Err(CalendarError::ClosureAlreadyExists(date));
+    }
+
+    calendar.closures.push(Closure { date, reason });
+    calendar.closures.sort_by_key(|c| c.date);
+    Ok(())
+}
+
+pub fn remove_closure(
+    calendar: &mut LibraryCalendar,
+    date: NaiveDate,
+) -> Result<(), CalendarError> {
+    let index = calendar
+        .closures
+        .iter()
+        .position(|c| c.date == date)
+        .ok_or(CalendarError::ClosureNotFound(date))?;
+
+    calendar.closures.remove(index);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).expect("Data inválida")
+    }
+
+    /// Closed on Sundays and on 2024-04-01 (a Monday).
+    fn calendar() -> LibraryCalendar {
+        let mut calendar = LibraryCalendar::default();
+        set_open_weekdays(
+            &mut calendar,
+            vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+                Weekday::Sat,
+            ],
+        )
+        .expect("Falha ao definir dias de funcionamento");
+        add_closure(&mut calendar, date(2024, 4, 1), "Feriado".to_string())
+            .expect("Falha ao adicionar fechamento");
+        calendar
+    }
+
+    #[test]
+    fn test_due_date_rolls_forward_past_closed_days() {
+        let calendar = calendar();
+        // 2024-03-17 + 14 days is Sunday 2024-03-31, followed by the closure.
+        assert_eq!(
+            due_date(&calendar, date(2024, 3, 17), 14),
+            Some(date(2024, 4, 2))
+        );
+        assert_eq!(
+            due_date(&calendar, date(2024, 3, 16), 14),
+            Some(date(2024, 3, 30))
+        );
+    }
+
+    #[test]
+    fn test_open_days_between_skips_closures() {
+        let calendar = calendar();
+        assert_eq!(
+            open_days_between(&calendar, date(2024, 3, 30), date(2024, 4, 3)),
+            2
+        );
+        assert_eq!(
+            open_days_between(&calendar, date(2024, 4, 3), date(2024, 4, 3)),
+            0
+        );
+        assert_eq!(
+            open_days_between(&calendar, date(2024, 4, 3), date(2024, 4, 1)),
+            0
+        );
+    }
+
+    #[test]
+    fn test_default_calendar_is_always_open() {
+        let calendar = LibraryCalendar::default();
+        assert_eq!(
+            next_open_day(&calendar, date(2024, 3, 31)),
+            date(2024, 3, 31)
+        );
+        assert_eq!(
+            open_days_between(&calendar, date(2024, 3, 1), date(2024, 3, 8)),
+            7
+        );
+    }
+
+    #[test]
+    fn test_closure_errors() {
+        let mut calendar = calendar();
+        assert!(matches!(
+            add_closure(&mut calendar, date(2024, 4, 1), String::new()),
+            Err(CalendarError::ClosureAlreadyExists(_))
+        ));
+        assert!(remove_closure(&mut calendar, date(2024, 4, 1)).is_ok());
+        assert!(matches!(
+            remove_closure(&mut calendar, date(2024, 4, 1)),
+            Err(CalendarError::ClosureNotFound(_))
+        ));
+        assert!(matches!(
+            set_open_weekdays(&mut calendar, Vec::new()),
+            Err(CalendarError::NoOpeningDays)
+        ));
+    }
+
+    #[test]
+    fn test_save_and_read_calendar() {
+        let temp_file = NamedTempFile::new().expect("Não foi possível criar arquivo temporário");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let calendar = calendar();
+        assert!(save_to_json(file_path, &calendar).is_ok());
+
+        let loaded = read_from_json(file_path).expect("Falha ao ler calendário");
+        assert_eq!(loaded, calendar);
+    }
+
+    #[test]
+    fn test_read_from_nonexistent_file() {
+        let result = read_from_json("arquivo_que_nao_existe.json");
+        assert_eq!(
+            result.expect("Falha ao ler calendário"),
+            LibraryCalendar::default()
+        );
+    }
+
+    #[test]
+    fn test_read_from_malformed_json() {
+        let mut temp_file =
+            NamedTempFile::new().expect("Não foi possível criar arquivo temporário");
+        writeln!(temp_file, "isto não é um JSON válido")
+            .expect("Falha ao escrever no arquivo temporário");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let result = read_from_json(file_path);
+        assert!(matches!(result, Err(CalendarError::JsonError(_))));
+    }
+}
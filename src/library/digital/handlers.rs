@@ -0,0 +1,130 @@
+use super::service;
+use crate::library::digital::models::{
+    DigitalCollection, DigitalError, DigitalHold, DigitalLoan, DigitalTitle, License,
+};
+use crate::library::ids::models::IdAllocator;
+use crate::library::users::models::User;
+use chrono::NaiveDate;
+
+pub(crate) fn save_collection(collection: &DigitalCollection) -> Result<(), DigitalError> {
+    service::save_to_json("digital.json", collection)
+}
+
+pub(crate) fn read_collection() -> Result<DigitalCollection, DigitalError> {
+    service::read_from_json("digital.json")
+}
+
+pub(crate) fn add_title(
+    titles: &mut Vec<DigitalTitle>,
+    ids: &mut IdAllocator,
+    title: DigitalTitle,
+) -> Result<u32, DigitalError> {
+    service::add_title(titles, ids, title)
+}
+
+pub(crate) fn add_license(
+    titles: &mut [DigitalTitle],
+    ids: &mut IdAllocator,
+    title_id: u32,
+    license: License,
+) -> Result<u32, DigitalError> {
+    service::add_license(titles, ids, title_id, license)
+}
+
+pub(crate) fn checkout(
+    collection: &mut DigitalCollection,
+    users: &[User],
+    ids: &mut IdAllocator,
+    title_id: u32,
+    user_id: u32,
+    date: NaiveDate,
+    period_days: u32,
+) -> Result<DigitalLoan, DigitalError> {
+    service::checkout(collection, users, ids, title_id, user_id, date, period_days)
+}
+
+pub(crate) fn place_hold(
+    collection: &mut DigitalCollection,
+    users: &[User],
+    ids: &mut IdAllocator,
+    title_id: u32,
+    user_id: u32,
+    date: NaiveDate,
+) -> Result<u32, DigitalError> {
+    service::place_hold(collection, users, ids, title_id, user_id, date)
+}
+
+pub(crate) fn cancel_hold(waitlist: &mut [DigitalHold], hold_id: u32) -> Result<(), DigitalError> {
+    service::cancel_hold(waitlist, hold_id)
+}
+
+pub(crate) fn return_early(
+    loans: &mut [DigitalLoan],
+    loan_id: u32,
+    date: NaiveDate,
+) -> Result<DigitalLoan, DigitalError> {
+    service::return_early(loans, loan_id, date)
+}
+
+pub(crate) fn expire_loans(loans: &mut [DigitalLoan], date: NaiveDate) -> Vec<DigitalLoan> {
+    service::expire_loans(loans, date)
+}
+
+pub(crate) fn assign_waitlist(
+    collection: &mut DigitalCollection,
+    users: &[User],
+    ids: &mut IdAllocator,
+    date: NaiveDate,
+    period_days: u32,
+) -> Vec<DigitalLoan> {
+    service::assign_waitlist(collection, users, ids, date, period_days)
+}
+
+pub(crate) fn get_user_loans(
+    loans: &[DigitalLoan],
+    user_id: u32,
+    date: NaiveDate,
+) -> Vec<DigitalLoan> {
+    service::get_user_loans(loans, user_id, date)
+}
+
+pub(crate) fn print_titles(collection: &DigitalCollection, date: NaiveDate) {
+    for title in &collection.titles {
+        println!("Digital Title ID: {}", title.id);
+        println!("Title: {}", title.title);
+        if !title.author.is_empty() {
+            println!("Author: {}", title.author);
+        }
+        for license in &title.licenses {
+            let state = if license.is_valid_on(date) {
+                ""
+            } else {
+                " (spent)"
+            };
+            println!("License {}: {}{}", license.id, license, state);
+        }
+        println!(
+            "On Loan: {}",
+            service::active_loans(&collection.loans, title.id, date)
+        );
+        println!(
+            "Waitlist: {}",
+            service::get_waitlist(&collection.waitlist, title.id).len()
+        );
+        println!();
+    }
+}
+
+pub(crate) fn print_loans(loans: &[DigitalLoan], titles: &[DigitalTitle]) {
+    for loan in loans {
+        let title = titles
+            .iter()
+            .find(|t| t.id == loan.title_id)
+            .map(|t| t.title.as_str())
+            .unwrap_or("Unknown title");
+        println!(
+            "Digital Loan {}: {} - user {} - {} to {}",
+            loan.id, title, loan.user_id, loan.loan_date, loan.due_date
+        );
+    }
+}
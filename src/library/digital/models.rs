@@ -0,0 +1,222 @@
+use crate::library::ids::models::IdError;
+use crate::library::loans::models::LoanError;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io;
+
+/// One purchase of lending rights for a digital title.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct License {
+    pub id: u32,
+    /// How many patrons may have the title at the same time.
+    pub concurrent_loans: u32,
+    /// Checkouts allowed before the license runs out.
+    #[serde(default)]
+    pub max_loans: Option<u32>,
+    #[serde(default)]
+    pub expires_on: Option<NaiveDate>,
+    #[serde(default)]
+    pub loans_used: u32,
+}
+
+impl License {
+    /// "One copy, one user": a single concurrent loan, forever.
+    pub fn one_copy_one_user(id: u32) -> Self {
+        Self::metered(id, 1, None, None)
+    }
+
+    /// `concurrent_loans` at a time, ending after `max_loans` checkouts or
+    /// on `expires_on`, whichever comes first.
+    pub fn metered(
+        id: u32,
+        concurrent_loans: u32,
+        max_loans: Option<u32>,
+        expires_on: Option<NaiveDate>,
+    ) -> Self {
+        Self {
+            id,
+            concurrent_loans,
+            max_loans,
+            expires_on,
+            loans_used: 0,
+        }
+    }
+
+    /// Whether the license may still be used for a new checkout on `date`.
+    pub fn is_valid_on(&self, date: NaiveDate) -> bool {
+        self.expires_on.is_none_or(|expiry| date < expiry)
+            && self.max_loans.is_none_or(|max| self.loans_used < max)
+    }
+}
+
+impl fmt::Display for License {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} concurrent", self.concurrent_loans)?;
+        if let Some(max) = self.max_loans {
+            write!(f, ", {}/{} loans used", self.loans_used, max)?;
+        }
+        if let Some(expiry) = self.expires_on {
+            write!(f, ", expires {}", expiry)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigitalTitle {
+    pub id: u32,
+    pub title: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub licenses: Vec<License>,
+}
+
+impl DigitalTitle {
+    pub fn new(id: u32, title: String, author: String) -> Self {
+        Self {
+            id,
+            title,
+            author,
+            licenses: Vec::new(),
+        }
+    }
+}
+
+/// A digital checkout. It ends by itself on its due date; returning it
+/// early is optional.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigitalLoan {
+    pub id: u32,
+    pub title_id: u32,
+    pub license_id: u32,
+    pub user_id: u32,
+    pub loan_date: NaiveDate,
+    pub due_date: NaiveDate,
+    #[serde(default)]
+    pub ended_on: Option<NaiveDate>,
+}
+
+impl DigitalLoan {
+    pub fn is_active_on(&self, date: NaiveDate) -> bool {
+        self.ended_on.is_none() && date < self.due_date
+    }
+}
+
+/// A patron waiting for a license to free up. They get the title
+/// automatically when one does.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigitalHold {
+    pub id: u32,
+    pub title_id: u32,
+    pub user_id: u32,
+    pub placed_on: NaiveDate,
+    /// The loan the hold turned into.
+    #[serde(default)]
+    pub loan_id: Option<u32>,
+    #[serde(default)]
+    pub cancelled: bool,
+}
+
+impl DigitalHold {
+    pub fn is_waiting(&self) -> bool {
+        self.loan_id.is_none() && !self.cancelled
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DigitalRules {
+    pub loan_period_days: u32,
+}
+
+impl Default for DigitalRules {
+    fn default() -> Self {
+        Self {
+            loan_period_days: 14,
+        }
+    }
+}
+
+/// Digital titles, their loans and waitlist, persisted in `digital.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DigitalCollection {
+    #[serde(default)]
+    pub titles: Vec<DigitalTitle>,
+    #[serde(default)]
+    pub loans: Vec<DigitalLoan>,
+    #[serde(default)]
+    pub waitlist: Vec<DigitalHold>,
+}
+
+#[derive(Debug)]
+pub enum DigitalError {
+    IoError(io::Error),
+    JsonError(serde_json::Error),
+    /// The patron may not borrow at the moment.
+    Patron(LoanError),
+    TitleNotFound,
+    UserNotFound,
+    LoanNotFound,
+    HoldNotFound,
+    NoLicenseAvailable,
+    LicenseAvailable,
+    AlreadyBorrowed,
+    AlreadyWaiting,
+    IdError(IdError),
+}
+
+impl fmt::Display for DigitalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DigitalError::IoError(err) => write!(f, "IO Error: {}", err),
+            DigitalError::JsonError(err) => write!(f, "JSON Error: {}", err),
+            DigitalError::IdError(err) => write!(f, "{}", err),
+            DigitalError::Patron(err) => write!(f, "{}", err),
+            DigitalError::TitleNotFound => write!(f, "Digital title not found"),
+            DigitalError::UserNotFound => write!(f, "User not found"),
+            DigitalError::LoanNotFound => write!(f, "Digital loan not found"),
+            DigitalError::HoldNotFound => write!(f, "Waitlist entry not found"),
+            DigitalError::NoLicenseAvailable => {
+                write!(f, "No license is free; the patron can join the waitlist")
+            }
+            DigitalError::LicenseAvailable => {
+                write!(
+                    f,
+                    "A license is free and the title can be borrowed right away"
+                )
+            }
+            DigitalError::AlreadyBorrowed => write!(f, "Patron already has this title"),
+            DigitalError::AlreadyWaiting => {
+                write!(f, "Patron is already on the waitlist for this title")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DigitalError {}
+
+impl From<io::Error> for DigitalError {
+    fn from(err: io::Error) -> Self {
+        DigitalError::IoError(err)
+    }
+}
+
+impl From<serde_json::Error> for DigitalError {
+    fn from(err: serde_json::Error) -> Self {
+        DigitalError::JsonError(err)
+    }
+}
+
+impl From<IdError> for DigitalError {
+    fn from(err: IdError) -> Self {
+        DigitalError::IdError(err)
+    }
+}
+
+impl From<LoanError> for DigitalError {
+    fn from(err: LoanError) -> Self {
+        DigitalError::Patron(err)
+    }
+}
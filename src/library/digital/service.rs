@@ -0,0 +1,536 @@
+use super::models::{
+    DigitalCollection, DigitalError, DigitalHold, DigitalLoan, DigitalTitle, License,
+};
+use crate::library::ids::models::IdAllocator;
+use crate::library::users::models::User;
+use chrono::{Days, NaiveDate};
+use std::fs::File;
+use std::io::ErrorKind;
+
+pub fn read_from_json(file_path: &str) -> Result<DigitalCollection, DigitalError> {
+    let file = match File::open(file_path) {
+        Ok(file) => file,
+        Err(err) => {
+            if err.kind() == ErrorKind::NotFound {
+                return Ok(DigitalCollection::default());
+            } else {
+                return Err(DigitalError::IoError(err));
+            }
+        }
+    };
+
+    let collection: DigitalCollection =
+        serde_json::from_reader(file).map_err(DigitalError::JsonError)?;
+
+    Ok(collection)
+}
+
+pub fn save_to_json(file_path: &str, collection: &DigitalCollection) -> Result<(), DigitalError> {
+    let file = File::create(file_path)?;
+    serde_json::to_writer_pretty(file, collection)?;
+    Ok(())
+}
+
+pub fn add_title(
+    titles: &mut Vec<DigitalTitle>,
+    ids: &mut IdAllocator,
+    mut title: DigitalTitle,
+) -> Result<u32, DigitalError> {
+    title.id = ids.allocate()?;
+    let id = title.id;
+    titles.push(title);
+    Ok(id)
+}
+
+pub fn add_license(
+    titles: &mut [DigitalTitle],
+    ids: &mut IdAllocator,
+    title_id: u32,
+    mut license: License,
+) -> Result<u32, DigitalError> {
+    let title = titles
+        .iter_mut()
+        .find(|t| t.id == title_id)
+        .ok_or(DigitalError::TitleNotFound)?;
+
+    license.id = ids.allocate()?;
+    let id = license.id;
+    title.licenses.push(license);
+    Ok(id)
+}
+
+/// Loans of a title still running on `date`.
+pub fn active_loans(loans: &[DigitalLoan], title_id: u32, date: NaiveDate) -> usize {
+    loans
+        .iter()
+        .filter(|l| l.title_id == title_id && l.is_active_on(date))
+        .count()
+}
+
+/// Index of a license of `title` that can take another checkout on `date`.
+fn free_license(title: &DigitalTitle, loans: &[DigitalLoan], date: NaiveDate) -> Option<usize> {
+    title.licenses.iter().position(|license| {
+        let in_use = loans
+            .iter()
+            .filter(|l| l.license_id == license.id && l.is_active_on(date))
+            .count();
+        license.is_valid_on(date) && in_use < license.concurrent_loans as usize
+    })
+}
+
+pub fn is_available(collection: &DigitalCollection, title_id: u32, date: NaiveDate) -> bool {
+    collection
+        .titles
+        .iter()
+        .find(|t| t.id == title_id)
+        .is_some_and(|title| free_license(title, &collection.loans, date).is_some())
+}
+
+/// Waiting patrons for a title, first come, first served.
+pub fn get_waitlist(waitlist: &[DigitalHold], title_id: u32) -> Vec<DigitalHold> {
+    let mut queue: Vec<DigitalHold> = waitlist
+        .iter()
+        .filter(|h| h.title_id == title_id && h.is_waiting())
+        .cloned()
+        .collect();
+    queue.sort_by_key(|h| (h.placed_on, h.id));
+    queue
+}
+
+/// Lends the title on a free license, failing with `NoLicenseAvailable`
+/// when every license is in use or spent. The loan never outlives its
+/// license.
+fn lend(
+    collection: &mut DigitalCollection,
+    ids: &mut IdAllocator,
+    title_id: u32,
+    user_id: u32,
+    date: NaiveDate,
+    period_days: u32,
+) -> Result<DigitalLoan, DigitalError> {
+    let title = collection
+        .titles
+        .iter_mut()
+        .find(|t| t.id == title_id)
+        .ok_or(DigitalError::TitleNotFound)?;
+    let index =
+        free_license(title, &collection.loans, date).ok_or(DigitalError::NoLicenseAvailable)?;
+    let license = &mut title.licenses[index];
+
+    let mut due_date = date
+        .checked_add_days(Days::new(period_days.into()))
+        .ok_or(DigitalError::NoLicenseAvailable)?;
+    if let Some(expiry) = license.expires_on {
+        due_date = due_date.min(expiry);
+    }
+    let id = ids.allocate()?;
+    license.loans_used += 1;
+
+    let loan = DigitalLoan {
+        id,
+        title_id,
+        license_id: license.id,
+        user_id,
+        loan_date: date,
+        due_date,
+        ended_on: None,
+    };
+    collection.loans.push(loan.clone());
+    Ok(loan)
+}
+
+/// Lends the title to `user_id`. `users` are the patrons allowed to borrow
+/// on `date`; anyone else waiting for the title does not hold up the queue.
+pub fn checkout(
+    collection: &mut DigitalCollection,
+    users: &[User],
+    ids: &mut IdAllocator,
+    title_id: u32,
+    user_id: u32,
+    date: NaiveDate,
+    period_days: u32,
+) -> Result<DigitalLoan, DigitalError> {
+    check_request(collection, users, title_id, user_id, date)?;
+
+    // Patrons on the waitlist get freed licenses before anyone else.
+    if get_waitlist(&collection.waitlist, title_id)
+        .iter()
+        .any(|h| users.iter().any(|u| u.id == h.user_id))
+    {
+        return Err(DigitalError::NoLicenseAvailable);
+    }
+
+    lend(collection, ids, title_id, user_id, date, period_days)
+}
+
+pub fn place_hold(
+    collection: &mut DigitalCollection,
+    users: &[User],
+    ids: &mut IdAllocator,
+    title_id: u32,
+    user_id: u32,
+    date: NaiveDate,
+) -> Result<u32, DigitalError> {
+    check_request(collection, users, title_id, user_id, date)?;
+
+    let queue = get_waitlist(&collection.waitlist, title_id);
+    if queue.iter().any(|h| h.user_id == user_id) {
+        return Err(DigitalError::AlreadyWaiting);
+    }
+    if queue.is_empty() && is_available(collection, title_id, date) {
+        return Err(DigitalError::LicenseAvailable);
+    }
+
+    let id = ids.allocate()?;
+    collection.waitlist.push(DigitalHold {
+        id,
+        title_id,
+        user_id,
+        placed_on: date,
+        loan_id: None,
+        cancelled: false,
+    });
+    Ok(id)
+}
+
+pub fn cancel_hold(waitlist: &mut [DigitalHold], hold_id: u32) -> Result<(), DigitalError> {
+    let hold = waitlist
+        .iter_mut()
+        .find(|h| h.id == hold_id && h.is_waiting())
+        .ok_or(DigitalError::HoldNotFound)?;
+    hold.cancelled = true;
+    Ok(())
+}
+
+/// Ends a loan before its due date.
+pub fn return_early(
+    loans: &mut [DigitalLoan],
+    loan_id: u32,
+    date: NaiveDate,
+) -> Result<DigitalLoan, DigitalError> {
+    let loan = loans
+        .iter_mut()
+        .find(|l| l.id == loan_id && l.is_active_on(date))
+        .ok_or(DigitalError::LoanNotFound)?;
+    loan.ended_on = Some(date);
+    Ok(loan.clone())
+}
+
+/// Closes every loan whose due date has come. Returns the closed loans.
+pub fn expire_loans(loans: &mut [DigitalLoan], date: NaiveDate) -> Vec<DigitalLoan> {
+    loans
+        .iter_mut()
+        .filter(|l| l.ended_on.is_none() && l.due_date <= date)
+        .map(|loan| {
+            loan.ended_on = Some(loan.due_date);
+            loan.clone()
+        })
+        .collect()
+}
+
+/// Gives free licenses to waiting patrons in queue order. Patrons missing
+/// from `users`, the ones allowed to borrow on `date`, keep their place but
+/// are passed over. Returns the new loans.
+pub fn assign_waitlist(
+    collection: &mut DigitalCollection,
+    users: &[User],
+    ids: &mut IdAllocator,
+    date: NaiveDate,
+    period_days: u32,
+) -> Vec<DigitalLoan> {
+    let mut waiting: Vec<DigitalHold> = collection
+        .waitlist
+        .iter()
+        .filter(|h| h.is_waiting() && users.iter().any(|u| u.id == h.user_id))
+        .cloned()
+        .collect();
+    waiting.sort_by_key(|h| (h.placed_on, h.id));
+
+    let mut assigned = Vec::new();
+    for hold in waiting {
+        let Ok(loan) = lend(
+            collection,
+            ids,
+            hold.title_id,
+            hold.user_id,
+            date,
+            period_days,
+        ) else {
+            continue;
+        };
+        if let Some(entry) = collection.waitlist.iter_mut().find(|h| h.id == hold.id) {
+            entry.loan_id = Some(loan.id);
+        }
+        assigned.push(loan);
+    }
+    assigned
+}
+
+pub fn get_user_loans(loans: &[DigitalLoan], user_id: u32, date: NaiveDate) -> Vec<DigitalLoan> {
+    loans
+        .iter()
+        .filter(|l| l.user_id == user_id && l.is_active_on(date))
+        .cloned()
+        .collect()
+}
+
+fn check_request(
+    collection: &DigitalCollection,
+    users: &[User],
+    title_id: u32,
+    user_id: u32,
+    date: NaiveDate,
+) -> Result<(), DigitalError> {
+    if !collection.titles.iter().any(|t| t.id == title_id) {
+        return Err(DigitalError::TitleNotFound);
+    }
+    if !users.iter().any(|u| u.id == user_id) {
+        return Err(DigitalError::UserNotFound);
+    }
+    if collection
+        .loans
+        .iter()
+        .any(|l| l.title_id == title_id && l.user_id == user_id && l.is_active_on(date))
+    {
+        return Err(DigitalError::AlreadyBorrowed);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).expect("Data inválida")
+    }
+
+    fn users() -> Vec<User> {
+        vec![
+            User::new(1, "Alice".to_string()),
+            User::new(2, "Bob".to_string()),
+            User::new(3, "Carol".to_string()),
+        ]
+    }
+
+    fn collection(license: License) -> DigitalCollection {
+        DigitalCollection {
+            titles: vec![DigitalTitle {
+                licenses: vec![license],
+                ..DigitalTitle::new(1, "E-book".to_string(), "Autor".to_string())
+            }],
+            ..DigitalCollection::default()
+        }
+    }
+
+    #[test]
+    fn test_one_copy_one_user_enforces_single_loan() {
+        let mut collection = collection(License::one_copy_one_user(1));
+        let users = users();
+        let mut ids = IdAllocator::default();
+
+        let loan = checkout(
+            &mut collection,
+            &users,
+            &mut ids,
+            1,
+            1,
+            date(2024, 3, 1),
+            14,
+        )
+        .expect("Falha ao emprestar e-book");
+        assert_eq!(loan.due_date, date(2024, 3, 15));
+        assert!(matches!(
+            checkout(
+                &mut collection,
+                &users,
+                &mut ids,
+                1,
+                2,
+                date(2024, 3, 2),
+                14
+            ),
+            Err(DigitalError::NoLicenseAvailable)
+        ));
+        assert!(matches!(
+            checkout(
+                &mut collection,
+                &users,
+                &mut ids,
+                1,
+                1,
+                date(2024, 3, 2),
+                14
+            ),
+            Err(DigitalError::AlreadyBorrowed)
+        ));
+
+        // The loan ends by itself on its due date.
+        assert!(checkout(
+            &mut collection,
+            &users,
+            &mut ids,
+            1,
+            2,
+            date(2024, 3, 15),
+            14
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_metered_license_runs_out_after_max_loans() {
+        let mut collection = collection(License::metered(1, 2, Some(3), None));
+        let users = users();
+        let mut ids = IdAllocator::default();
+
+        for user_id in [1, 2] {
+            assert!(checkout(
+                &mut collection,
+                &users,
+                &mut ids,
+                1,
+                user_id,
+                date(2024, 3, 1),
+                7
+            )
+            .is_ok());
+        }
+        assert!(matches!(
+            checkout(&mut collection, &users, &mut ids, 1, 3, date(2024, 3, 1), 7),
+            Err(DigitalError::NoLicenseAvailable)
+        ));
+
+        assert!(checkout(&mut collection, &users, &mut ids, 1, 3, date(2024, 3, 8), 7).is_ok());
+        assert!(matches!(
+            checkout(&mut collection, &users, &mut ids, 1, 1, date(2024, 3, 8), 7),
+            Err(DigitalError::NoLicenseAvailable)
+        ));
+        assert_eq!(collection.titles[0].licenses[0].loans_used, 3);
+    }
+
+    #[test]
+    fn test_loans_never_outlive_license_expiry() {
+        let mut collection = collection(License::metered(1, 5, None, Some(date(2024, 3, 10))));
+        let users = users();
+        let mut ids = IdAllocator::default();
+
+        let loan = checkout(
+            &mut collection,
+            &users,
+            &mut ids,
+            1,
+            1,
+            date(2024, 3, 1),
+            14,
+        )
+        .expect("Falha ao emprestar e-book");
+        assert_eq!(loan.due_date, date(2024, 3, 10));
+        assert!(matches!(
+            checkout(
+                &mut collection,
+                &users,
+                &mut ids,
+                1,
+                2,
+                date(2024, 3, 10),
+                14
+            ),
+            Err(DigitalError::NoLicenseAvailable)
+        ));
+    }
+
+    #[test]
+    fn test_waitlist_is_assigned_when_license_frees_up() {
+        let mut collection = collection(License::one_copy_one_user(1));
+        let users = users();
+        let mut loan_ids = IdAllocator::default();
+        let mut hold_ids = IdAllocator::default();
+
+        assert!(matches!(
+            place_hold(
+                &mut collection,
+                &users,
+                &mut hold_ids,
+                1,
+                2,
+                date(2024, 3, 1)
+            ),
+            Err(DigitalError::LicenseAvailable)
+        ));
+        let loan = checkout(
+            &mut collection,
+            &users,
+            &mut loan_ids,
+            1,
+            1,
+            date(2024, 3, 1),
+            14,
+        )
+        .expect("Falha ao emprestar e-book");
+        place_hold(
+            &mut collection,
+            &users,
+            &mut hold_ids,
+            1,
+            2,
+            date(2024, 3, 2),
+        )
+        .expect("Falha ao entrar na fila");
+        place_hold(
+            &mut collection,
+            &users,
+            &mut hold_ids,
+            1,
+            3,
+            date(2024, 3, 3),
+        )
+        .expect("Falha ao entrar na fila");
+
+        return_early(&mut collection.loans, loan.id, date(2024, 3, 5))
+            .expect("Falha ao devolver e-book");
+        let assigned =
+            assign_waitlist(&mut collection, &users, &mut loan_ids, date(2024, 3, 5), 14);
+        assert_eq!(assigned.len(), 1);
+        assert_eq!(assigned[0].user_id, 2);
+        assert_eq!(get_waitlist(&collection.waitlist, 1).len(), 1);
+
+        let expired = expire_loans(&mut collection.loans, date(2024, 3, 19));
+        assert_eq!(expired.len(), 1);
+        let assigned = assign_waitlist(
+            &mut collection,
+            &users,
+            &mut loan_ids,
+            date(2024, 3, 19),
+            14,
+        );
+        assert_eq!(assigned[0].user_id, 3);
+        assert!(get_waitlist(&collection.waitlist, 1).is_empty());
+    }
+
+    #[test]
+    fn test_save_and_read_collection() {
+        let temp_file = NamedTempFile::new().expect("Não foi possível criar arquivo temporário");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let collection = collection(License::metered(1, 3, Some(26), None));
+        assert!(save_to_json(file_path, &collection).is_ok());
+
+        let loaded = read_from_json(file_path).expect("Falha ao ler acervo digital");
+        assert_eq!(loaded.titles[0].licenses, collection.titles[0].licenses);
+    }
+
+    #[test]
+    fn test_read_from_malformed_json() {
+        let mut temp_file =
+            NamedTempFile::new().expect("Não foi possível criar arquivo temporário");
+        writeln!(temp_file, "isto não é um JSON válido")
+            .expect("Falha ao escrever no arquivo temporário");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let result = read_from_json(file_path);
+        assert!(matches!(result, Err(DigitalError::JsonError(_))));
+    }
+}
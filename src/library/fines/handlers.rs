@@ -0,0 +1,76 @@
+use super::service;
+use crate::library::fines::models::{
+    ChargeReason, FineError, FineRules, LedgerEntry, StatementLine,
+};
+use crate::library::ids::models::IdAllocator;
+
+pub(crate) fn save_ledger(ledger: &[LedgerEntry]) -> Result<(), FineError> {
+    service::save_to_json("ledger.json", ledger)
+}
+
+pub(crate) fn read_ledger() -> Result<Vec<LedgerEntry>, FineError> {
+    service::read_from_json("ledger.json")
+}
+
+pub(crate) fn overdue_fine(days_overdue: i64, daily_fine_cents: u32, rules: &FineRules) -> i64 {
+    service::overdue_fine(days_overdue, daily_fine_cents, rules)
+}
+
+pub(crate) fn replacement_cost(
+    price_cents: Option<i64>,
+    reason: ChargeReason,
+    rules: &FineRules,
+) -> i64 {
+    service::replacement_cost(price_cents, reason, rules)
+}
+
+pub(crate) fn charged_for_loan(ledger: &[LedgerEntry], loan_id: u32, reason: ChargeReason) -> i64 {
+    service::charged_for_loan(ledger, loan_id, reason)
+}
+
+pub(crate) fn waived_for_loan(ledger: &[LedgerEntry], loan_id: u32) -> i64 {
+    service::waived_for_loan(ledger, loan_id)
+}
+
+pub(crate) fn balance(ledger: &[LedgerEntry], user_id: u32) -> i64 {
+    service::balance(ledger, user_id)
+}
+
+pub(crate) fn post_entry(
+    ledger: &mut Vec<LedgerEntry>,
+    ids: &mut IdAllocator,
+    entry: LedgerEntry,
+) -> Result<u32, FineError> {
+    service::post_entry(ledger, ids, entry)
+}
+
+pub(crate) fn statement(ledger: &[LedgerEntry], user_id: u32) -> Vec<StatementLine> {
+    service::statement(ledger, user_id)
+}
+
+pub(crate) fn format_amount(cents: i64) -> String {
+    service::format_amount(cents)
+}
+
+pub(crate) fn print_statement(lines: &[StatementLine]) {
+    for line in lines {
+        let entry = &line.entry;
+        let sign = if entry.signed_amount() < 0 { "-" } else { "+" };
+        print!(
+            "{} #{} {:<14} {}{:>10} Balance: {:>10}",
+            entry.date,
+            entry.id,
+            entry.kind.to_string(),
+            sign,
+            service::format_amount(entry.amount_cents),
+            service::format_amount(line.balance_cents)
+        );
+        if let Some(loan_id) = entry.loan_id {
+            print!(" (Loan {})", loan_id);
+        }
+        if !entry.note.is_empty() {
+            print!(" - {}", entry.note);
+        }
+        println!();
+    }
+}
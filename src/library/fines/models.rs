@@ -0,0 +1,215 @@
+use crate::library::ids::models::IdError;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChargeReason {
+    Overdue,
+    LostItem,
+    DamagedItem,
+}
+
+impl fmt::Display for ChargeReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChargeReason::Overdue => write!(f, "Overdue fine"),
+            ChargeReason::LostItem => write!(f, "Lost item"),
+            ChargeReason::DamagedItem => write!(f, "Damaged item"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EntryKind {
+    Charge(ChargeReason),
+    Payment,
+    Waiver,
+    /// Money owed back to the patron, e.g. a refunded replacement charge.
+    /// Unlike payments and waivers it may take the balance below zero.
+    Credit,
+}
+
+impl fmt::Display for EntryKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EntryKind::Charge(reason) => write!(f, "{}", reason),
+            EntryKind::Payment => write!(f, "Payment"),
+            EntryKind::Waiver => write!(f, "Waiver"),
+            EntryKind::Credit => write!(f, "Credit"),
+        }
+    }
+}
+
+/// One line of a patron's account. `amount_cents` is always positive; the
+/// kind decides whether it raises or lowers the balance.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    pub id: u32,
+    pub user_id: u32,
+    pub date: NaiveDate,
+    pub kind: EntryKind,
+    pub amount_cents: i64,
+    #[serde(default)]
+    pub loan_id: Option<u32>,
+    #[serde(default)]
+    pub note: String,
+}
+
+impl LedgerEntry {
+    pub fn charge(
+        user_id: u32,
+        date: NaiveDate,
+        reason: ChargeReason,
+        amount_cents: i64,
+        loan_id: Option<u32>,
+    ) -> Self {
+        Self {
+            id: 0,
+            user_id,
+            date,
+            kind: EntryKind::Charge(reason),
+            amount_cents,
+            loan_id,
+            note: String::new(),
+        }
+    }
+
+    pub fn payment(user_id: u32, date: NaiveDate, amount_cents: i64) -> Self {
+        Self {
+            id: 0,
+            user_id,
+            date,
+            kind: EntryKind::Payment,
+            amount_cents,
+            loan_id: None,
+            note: String::new(),
+        }
+    }
+
+    /// A waiver naming `loan_id` forgives charges on that loan.
+    pub fn waiver(
+        user_id: u32,
+        date: NaiveDate,
+        amount_cents: i64,
+        loan_id: Option<u32>,
+        note: String,
+    ) -> Self {
+        Self {
+            id: 0,
+            user_id,
+            date,
+            kind: EntryKind::Waiver,
+            amount_cents,
+            loan_id,
+            note,
+        }
+    }
+
+    pub fn credit(
+        user_id: u32,
+        date: NaiveDate,
+        amount_cents: i64,
+        loan_id: Option<u32>,
+        note: String,
+    ) -> Self {
+        Self {
+            id: 0,
+            user_id,
+            date,
+            kind: EntryKind::Credit,
+            amount_cents,
+            loan_id,
+            note,
+        }
+    }
+
+    /// The entry's effect on the balance: charges add, everything else subtracts.
+    pub fn signed_amount(&self) -> i64 {
+        match self.kind {
+            EntryKind::Charge(_) => self.amount_cents,
+            EntryKind::Payment | EntryKind::Waiver | EntryKind::Credit => -self.amount_cents,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StatementLine {
+    pub entry: LedgerEntry,
+    pub balance_cents: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FineRules {
+    pub grace_period_days: u32,
+    pub max_fine_per_item_cents: i64,
+    /// Billed for a lost item that has no price on record.
+    pub lost_item_fee_cents: i64,
+    /// Billed for a damaged item that has no price on record.
+    pub damaged_item_fee_cents: i64,
+    /// Checkouts are refused while the balance is above this amount.
+    pub block_threshold_cents: i64,
+}
+
+impl Default for FineRules {
+    fn default() -> Self {
+        Self {
+            grace_period_days: 2,
+            max_fine_per_item_cents: 2000,
+            lost_item_fee_cents: 5000,
+            damaged_item_fee_cents: 1500,
+            block_threshold_cents: 1000,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum FineError {
+    IoError(io::Error),
+    JsonError(serde_json::Error),
+    InvalidAmount,
+    ExceedsBalance { balance_cents: i64 },
+    UserNotFound,
+    LoanNotFound,
+    IdError(IdError),
+}
+
+impl fmt::Display for FineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FineError::IoError(err) => write!(f, "IO Error: {}", err),
+            FineError::JsonError(err) => write!(f, "JSON Error: {}", err),
+            FineError::IdError(err) => write!(f, "{}", err),
+            FineError::InvalidAmount => write!(f, "Amount must be greater than zero"),
+            FineError::ExceedsBalance { balance_cents } => write!(
+                f,
+                "Amount exceeds the outstanding balance of {}",
+                super::service::format_amount(*balance_cents)
+            ),
+            FineError::UserNotFound => write!(f, "User not found"),
+            FineError::LoanNotFound => write!(f, "Loan not found"),
+        }
+    }
+}
+
+impl std::error::Error for FineError {}
+
+impl From<io::Error> for FineError {
+    fn from(err: io::Error) -> Self {
+        FineError::IoError(err)
+    }
+}
+
+impl From<serde_json::Error> for FineError {
+    fn from(err: serde_json::Error) -> Self {
+        FineError::JsonError(err)
+    }
+}
+
+impl From<IdError> for FineError {
+    fn from(err: IdError) -> Self {
+        FineError::IdError(err)
+    }
+}
@@ -0,0 +1,317 @@
+use super::models::{ChargeReason, EntryKind, FineError, FineRules, LedgerEntry, StatementLine};
+use crate::library::ids::models::IdAllocator;
+use std::fs::File;
+use std::io::ErrorKind;
+
+pub fn read_from_json(file_path: &str) -> Result<Vec<LedgerEntry>, FineError> {
+    let file = match File::open(file_path) {
+        Ok(file) => file,
+        Err(err) => {
+            if err.kind() == ErrorKind::NotFound {
+                return Ok(Vec::new());
+            } else {
+                return Err(FineError::IoError(err));
+            }
+        }
+    };
+
+    let ledger: Vec<LedgerEntry> = serde_json::from_reader(file).map_err(FineError::JsonError)?;
+
+    Ok(ledger)
+}
+
+pub fn save_to_json(file_path: &str, ledger: &[LedgerEntry]) -> Result<(), FineError> {
+    let file = File::create(file_path)?;
+    serde_json::to_writer_pretty(file, ledger)?;
+    Ok(())
+}
+
+pub fn format_amount(cents: i64) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    format!("{}{}.{:02}", sign, cents.abs() / 100, cents.abs() % 100)
+}
+
+/// Fine for an item returned `days_overdue` days late. Days inside the grace
+/// period are free, and the total is capped per item.
+pub fn overdue_fine(days_overdue: i64, daily_fine_cents: u32, rules: &FineRules) -> i64 {
+    let chargeable_days = days_overdue - i64::from(rules.grace_period_days);
+    if chargeable_days <= 0 {
+        return 0;
+    }
+
+    (chargeable_days * i64::from(daily_fine_cents)).min(rules.max_fine_per_item_cents)
+}
+
+/// Replacement cost for a lost or damaged item: its price when known,
+/// otherwise the flat fee from the rules.
+pub fn replacement_cost(price_cents: Option<i64>, reason: ChargeReason, rules: &FineRules) -> i64 {
+    let flat_fee = match reason {
+        ChargeReason::LostItem => rules.lost_item_fee_cents,
+        ChargeReason::DamagedItem => rules.damaged_item_fee_cents,
+        ChargeReason::Overdue => 0,
+    };
+    price_cents.filter(|&p| p > 0).unwrap_or(flat_fee)
+}
+
+/// Total charged against `loan_id` for `reason`.
+pub fn charged_for_loan(ledger: &[LedgerEntry], loan_id: u32, reason: ChargeReason) -> i64 {
+    ledger
+        .iter()
+        .filter(|e| e.loan_id == Some(loan_id) && e.kind == EntryKind::Charge(reason))
+        .map(|e| e.amount_cents)
+        .sum()
+}
+
+/// Total waived against `loan_id`.
+pub fn waived_for_loan(ledger: &[LedgerEntry], loan_id: u32) -> i64 {
+    ledger
+        .iter()
+        .filter(|e| e.loan_id == Some(loan_id) && e.kind == EntryKind::Waiver)
+        .map(|e| e.amount_cents)
+        .sum()
+}
+
+pub fn balance(ledger: &[LedgerEntry], user_id: u32) -> i64 {
+    ledger
+        .iter()
+        .filter(|e| e.user_id == user_id)
+        .map(LedgerEntry::signed_amount)
+        .sum()
+}
+
+/// Adds `entry` to the ledger and returns its id. Payments and waivers may
+/// not take the balance below zero.
+pub fn post_entry(
+    ledger: &mut Vec<LedgerEntry>,
+    ids: &mut IdAllocator,
+    mut entry: LedgerEntry,
+) -> Result<u32, FineError> {
+    if entry.amount_cents <= 0 {
+        return Err(FineError::InvalidAmount);
+    }
+
+    if matches!(entry.kind, EntryKind::Payment | EntryKind::Waiver) {
+        let balance_cents = balance(ledger, entry.user_id);
+        if entry.amount_cents > balance_cents {
+            return Err(FineError::ExceedsBalance { balance_cents });
+        }
+    }
+
+    entry.id = ids.allocate()?;
+    let id = entry.id;
+    ledger.push(entry);
+    Ok(id)
+}
+
+/// The patron's entries in posting order, each with the balance after it.
+pub fn statement(ledger: &[LedgerEntry], user_id: u32) -> Vec<StatementLine> {
+    let mut balance_cents = 0;
+
+    ledger
+        .iter()
+        .filter(|e| e.user_id == user_id)
+        .map(|e| {
+            balance_cents += e.signed_amount();
+            StatementLine {
+                entry: e.clone(),
+                balance_cents,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::fines::models::ChargeReason;
+    use chrono::NaiveDate;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).expect("Data inválida")
+    }
+
+    #[test]
+    fn test_overdue_fine_grace_period_and_cap() {
+        let rules = FineRules {
+            grace_period_days: 2,
+            max_fine_per_item_cents: 300,
+            ..FineRules::default()
+        };
+        assert_eq!(overdue_fine(0, 50, &rules), 0);
+        assert_eq!(overdue_fine(2, 50, &rules), 0);
+        assert_eq!(overdue_fine(5, 50, &rules), 150);
+        assert_eq!(overdue_fine(30, 50, &rules), 300);
+    }
+
+    #[test]
+    fn test_post_entries_and_balance() {
+        let mut ledger = Vec::new();
+        let mut ids = IdAllocator::default();
+
+        let charge = LedgerEntry::charge(1, date(2024, 1, 10), ChargeReason::Overdue, 500, Some(3));
+        assert_eq!(post_entry(&mut ledger, &mut ids, charge).unwrap(), 1);
+        post_entry(
+            &mut ledger,
+            &mut ids,
+            LedgerEntry::payment(1, date(2024, 1, 11), 200),
+        )
+        .expect("Falha ao registrar pagamento");
+        post_entry(
+            &mut ledger,
+            &mut ids,
+            LedgerEntry::charge(2, date(2024, 1, 11), ChargeReason::LostItem, 4000, None),
+        )
+        .expect("Falha ao registrar cobrança");
+
+        assert_eq!(balance(&ledger, 1), 300);
+        assert_eq!(balance(&ledger, 2), 4000);
+        assert_eq!(balance(&ledger, 3), 0);
+    }
+
+    #[test]
+    fn test_payment_cannot_exceed_balance() {
+        let mut ledger = Vec::new();
+        let mut ids = IdAllocator::default();
+        post_entry(
+            &mut ledger,
+            &mut ids,
+            LedgerEntry::charge(1, date(2024, 1, 10), ChargeReason::Overdue, 100, None),
+        )
+        .unwrap();
+
+        let result = post_entry(
+            &mut ledger,
+            &mut ids,
+            LedgerEntry::payment(1, date(2024, 1, 11), 150),
+        );
+        assert!(matches!(
+            result,
+            Err(FineError::ExceedsBalance { balance_cents: 100 })
+        ));
+        assert_eq!(ledger.len(), 1);
+    }
+
+    #[test]
+    fn test_post_entry_rejects_non_positive_amount() {
+        let mut ledger = Vec::new();
+        let result = post_entry(
+            &mut ledger,
+            &mut IdAllocator::default(),
+            LedgerEntry::charge(1, date(2024, 1, 10), ChargeReason::Overdue, 0, None),
+        );
+        assert!(matches!(result, Err(FineError::InvalidAmount)));
+    }
+
+    #[test]
+    fn test_statement_running_balance() {
+        let mut ledger = Vec::new();
+        let mut ids = IdAllocator::default();
+        let entries = vec![
+            LedgerEntry::charge(1, date(2024, 1, 10), ChargeReason::Overdue, 500, None),
+            LedgerEntry::charge(2, date(2024, 1, 10), ChargeReason::Overdue, 900, None),
+            LedgerEntry::waiver(
+                1,
+                date(2024, 1, 12),
+                100,
+                None,
+                "Primeiro atraso".to_string(),
+            ),
+            LedgerEntry::charge(1, date(2024, 1, 15), ChargeReason::DamagedItem, 1500, None),
+            LedgerEntry::payment(1, date(2024, 1, 20), 1000),
+        ];
+        for entry in entries {
+            post_entry(&mut ledger, &mut ids, entry).expect("Falha ao registrar lançamento");
+        }
+
+        let balances: Vec<i64> = statement(&ledger, 1)
+            .iter()
+            .map(|line| line.balance_cents)
+            .collect();
+        assert_eq!(balances, vec![500, 400, 1900, 900]);
+    }
+
+    #[test]
+    fn test_replacement_cost_prefers_price() {
+        let rules = FineRules::default();
+        assert_eq!(
+            replacement_cost(Some(3250), ChargeReason::LostItem, &rules),
+            3250
+        );
+        assert_eq!(
+            replacement_cost(None, ChargeReason::LostItem, &rules),
+            rules.lost_item_fee_cents
+        );
+        assert_eq!(
+            replacement_cost(None, ChargeReason::DamagedItem, &rules),
+            rules.damaged_item_fee_cents
+        );
+    }
+
+    #[test]
+    fn test_credit_can_take_balance_below_zero() {
+        let mut ledger = Vec::new();
+        let mut ids = IdAllocator::default();
+        let entries = vec![
+            LedgerEntry::charge(1, date(2024, 1, 10), ChargeReason::LostItem, 3000, Some(4)),
+            LedgerEntry::payment(1, date(2024, 1, 11), 3000),
+            LedgerEntry::credit(
+                1,
+                date(2024, 2, 1),
+                3000,
+                Some(4),
+                "Item encontrado".to_string(),
+            ),
+        ];
+        for entry in entries {
+            post_entry(&mut ledger, &mut ids, entry).expect("Falha ao registrar lançamento");
+        }
+
+        assert_eq!(charged_for_loan(&ledger, 4, ChargeReason::LostItem), 3000);
+        assert_eq!(charged_for_loan(&ledger, 4, ChargeReason::Overdue), 0);
+        assert_eq!(balance(&ledger, 1), -3000);
+    }
+
+    #[test]
+    fn test_format_amount() {
+        assert_eq!(format_amount(0), "0.00");
+        assert_eq!(format_amount(1505), "15.05");
+        assert_eq!(format_amount(-250), "-2.50");
+    }
+
+    #[test]
+    fn test_save_and_read_ledger() {
+        let temp_file = NamedTempFile::new().expect("Não foi possível criar arquivo temporário");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let ledger = vec![
+            LedgerEntry::charge(1, date(2024, 1, 10), ChargeReason::Overdue, 500, Some(1)),
+            LedgerEntry::payment(1, date(2024, 1, 11), 500),
+        ];
+        assert!(save_to_json(file_path, &ledger).is_ok());
+
+        let loaded = read_from_json(file_path).expect("Falha ao ler lançamentos");
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].kind, EntryKind::Charge(ChargeReason::Overdue));
+    }
+
+    #[test]
+    fn test_read_from_nonexistent_file() {
+        let result = read_from_json("arquivo_que_nao_existe.json");
+        assert!(result.is_ok());
+        assert!(result.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_read_from_malformed_json() {
+        let mut temp_file =
+            NamedTempFile::new().expect("Não foi possível criar arquivo temporário");
+        writeln!(temp_file, "isto não é um JSON válido")
+            .expect("Falha ao escrever no arquivo temporário");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let result = read_from_json(file_path);
+        assert!(matches!(result, Err(FineError::JsonError(_))));
+    }
+}
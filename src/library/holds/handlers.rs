@@ -0,0 +1,92 @@
+use super::service;
+use crate::library::books::models::Book;
+use crate::library::holds::models::{Hold, HoldError};
+use crate::library::ids::models::IdAllocator;
+use crate::library::users::models::User;
+use chrono::NaiveDate;
+
+pub(crate) fn save_holds(holds: &[Hold]) -> Result<(), HoldError> {
+    service::save_to_json("holds.json", holds)
+}
+
+pub(crate) fn read_holds() -> Result<Vec<Hold>, HoldError> {
+    service::read_from_json("holds.json")
+}
+
+pub(crate) fn place_hold(
+    holds: &mut Vec<Hold>,
+    users: &[User],
+    books: &[Book],
+    ids: &mut IdAllocator,
+    user_id: u32,
+    book_id: u32,
+    date: NaiveDate,
+) -> Result<u32, HoldError> {
+    service::place_hold(holds, users, books, ids, user_id, book_id, date)
+}
+
+pub(crate) fn get_queue(holds: &[Hold], book_id: u32) -> Vec<Hold> {
+    service::get_queue(holds, book_id)
+}
+
+pub(crate) fn queue_position(holds: &[Hold], hold_id: u32) -> Result<usize, HoldError> {
+    service::queue_position(holds, hold_id)
+}
+
+pub(crate) fn cancel_hold(
+    holds: &mut [Hold],
+    hold_id: u32,
+    date: NaiveDate,
+) -> Result<Hold, HoldError> {
+    service::cancel_hold(holds, hold_id, date)
+}
+
+pub(crate) fn assign_next(
+    holds: &mut [Hold],
+    book_id: u32,
+    date: NaiveDate,
+    pickup_window_days: u32,
+) -> Option<Hold> {
+    service::assign_next(holds, book_id, date, pickup_window_days)
+}
+
+pub(crate) fn ready_hold(holds: &[Hold], user_id: u32, book_id: u32) -> Option<Hold> {
+    service::ready_hold(holds, user_id, book_id)
+}
+
+pub(crate) fn fulfill_hold(
+    holds: &mut [Hold],
+    hold_id: u32,
+    date: NaiveDate,
+) -> Result<(), HoldError> {
+    service::fulfill_hold(holds, hold_id, date)
+}
+
+pub(crate) fn expire_holds(holds: &mut [Hold], date: NaiveDate) -> Vec<Hold> {
+    service::expire_holds(holds, date)
+}
+
+pub(crate) fn get_holds_by_user(holds: &[Hold], user_id: u32) -> Vec<Hold> {
+    service::get_holds_by_user(holds, user_id)
+}
+
+pub(crate) fn print_holds(holds: &[Hold], all_holds: &[Hold], books: &[Book]) {
+    for hold in holds {
+        let title = books
+            .iter()
+            .find(|b| b.id == hold.book_id)
+            .map(|b| b.title.as_str())
+            .unwrap_or("Unknown book");
+        println!("Hold ID: {}", hold.id);
+        println!("Book: {} (ID {})", title, hold.book_id);
+        println!("Placed On: {}", hold.placed_on);
+        println!("Status: {}", hold.status);
+        if let Ok(position) = service::queue_position(all_holds, hold.id) {
+            println!("Queue Position: {}", position);
+        }
+        if let Some(deadline) = hold.pickup_deadline {
+            println!("Pick Up By: {}", deadline);
+        }
+        println!();
+    }
+}
@@ -0,0 +1,146 @@
+use crate::library::books::models::{BookError, ItemStatus};
+use crate::library::ids::models::IdError;
+use crate::library::loans::models::LoanError;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HoldStatus {
+    Waiting,
+    ReadyForPickup,
+    Fulfilled,
+    Cancelled,
+    Expired,
+}
+
+impl fmt::Display for HoldStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HoldStatus::Waiting => write!(f, "Waiting"),
+            HoldStatus::ReadyForPickup => write!(f, "Ready for pickup"),
+            HoldStatus::Fulfilled => write!(f, "Fulfilled"),
+            HoldStatus::Cancelled => write!(f, "Cancelled"),
+            HoldStatus::Expired => write!(f, "Expired"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Hold {
+    pub id: u32,
+    pub user_id: u32,
+    pub book_id: u32,
+    pub placed_on: NaiveDate,
+    pub status: HoldStatus,
+    #[serde(default)]
+    pub pickup_deadline: Option<NaiveDate>,
+    #[serde(default)]
+    pub closed_on: Option<NaiveDate>,
+}
+
+impl Hold {
+    pub fn new(id: u32, user_id: u32, book_id: u32, placed_on: NaiveDate) -> Self {
+        Self {
+            id,
+            user_id,
+            book_id,
+            placed_on,
+            status: HoldStatus::Waiting,
+            pickup_deadline: None,
+            closed_on: None,
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        matches!(
+            self.status,
+            HoldStatus::Waiting | HoldStatus::ReadyForPickup
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HoldRules {
+    /// Days a returned item waits on the hold shelf before the hold expires.
+    pub pickup_window_days: u32,
+}
+
+impl Default for HoldRules {
+    fn default() -> Self {
+        Self {
+            pickup_window_days: 7,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum HoldError {
+    IoError(io::Error),
+    JsonError(serde_json::Error),
+    HoldNotFound,
+    HoldAlreadyExists,
+    UserNotFound,
+    BookNotFound,
+    BookAvailable,
+    /// The copy is withdrawn, lost or missing, so the hold could never be filled.
+    NotCirculating(ItemStatus),
+    HoldClosed(HoldStatus),
+    /// The hold was refused because the loan it should recall could not be recalled.
+    RecallFailed(LoanError),
+    BookError(BookError),
+    IdError(IdError),
+}
+
+impl fmt::Display for HoldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HoldError::IoError(err) => write!(f, "IO Error: {}", err),
+            HoldError::JsonError(err) => write!(f, "JSON Error: {}", err),
+            HoldError::IdError(err) => write!(f, "{}", err),
+            HoldError::BookError(err) => write!(f, "Book Error: {}", err),
+            HoldError::HoldNotFound => write!(f, "Hold not found"),
+            HoldError::HoldAlreadyExists => {
+                write!(f, "Patron already has a hold on this book")
+            }
+            HoldError::UserNotFound => write!(f, "User not found"),
+            HoldError::BookNotFound => write!(f, "Book not found"),
+            HoldError::BookAvailable => {
+                write!(f, "Book is available and can be borrowed right away")
+            }
+            HoldError::NotCirculating(status) => {
+                write!(f, "Book cannot be reserved while it is {}", status)
+            }
+            HoldError::HoldClosed(status) => write!(f, "Hold is already closed ({})", status),
+            HoldError::RecallFailed(err) => write!(f, "Recall failed: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for HoldError {}
+
+impl From<io::Error> for HoldError {
+    fn from(err: io::Error) -> Self {
+        HoldError::IoError(err)
+    }
+}
+
+impl From<serde_json::Error> for HoldError {
+    fn from(err: serde_json::Error) -> Self {
+        HoldError::JsonError(err)
+    }
+}
+
+impl From<IdError> for HoldError {
+    fn from(err: IdError) -> Self {
+        HoldError::IdError(err)
+    }
+}
+
+impl From<BookError> for HoldError {
+    fn from(err: BookError) -> Self {
+        HoldError::BookError(err)
+    }
+}
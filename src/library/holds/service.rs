@@ -0,0 +1,416 @@
+use super::models::{Hold, HoldError, HoldStatus};
+use crate::library::books::models::{Book, ItemStatus};
+use crate::library::ids::models::IdAllocator;
+use crate::library::users::models::User;
+use chrono::{Days, NaiveDate};
+use std::fs::File;
+use std::io::ErrorKind;
+
+pub fn read_from_json(file_path: &str) -> Result<Vec<Hold>, HoldError> {
+    let file = match File::open(file_path) {
+        Ok(file) => file,
+        Err(err) => {
+            if err.kind() == ErrorKind::NotFound {
+                return Ok(Vec::new());
+            } else {
+                return Err(HoldError::IoError(err));
+            }
+        }
+    };
+
+    let holds: Vec<Hold> = serde_json::from_reader(file).map_err(HoldError::JsonError)?;
+
+    Ok(holds)
+}
+
+pub fn save_to_json(file_path: &str, holds: &[Hold]) -> Result<(), HoldError> {
+    let file = File::create(file_path)?;
+    serde_json::to_writer_pretty(file, holds)?;
+    Ok(())
+}
+
+pub fn place_hold(
+    holds: &mut Vec<Hold>,
+    users: &[User],
+    books: &[Book],
+    ids: &mut IdAllocator,
+    user_id: u32,
+    book_id: u32,
+    date: NaiveDate,
+) -> Result<u32, HoldError> {
+    if !users.iter().any(|u| u.id == user_id) {
+        return Err(HoldError::UserNotFound);
+    }
+
+    let book = books
+        .iter()
+        .find(|b| b.id == book_id)
+        .ok_or(HoldError::BookNotFound)?;
+
+    if matches!(
+        book.status,
+        ItemStatus::Withdrawn | ItemStatus::Lost | ItemStatus::Missing
+    ) {
+        return Err(HoldError::NotCirculating(book.status));
+    }
+
+    if book.is_available() && get_queue(holds, book_id).is_empty() {
+        return Err(HoldError::BookAvailable);
+    }
+
+    if holds
+        .iter()
+        .any(|h| h.user_id == user_id && h.book_id == book_id && h.is_pending())
+    {
+        return Err(HoldError::HoldAlreadyExists);
+    }
+
+    let hold = Hold::new(ids.allocate()?, user_id, book_id, date);
+    let id = hold.id;
+    holds.push(hold);
+    Ok(id)
+}
+
+/// Waiting holds for a book, first come, first served.
+pub fn get_queue(holds: &[Hold], book_id: u32) -> Vec<Hold> {
+    let mut queue: Vec<Hold> = holds
+        .iter()
+        .filter(|h| h.book_id == book_id && h.status == HoldStatus::Waiting)
+        .cloned()
+        .collect();
+    queue.sort_by_key(|h| (h.placed_on, h.id));
+    queue
+}
+
+/// 1-based position of a waiting hold in its book's queue.
+pub fn queue_position(holds: &[Hold], hold_id: u32) -> Result<usize, HoldError> {
+    let hold = holds
+        .iter()
+        .find(|h| h.id == hold_id)
+        .ok_or(HoldError::HoldNotFound)?;
+
+    if hold.status != HoldStatus::Waiting {
+        return Err(HoldError::HoldClosed(hold.status));
+    }
+
+    Ok(get_queue(holds, hold.book_id)
+        .iter()
+        .position(|h| h.id == hold_id)
+        .map(|i| i + 1)
+        .unwrap_or_default())
+}
+
+pub fn cancel_hold(holds: &mut [Hold], hold_id: u32, date: NaiveDate) -> Result<Hold, HoldError> {
+    let hold = holds
+        .iter_mut()
+        .find(|h| h.id == hold_id)
+        .ok_or(HoldError::HoldNotFound)?;
+
+    if !hold.is_pending() {
+        return Err(HoldError::HoldClosed(hold.status));
+    }
+
+    let cancelled = hold.clone();
+    hold.status = HoldStatus::Cancelled;
+    hold.closed_on = Some(date);
+    Ok(cancelled)
+}
+
+/// Puts the item aside for the next patron in the queue, if any.
+pub fn assign_next(
+    holds: &mut [Hold],
+    book_id: u32,
+    date: NaiveDate,
+    pickup_window_days: u32,
+) -> Option<Hold> {
+    let next_id = get_queue(holds, book_id).first()?.id;
+    let hold = holds.iter_mut().find(|h| h.id == next_id)?;
+
+    hold.status = HoldStatus::ReadyForPickup;
+    hold.pickup_deadline = date.checked_add_days(Days::new(pickup_window_days.into()));
+    Some(hold.clone())
+}
+
+/// The hold waiting on the shelf for this patron and book, if there is one.
+pub fn ready_hold(holds: &[Hold], user_id: u32, book_id: u32) -> Option<Hold> {
+    holds
+        .iter()
+        .find(|h| {
+            h.user_id == user_id && h.book_id == book_id && h.status == HoldStatus::ReadyForPickup
+        })
+        .cloned()
+}
+
+pub fn fulfill_hold(holds: &mut [Hold], hold_id: u32, date: NaiveDate) -> Result<(), HoldError> {
+    let hold = holds
+        .iter_mut()
+        .find(|h| h.id == hold_id)
+        .ok_or(HoldError::HoldNotFound)?;
+
+    hold.status = HoldStatus::Fulfilled;
+    hold.closed_on = Some(date);
+    Ok(())
+}
+
+/// Expires holds whose pickup deadline passed before `date` and returns them.
+pub fn expire_holds(holds: &mut [Hold], date: NaiveDate) -> Vec<Hold> {
+    let mut expired = Vec::new();
+
+    for hold in holds.iter_mut().filter(|h| {
+        h.status == HoldStatus::ReadyForPickup && h.pickup_deadline.is_some_and(|d| d < date)
+    }) {
+        hold.status = HoldStatus::Expired;
+        hold.closed_on = Some(date);
+        expired.push(hold.clone());
+    }
+
+    expired
+}
+
+pub fn get_holds_by_user(holds: &[Hold], user_id: u32) -> Vec<Hold> {
+    holds
+        .iter()
+        .filter(|h| h.user_id == user_id && h.is_pending())
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).expect("Data inválida")
+    }
+
+    fn borrowed_book() -> Book {
+        Book {
+            status: ItemStatus::OnLoan,
+            ..Book::new(1, "Rust Book".to_string(), "Steve".to_string(), 300)
+        }
+    }
+
+    fn users() -> Vec<User> {
+        vec![
+            User::new(1, "Alice".to_string()),
+            User::new(2, "Bob".to_string()),
+            User::new(3, "Carol".to_string()),
+        ]
+    }
+
+    #[test]
+    fn test_place_hold_queues_in_order() {
+        let mut holds = Vec::new();
+        let books = vec![borrowed_book()];
+        let mut ids = IdAllocator::default();
+
+        for user_id in [2, 1, 3] {
+            place_hold(
+                &mut holds,
+                &users(),
+                &books,
+                &mut ids,
+                user_id,
+                1,
+                date(2024, 3, 1),
+            )
+            .expect("Falha ao reservar");
+        }
+
+        let queue: Vec<u32> = get_queue(&holds, 1).iter().map(|h| h.user_id).collect();
+        assert_eq!(queue, vec![2, 1, 3]);
+        assert_eq!(queue_position(&holds, 3).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_place_hold_duplicate() {
+        let mut holds = Vec::new();
+        let books = vec![borrowed_book()];
+        let mut ids = IdAllocator::default();
+
+        assert!(place_hold(
+            &mut holds,
+            &users(),
+            &books,
+            &mut ids,
+            1,
+            1,
+            date(2024, 3, 1)
+        )
+        .is_ok());
+        let result = place_hold(
+            &mut holds,
+            &users(),
+            &books,
+            &mut ids,
+            1,
+            1,
+            date(2024, 3, 2),
+        );
+        assert!(matches!(result, Err(HoldError::HoldAlreadyExists)));
+    }
+
+    #[test]
+    fn test_place_hold_on_available_book() {
+        let mut holds = Vec::new();
+        let books = vec![Book::new(
+            1,
+            "Rust Book".to_string(),
+            "Steve".to_string(),
+            300,
+        )];
+        let result = place_hold(
+            &mut holds,
+            &users(),
+            &books,
+            &mut IdAllocator::default(),
+            1,
+            1,
+            date(2024, 3, 1),
+        );
+        assert!(matches!(result, Err(HoldError::BookAvailable)));
+    }
+
+    #[test]
+    fn test_place_hold_unknown_user_or_book() {
+        let mut holds = Vec::new();
+        let books = vec![borrowed_book()];
+        let mut ids = IdAllocator::default();
+
+        let result = place_hold(
+            &mut holds,
+            &users(),
+            &books,
+            &mut ids,
+            9,
+            1,
+            date(2024, 3, 1),
+        );
+        assert!(matches!(result, Err(HoldError::UserNotFound)));
+        let result = place_hold(
+            &mut holds,
+            &users(),
+            &books,
+            &mut ids,
+            1,
+            9,
+            date(2024, 3, 1),
+        );
+        assert!(matches!(result, Err(HoldError::BookNotFound)));
+    }
+
+    #[test]
+    fn test_place_hold_on_non_circulating_book() {
+        let mut holds = Vec::new();
+        for status in [ItemStatus::Withdrawn, ItemStatus::Lost, ItemStatus::Missing] {
+            let books = vec![Book {
+                status,
+                ..borrowed_book()
+            }];
+            let result = place_hold(
+                &mut holds,
+                &users(),
+                &books,
+                &mut IdAllocator::default(),
+                1,
+                1,
+                date(2024, 3, 1),
+            );
+            assert!(matches!(result, Err(HoldError::NotCirculating(s)) if s == status));
+        }
+        assert!(holds.is_empty());
+    }
+
+    #[test]
+    fn test_assign_next_and_expire() {
+        let mut holds = vec![
+            Hold::new(1, 1, 1, date(2024, 3, 1)),
+            Hold::new(2, 2, 1, date(2024, 3, 2)),
+        ];
+
+        let ready = assign_next(&mut holds, 1, date(2024, 3, 10), 7).expect("Fila vazia");
+        assert_eq!(ready.user_id, 1);
+        assert_eq!(ready.pickup_deadline, Some(date(2024, 3, 17)));
+        assert_eq!(queue_position(&holds, 2).unwrap(), 1);
+
+        assert!(expire_holds(&mut holds, date(2024, 3, 17)).is_empty());
+        let expired = expire_holds(&mut holds, date(2024, 3, 18));
+        assert_eq!(expired.len(), 1);
+        assert_eq!(holds[0].status, HoldStatus::Expired);
+
+        let next = assign_next(&mut holds, 1, date(2024, 3, 18), 7).expect("Fila vazia");
+        assert_eq!(next.user_id, 2);
+        assert!(assign_next(&mut holds, 1, date(2024, 3, 18), 7).is_none());
+    }
+
+    #[test]
+    fn test_cancel_hold() {
+        let mut holds = vec![
+            Hold::new(1, 1, 1, date(2024, 3, 1)),
+            Hold::new(2, 2, 1, date(2024, 3, 2)),
+        ];
+
+        let cancelled = cancel_hold(&mut holds, 1, date(2024, 3, 5)).expect("Falha ao cancelar");
+        assert_eq!(cancelled.status, HoldStatus::Waiting);
+        assert_eq!(holds[0].status, HoldStatus::Cancelled);
+        assert_eq!(queue_position(&holds, 2).unwrap(), 1);
+
+        let result = cancel_hold(&mut holds, 1, date(2024, 3, 6));
+        assert!(matches!(
+            result,
+            Err(HoldError::HoldClosed(HoldStatus::Cancelled))
+        ));
+        assert!(matches!(
+            cancel_hold(&mut holds, 9, date(2024, 3, 6)),
+            Err(HoldError::HoldNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_ready_hold_and_fulfill() {
+        let mut holds = vec![Hold::new(1, 1, 1, date(2024, 3, 1))];
+        assert!(ready_hold(&holds, 1, 1).is_none());
+
+        assign_next(&mut holds, 1, date(2024, 3, 10), 7);
+        let hold = ready_hold(&holds, 1, 1).expect("Reserva não encontrada");
+        assert!(fulfill_hold(&mut holds, hold.id, date(2024, 3, 11)).is_ok());
+        assert_eq!(holds[0].status, HoldStatus::Fulfilled);
+        assert!(get_holds_by_user(&holds, 1).is_empty());
+    }
+
+    #[test]
+    fn test_save_and_read_holds() {
+        let temp_file = NamedTempFile::new().expect("Não foi possível criar arquivo temporário");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let holds = vec![
+            Hold::new(1, 1, 1, date(2024, 3, 1)),
+            Hold::new(2, 2, 1, date(2024, 3, 2)),
+        ];
+        assert!(save_to_json(file_path, &holds).is_ok());
+
+        let loaded = read_from_json(file_path).expect("Falha ao ler reservas");
+        assert_eq!(loaded.len(), 2);
+    }
+
+    #[test]
+    fn test_read_from_nonexistent_file() {
+        let result = read_from_json("arquivo_que_nao_existe.json");
+        assert!(result.is_ok());
+        assert!(result.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_read_from_malformed_json() {
+        let mut temp_file =
+            NamedTempFile::new().expect("Não foi possível criar arquivo temporário");
+        writeln!(temp_file, "isto não é um JSON válido")
+            .expect("Falha ao escrever no arquivo temporário");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let result = read_from_json(file_path);
+        assert!(matches!(result, Err(HoldError::JsonError(_))));
+    }
+}
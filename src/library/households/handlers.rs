@@ -0,0 +1,100 @@
+use super::service;
+use crate::library::households::models::{Household, HouseholdError};
+use crate::library::ids::models::IdAllocator;
+use crate::library::loans::models::Loan;
+use crate::library::users::models::User;
+
+pub(crate) fn save_households(households: &[Household]) -> Result<(), HouseholdError> {
+    service::save_to_json("households.json", households)
+}
+
+pub(crate) fn read_households() -> Result<Vec<Household>, HouseholdError> {
+    service::read_from_json("households.json")
+}
+
+pub(crate) fn create_household(
+    households: &mut Vec<Household>,
+    users: &[User],
+    ids: &mut IdAllocator,
+    name: String,
+    guardian_id: u32,
+) -> Result<u32, HouseholdError> {
+    service::create_household(households, users, ids, name, guardian_id)
+}
+
+pub(crate) fn add_member(
+    households: &mut [Household],
+    users: &[User],
+    household_id: u32,
+    user_id: u32,
+) -> Result<(), HouseholdError> {
+    service::add_member(households, users, household_id, user_id)
+}
+
+pub(crate) fn remove_member(
+    households: &mut [Household],
+    household_id: u32,
+    user_id: u32,
+) -> Result<(), HouseholdError> {
+    service::remove_member(households, household_id, user_id)
+}
+
+pub(crate) fn forget_user(households: &mut Vec<Household>, user_id: u32) {
+    service::forget_user(households, user_id)
+}
+
+pub(crate) fn get_household_mut(
+    households: &mut [Household],
+    household_id: u32,
+) -> Result<&mut Household, HouseholdError> {
+    service::get_household_mut(households, household_id)
+}
+
+pub(crate) fn household_of(households: &[Household], user_id: u32) -> Option<&Household> {
+    service::household_of(households, user_id)
+}
+
+pub(crate) fn guarded_by(
+    households: &[Household],
+    guardian_id: u32,
+) -> Result<&Household, HouseholdError> {
+    service::guarded_by(households, guardian_id)
+}
+
+pub(crate) fn active_loans(household: &Household, loans: &[Loan]) -> Vec<Loan> {
+    service::active_loans(household, loans)
+}
+
+pub(crate) fn print_households(households: &[Household], users: &[User]) {
+    let name = |user_id: u32| {
+        users
+            .iter()
+            .find(|u| u.id == user_id)
+            .map(|u| u.name.as_str())
+            .unwrap_or("Unknown user")
+    };
+
+    for household in households {
+        println!("Household ID: {}", household.id);
+        println!("Name: {}", household.name);
+        println!(
+            "Guardian: {} (ID {})",
+            name(household.guardian_id),
+            household.guardian_id
+        );
+        for &member in household
+            .member_ids
+            .iter()
+            .filter(|&&id| id != household.guardian_id)
+        {
+            println!("Member: {} (ID {})", name(member), member);
+        }
+        if let Some(limit) = household.loan_limit {
+            println!("Household Loan Limit: {}", limit);
+        }
+        if household.shared_fines {
+            println!("Fines: combined");
+        }
+        println!();
+    }
+}
@@ -0,0 +1,103 @@
+use crate::library::ids::models::IdError;
+use crate::library::loans::models::LoanError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io;
+
+/// Patrons who borrow as a family. The guardian can see and renew every
+/// member's loans.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Household {
+    pub id: u32,
+    pub name: String,
+    pub guardian_id: u32,
+    /// Every member, the guardian included.
+    pub member_ids: Vec<u32>,
+    /// Active loans allowed across the whole household, on top of each
+    /// member's own limit.
+    #[serde(default)]
+    pub loan_limit: Option<u32>,
+    /// Members are blocked on the household's combined fines balance rather
+    /// than their own.
+    #[serde(default)]
+    pub shared_fines: bool,
+}
+
+impl Household {
+    pub fn new(id: u32, name: String, guardian_id: u32) -> Self {
+        Self {
+            id,
+            name,
+            guardian_id,
+            member_ids: vec![guardian_id],
+            loan_limit: None,
+            shared_fines: false,
+        }
+    }
+
+    pub fn has_member(&self, user_id: u32) -> bool {
+        self.member_ids.contains(&user_id)
+    }
+}
+
+#[derive(Debug)]
+pub enum HouseholdError {
+    IoError(io::Error),
+    JsonError(serde_json::Error),
+    LoanError(LoanError),
+    HouseholdNotFound,
+    UserNotFound,
+    /// The patron already belongs to the household with this id.
+    AlreadyInHousehold(u32),
+    NotAMember,
+    NotGuardian,
+    CannotRemoveGuardian,
+    IdError(IdError),
+}
+
+impl fmt::Display for HouseholdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HouseholdError::IoError(err) => write!(f, "IO Error: {}", err),
+            HouseholdError::JsonError(err) => write!(f, "JSON Error: {}", err),
+            HouseholdError::IdError(err) => write!(f, "{}", err),
+            HouseholdError::LoanError(err) => write!(f, "{}", err),
+            HouseholdError::HouseholdNotFound => write!(f, "Household not found"),
+            HouseholdError::UserNotFound => write!(f, "User not found"),
+            HouseholdError::AlreadyInHousehold(id) => {
+                write!(f, "User already belongs to household {}", id)
+            }
+            HouseholdError::NotAMember => write!(f, "User is not a member of the household"),
+            HouseholdError::NotGuardian => write!(f, "User is not the household's guardian"),
+            HouseholdError::CannotRemoveGuardian => {
+                write!(f, "The guardian cannot be removed from the household")
+            }
+        }
+    }
+}
+
+impl std::error::Error for HouseholdError {}
+
+impl From<io::Error> for HouseholdError {
+    fn from(err: io::Error) -> Self {
+        HouseholdError::IoError(err)
+    }
+}
+
+impl From<serde_json::Error> for HouseholdError {
+    fn from(err: serde_json::Error) -> Self {
+        HouseholdError::JsonError(err)
+    }
+}
+
+impl From<IdError> for HouseholdError {
+    fn from(err: IdError) -> Self {
+        HouseholdError::IdError(err)
+    }
+}
+
+impl From<LoanError> for HouseholdError {
+    fn from(err: LoanError) -> Self {
+        HouseholdError::LoanError(err)
+    }
+}
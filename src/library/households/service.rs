@@ -0,0 +1,266 @@
+use super::models::{Household, HouseholdError};
+use crate::library::ids::models::IdAllocator;
+use crate::library::loans::models::Loan;
+use crate::library::users::models::User;
+use std::fs::File;
+use std::io::ErrorKind;
+
+pub fn read_from_json(file_path: &str) -> Result<Vec<Household>, HouseholdError> {
+    let file = match File::open(file_path) {
+        Ok(file) => file,
+        Err(err) => {
+            if err.kind() == ErrorKind::NotFound {
+                return Ok(Vec::new());
+            } else {
+                return Err(HouseholdError::IoError(err));
+            }
+        }
+    };
+
+    let households: Vec<Household> =
+        serde_json::from_reader(file).map_err(HouseholdError::JsonError)?;
+
+    Ok(households)
+}
+
+pub fn save_to_json(file_path: &str, households: &[Household]) -> Result<(), HouseholdError> {
+    let file = File::create(file_path)?;
+    serde_json::to_writer_pretty(file, households)?;
+    Ok(())
+}
+
+/// A patron may belong to one household only.
+fn check_joinable(
+    households: &[Household],
+    users: &[User],
+    user_id: u32,
+) -> Result<(), HouseholdError> {
+    if !users.iter().any(|u| u.id == user_id) {
+        return Err(HouseholdError::UserNotFound);
+    }
+    match household_of(households, user_id) {
+        Some(household) => Err(HouseholdError::AlreadyInHousehold(household.id)),
+        None => Ok(()),
+    }
+}
+
+pub fn create_household(
+    households: &mut Vec<Household>,
+    users: &[User],
+    ids: &mut IdAllocator,
+    name: String,
+    guardian_id: u32,
+) -> Result<u32, HouseholdError> {
+    check_joinable(households, users, guardian_id)?;
+
+    let id = ids.allocate()?;
+    households.push(Household::new(id, name, guardian_id));
+    Ok(id)
+}
+
+pub fn add_member(
+    households: &mut [Household],
+    users: &[User],
+    household_id: u32,
+    user_id: u32,
+) -> Result<(), HouseholdError> {
+    check_joinable(households, users, user_id)?;
+
+    get_household_mut(households, household_id)?
+        .member_ids
+        .push(user_id);
+    Ok(())
+}
+
+pub fn remove_member(
+    households: &mut [Household],
+    household_id: u32,
+    user_id: u32,
+) -> Result<(), HouseholdError> {
+    let household = get_household_mut(households, household_id)?;
+    if household.guardian_id == user_id {
+        return Err(HouseholdError::CannotRemoveGuardian);
+    } else if !household.has_member(user_id) {
+        return Err(HouseholdError::NotAMember);
+    }
+
+    household.member_ids.retain(|&id| id != user_id);
+    Ok(())
+}
+
+/// Takes a deleted patron out of their household. A household whose
+/// guardian is deleted is dissolved.
+pub fn forget_user(households: &mut Vec<Household>, user_id: u32) {
+    households.retain(|h| h.guardian_id != user_id);
+    for household in households.iter_mut() {
+        household.member_ids.retain(|&id| id != user_id);
+    }
+}
+
+pub fn get_household_mut(
+    households: &mut [Household],
+    household_id: u32,
+) -> Result<&mut Household, HouseholdError> {
+    households
+        .iter_mut()
+        .find(|h| h.id == household_id)
+        .ok_or(HouseholdError::HouseholdNotFound)
+}
+
+pub fn household_of(households: &[Household], user_id: u32) -> Option<&Household> {
+    households.iter().find(|h| h.has_member(user_id))
+}
+
+/// The household `guardian_id` is guardian of.
+pub fn guarded_by(
+    households: &[Household],
+    guardian_id: u32,
+) -> Result<&Household, HouseholdError> {
+    households
+        .iter()
+        .find(|h| h.guardian_id == guardian_id)
+        .ok_or(HouseholdError::NotGuardian)
+}
+
+/// Active loans of every member of the household.
+pub fn active_loans(household: &Household, loans: &[Loan]) -> Vec<Loan> {
+    loans
+        .iter()
+        .filter(|l| l.is_active() && household.has_member(l.user_id))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn users() -> Vec<User> {
+        ["Ana", "Bia", "Caio"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| User::new(i as u32 + 1, name.to_string()))
+            .collect()
+    }
+
+    fn household(users: &[User]) -> Vec<Household> {
+        let mut households = Vec::new();
+        create_household(
+            &mut households,
+            users,
+            &mut IdAllocator::default(),
+            "Família Silva".to_string(),
+            1,
+        )
+        .expect("Falha ao criar família");
+        households
+    }
+
+    #[test]
+    fn test_members_belong_to_one_household() {
+        let users = users();
+        let mut households = household(&users);
+
+        add_member(&mut households, &users, 1, 2).expect("Falha ao adicionar membro");
+        assert_eq!(households[0].member_ids, vec![1, 2]);
+        assert!(matches!(
+            add_member(&mut households, &users, 1, 2),
+            Err(HouseholdError::AlreadyInHousehold(1))
+        ));
+        assert!(matches!(
+            create_household(
+                &mut households,
+                &users,
+                &mut IdAllocator::default(),
+                "Outra".to_string(),
+                2
+            ),
+            Err(HouseholdError::AlreadyInHousehold(1))
+        ));
+        assert!(matches!(
+            add_member(&mut households, &users, 1, 9),
+            Err(HouseholdError::UserNotFound)
+        ));
+        assert!(matches!(
+            add_member(&mut households, &users, 9, 3),
+            Err(HouseholdError::HouseholdNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_remove_member() {
+        let users = users();
+        let mut households = household(&users);
+        add_member(&mut households, &users, 1, 2).expect("Falha ao adicionar membro");
+
+        assert!(matches!(
+            remove_member(&mut households, 1, 1),
+            Err(HouseholdError::CannotRemoveGuardian)
+        ));
+        assert!(matches!(
+            remove_member(&mut households, 1, 3),
+            Err(HouseholdError::NotAMember)
+        ));
+        remove_member(&mut households, 1, 2).expect("Falha ao remover membro");
+        assert!(household_of(&households, 2).is_none());
+
+        forget_user(&mut households, 1);
+        assert!(households.is_empty());
+    }
+
+    #[test]
+    fn test_active_loans_of_household() {
+        let users = users();
+        let mut households = household(&users);
+        add_member(&mut households, &users, 1, 2).expect("Falha ao adicionar membro");
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).expect("Data inválida");
+        let mut returned = Loan::new(2, 2, 11, date, date);
+        returned.return_date = Some(date);
+        let loans = vec![
+            Loan::new(1, 1, 10, date, date),
+            returned,
+            Loan::new(3, 2, 12, date, date),
+            Loan::new(4, 3, 13, date, date),
+        ];
+
+        let household = guarded_by(&households, 1).expect("Família não encontrada");
+        let ids: Vec<u32> = active_loans(household, &loans)
+            .iter()
+            .map(|l| l.id)
+            .collect();
+        assert_eq!(ids, vec![1, 3]);
+        assert!(matches!(
+            guarded_by(&households, 2),
+            Err(HouseholdError::NotGuardian)
+        ));
+    }
+
+    #[test]
+    fn test_save_and_read_households() {
+        let temp_file = NamedTempFile::new().expect("Não foi possível criar arquivo temporário");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let households = household(&users());
+        assert!(save_to_json(file_path, &households).is_ok());
+
+        let loaded = read_from_json(file_path).expect("Falha ao ler famílias");
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].guardian_id, 1);
+        assert!(!loaded[0].shared_fines);
+    }
+
+    #[test]
+    fn test_read_from_malformed_json() {
+        let mut temp_file =
+            NamedTempFile::new().expect("Não foi possível criar arquivo temporário");
+        writeln!(temp_file, "isto não é um JSON válido")
+            .expect("Falha ao escrever no arquivo temporário");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let result = read_from_json(file_path);
+        assert!(matches!(result, Err(HouseholdError::JsonError(_))));
+    }
+}
@@ -0,0 +1,17 @@
+use super::service;
+use crate::library::ids::models::{IdAllocator, IdCounters, IdError};
+
+pub(crate) fn save_counters(counters: &IdCounters) -> Result<(), IdError> {
+    service::save_to_json("ids.json", counters)
+}
+
+pub(crate) fn read_counters() -> Result<IdCounters, IdError> {
+    service::read_from_json("ids.json")
+}
+
+pub(crate) fn sync_with_existing(
+    allocator: &mut IdAllocator,
+    ids: impl IntoIterator<Item = u32>,
+) -> Result<(), IdError> {
+    service::sync_with_existing(allocator, ids)
+}
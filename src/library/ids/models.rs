@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io;
+
+/// Hands out increasing ids for one entity. The counter is persisted so ids
+/// are never reused, even after the record holding the highest id is deleted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdAllocator {
+    pub next_id: u32,
+}
+
+impl Default for IdAllocator {
+    fn default() -> Self {
+        Self { next_id: 1 }
+    }
+}
+
+impl IdAllocator {
+    /// The next free id. `u32::MAX` is never handed out: the counter would
+    /// have nowhere to go after it.
+    pub fn allocate(&mut self) -> Result<u32, IdError> {
+        let id = self.next_id;
+        self.next_id = id.checked_add(1).ok_or(IdError::Exhausted)?;
+        Ok(id)
+    }
+
+    /// Marks `id` as used, e.g. when it was typed in by hand.
+    pub fn reserve(&mut self, id: u32) -> Result<(), IdError> {
+        if id >= self.next_id {
+            self.next_id = id.checked_add(1).ok_or(IdError::OutOfRange(id))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IdCounters {
+    #[serde(default)]
+    pub books: IdAllocator,
+    #[serde(default)]
+    pub users: IdAllocator,
+    #[serde(default)]
+    pub loans: IdAllocator,
+    #[serde(default)]
+    pub ledger: IdAllocator,
+    #[serde(default)]
+    pub holds: IdAllocator,
+    #[serde(default)]
+    pub partners: IdAllocator,
+    #[serde(default)]
+    pub ill_requests: IdAllocator,
+    #[serde(default)]
+    pub serials: IdAllocator,
+    #[serde(default)]
+    pub serial_issues: IdAllocator,
+    #[serde(default)]
+    pub digital_titles: IdAllocator,
+    #[serde(default)]
+    pub licenses: IdAllocator,
+    #[serde(default)]
+    pub digital_loans: IdAllocator,
+    #[serde(default)]
+    pub digital_holds: IdAllocator,
+    #[serde(default)]
+    pub branches: IdAllocator,
+    #[serde(default)]
+    pub transfers: IdAllocator,
+    #[serde(default)]
+    pub households: IdAllocator,
+}
+
+#[derive(Debug)]
+pub enum IdError {
+    IoError(io::Error),
+    JsonError(serde_json::Error),
+    /// Every id up to `u32::MAX` has been used.
+    Exhausted,
+    OutOfRange(u32),
+}
+
+impl fmt::Display for IdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdError::IoError(err) => write!(f, "IO Error: {}", err),
+            IdError::JsonError(err) => write!(f, "JSON Error: {}", err),
+            IdError::Exhausted => write!(f, "No ids left to allocate"),
+            IdError::OutOfRange(id) => write!(f, "Id {} is out of range", id),
+        }
+    }
+}
+
+impl std::error::Error for IdError {}
+
+impl From<io::Error> for IdError {
+    fn from(err: io::Error) -> Self {
+        IdError::IoError(err)
+    }
+}
+
+impl From<serde_json::Error> for IdError {
+    fn from(err: serde_json::Error) -> Self {
+        IdError::JsonError(err)
+    }
+}
@@ -0,0 +1,120 @@
+use super::models::{IdAllocator, IdCounters, IdError};
+use std::fs::File;
+use std::io::ErrorKind;
+
+pub fn read_from_json(file_path: &str) -> Result<IdCounters, IdError> {
+    let file = match File::open(file_path) {
+        Ok(file) => file,
+        Err(err) => {
+            if err.kind() == ErrorKind::NotFound {
+                return Ok(IdCounters::default());
+            } else {
+                return Err(IdError::IoError(err));
+            }
+        }
+    };
+
+    let counters: IdCounters = serde_json::from_reader(file).map_err(IdError::JsonError)?;
+
+    Ok(counters)
+}
+
+pub fn save_to_json(file_path: &str, counters: &IdCounters) -> Result<(), IdError> {
+    let file = File::create(file_path)?;
+    serde_json::to_writer_pretty(file, counters)?;
+    Ok(())
+}
+
+/// Moves the counter past every id already in use, so a missing or stale
+/// counter file never hands out a duplicate.
+pub fn sync_with_existing(
+    allocator: &mut IdAllocator,
+    ids: impl IntoIterator<Item = u32>,
+) -> Result<(), IdError> {
+    for id in ids {
+        allocator.reserve(id)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_allocate_increments() {
+        let mut allocator = IdAllocator::default();
+        assert_eq!(allocator.allocate().unwrap(), 1);
+        assert_eq!(allocator.allocate().unwrap(), 2);
+        assert_eq!(allocator.next_id, 3);
+    }
+
+    #[test]
+    fn test_reserve_skips_used_ids() {
+        let mut allocator = IdAllocator::default();
+        allocator.reserve(10).unwrap();
+        assert_eq!(allocator.allocate().unwrap(), 11);
+        allocator.reserve(5).unwrap();
+        assert_eq!(allocator.allocate().unwrap(), 12);
+    }
+
+    #[test]
+    fn test_reserve_and_allocate_never_overflow() {
+        let mut allocator = IdAllocator::default();
+        assert!(matches!(
+            allocator.reserve(u32::MAX),
+            Err(IdError::OutOfRange(u32::MAX))
+        ));
+        assert_eq!(allocator.next_id, 1);
+
+        allocator.reserve(u32::MAX - 2).unwrap();
+        assert_eq!(allocator.allocate().unwrap(), u32::MAX - 1);
+        assert!(matches!(allocator.allocate(), Err(IdError::Exhausted)));
+        assert_eq!(allocator.next_id, u32::MAX);
+    }
+
+    #[test]
+    fn test_sync_with_existing() {
+        let mut allocator = IdAllocator::default();
+        sync_with_existing(&mut allocator, vec![3, 7, 2]).unwrap();
+        assert_eq!(allocator.allocate().unwrap(), 8);
+    }
+
+    #[test]
+    fn test_save_and_read_counters() {
+        let temp_file = NamedTempFile::new().expect("Não foi possível criar arquivo temporário");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let mut counters = IdCounters::default();
+        counters.books.reserve(4).unwrap();
+        counters.loans.allocate().unwrap();
+
+        assert!(save_to_json(file_path, &counters).is_ok());
+
+        let loaded = read_from_json(file_path).expect("Falha ao ler contadores");
+        assert_eq!(loaded.books.next_id, 5);
+        assert_eq!(loaded.users.next_id, 1);
+        assert_eq!(loaded.loans.next_id, 2);
+    }
+
+    #[test]
+    fn test_read_from_nonexistent_file() {
+        let result = read_from_json("arquivo_que_nao_existe.json");
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().books.next_id, 1);
+    }
+
+    #[test]
+    fn test_read_from_malformed_json() {
+        let mut temp_file =
+            NamedTempFile::new().expect("Não foi possível criar arquivo temporário");
+        writeln!(temp_file, "isto não é um JSON válido")
+            .expect("Falha ao escrever no arquivo temporário");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let result = read_from_json(file_path);
+        assert!(matches!(result, Err(IdError::JsonError(_))));
+    }
+}
@@ -0,0 +1,72 @@
+use super::service;
+use crate::library::branches::models::Transfer;
+use crate::library::digital::models::DigitalCollection;
+use crate::library::fines::models::LedgerEntry;
+use crate::library::holds::models::Hold;
+use crate::library::integrity::models::{Archive, DeleteError, DeletePolicy, Dependents, Subject};
+use crate::library::interlibrary::models::IllRequest;
+use crate::library::loans::models::Loan;
+use crate::library::serials::models::SerialIssue;
+
+pub(crate) fn save_archive(archive: &Archive) -> Result<(), DeleteError> {
+    service::save_to_json("archive.json", archive)
+}
+
+pub(crate) fn read_archive() -> Result<Archive, DeleteError> {
+    service::read_from_json("archive.json")
+}
+
+pub(crate) fn dependents(
+    loans: &[Loan],
+    holds: &[Hold],
+    ledger: &[LedgerEntry],
+    subject: Subject,
+) -> Dependents {
+    service::dependents(loans, holds, ledger, subject)
+}
+
+pub(crate) fn open_ill_requests(requests: &[IllRequest], subject: Subject) -> usize {
+    service::open_ill_requests(requests, subject)
+}
+
+pub(crate) fn open_transfers(transfers: &[Transfer], subject: Subject) -> usize {
+    service::open_transfers(transfers, subject)
+}
+
+pub(crate) fn open_digital(collection: &DigitalCollection, subject: Subject) -> (usize, usize) {
+    service::open_digital(collection, subject)
+}
+
+pub(crate) fn catalogued_issues(issues: &[SerialIssue], subject: Subject) -> usize {
+    service::catalogued_issues(issues, subject)
+}
+
+pub(crate) fn detach_issues(issues: &mut [SerialIssue], subject: Subject) {
+    service::detach_issues(issues, subject)
+}
+
+pub(crate) fn check_deletable(
+    dependents: &Dependents,
+    policy: DeletePolicy,
+) -> Result<(), DeleteError> {
+    service::check_deletable(dependents, policy)
+}
+
+pub(crate) fn archive_history(
+    loans: &mut Vec<Loan>,
+    holds: &mut Vec<Hold>,
+    ledger: &mut Vec<LedgerEntry>,
+    archive: &mut Archive,
+    subject: Subject,
+) {
+    service::archive_history(loans, holds, ledger, archive, subject)
+}
+
+pub(crate) fn anonymize_history(
+    loans: &mut [Loan],
+    holds: &mut [Hold],
+    ledger: &mut [LedgerEntry],
+    subject: Subject,
+) {
+    service::anonymize_history(loans, holds, ledger, subject)
+}
@@ -0,0 +1,141 @@
+use crate::library::books::models::BookError;
+use crate::library::fines::models::LedgerEntry;
+use crate::library::fines::service as fines_service;
+use crate::library::holds::models::Hold;
+use crate::library::loans::models::Loan;
+use crate::library::users::models::UserError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io;
+
+/// Id that anonymized history points at instead of the deleted book or user.
+pub const ANONYMOUS_ID: u32 = 0;
+
+/// What happens to the loan, hold and ledger history of a deleted record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeletePolicy {
+    /// Refuse to delete anything that still has history.
+    Block,
+    /// Move the history into the archive, then delete.
+    CascadeArchive,
+    /// Keep the history but detach it from the deleted record.
+    Anonymize,
+}
+
+impl fmt::Display for DeletePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeletePolicy::Block => write!(f, "Block"),
+            DeletePolicy::CascadeArchive => write!(f, "Archive history"),
+            DeletePolicy::Anonymize => write!(f, "Anonymize history"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subject {
+    Book(u32),
+    User(u32),
+}
+
+/// Records that still point at a book or user.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Dependents {
+    pub active_loans: usize,
+    pub pending_holds: usize,
+    pub balance_cents: i64,
+    /// Interlibrary requests not yet returned or cancelled.
+    pub open_ill_requests: usize,
+    /// Transfers between branches not yet received.
+    pub open_transfers: usize,
+    /// Closed loans, closed holds and ledger entries.
+    pub history_records: usize,
+}
+
+/// History moved out of the live files by `DeletePolicy::CascadeArchive`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Archive {
+    #[serde(default)]
+    pub loans: Vec<Loan>,
+    #[serde(default)]
+    pub holds: Vec<Hold>,
+    #[serde(default)]
+    pub ledger: Vec<LedgerEntry>,
+}
+
+#[derive(Debug)]
+pub enum DeleteError {
+    IoError(io::Error),
+    JsonError(serde_json::Error),
+    BookError(BookError),
+    UserError(UserError),
+    HasActiveLoans(usize),
+    HasPendingHolds(usize),
+    HasUnpaidFines { balance_cents: i64 },
+    HasOpenIllRequests(usize),
+    HasOpenTransfers(usize),
+    HasHistory { records: usize },
+}
+
+impl fmt::Display for DeleteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeleteError::IoError(err) => write!(f, "IO Error: {}", err),
+            DeleteError::JsonError(err) => write!(f, "JSON Error: {}", err),
+            DeleteError::BookError(err) => write!(f, "{}", err),
+            DeleteError::UserError(err) => write!(f, "{}", err),
+            DeleteError::HasActiveLoans(count) => {
+                write!(f, "Cannot delete: {} loan(s) are still active", count)
+            }
+            DeleteError::HasPendingHolds(count) => {
+                write!(f, "Cannot delete: {} hold(s) are still pending", count)
+            }
+            DeleteError::HasUnpaidFines { balance_cents } => write!(
+                f,
+                "Cannot delete: the account has an unpaid balance of {}",
+                fines_service::format_amount(*balance_cents)
+            ),
+            DeleteError::HasOpenIllRequests(count) => write!(
+                f,
+                "Cannot delete: {} interlibrary request(s) are still open",
+                count
+            ),
+            DeleteError::HasOpenTransfers(count) => write!(
+                f,
+                "Cannot delete: {} transfer(s) between branches are still in transit",
+                count
+            ),
+            DeleteError::HasHistory { records } => write!(
+                f,
+                "Cannot delete: {} history record(s) refer to it; archive or anonymize them instead",
+                records
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DeleteError {}
+
+impl From<io::Error> for DeleteError {
+    fn from(err: io::Error) -> Self {
+        DeleteError::IoError(err)
+    }
+}
+
+impl From<serde_json::Error> for DeleteError {
+    fn from(err: serde_json::Error) -> Self {
+        DeleteError::JsonError(err)
+    }
+}
+
+impl From<BookError> for DeleteError {
+    fn from(err: BookError) -> Self {
+        DeleteError::BookError(err)
+    }
+}
+
+impl From<UserError> for DeleteError {
+    fn from(err: UserError) -> Self {
+        DeleteError::UserError(err)
+    }
+}
@@ -0,0 +1,366 @@
+use super::models::{Archive, DeleteError, DeletePolicy, Dependents, Subject, ANONYMOUS_ID};
+use crate::library::branches::models::Transfer;
+use crate::library::digital::models::DigitalCollection;
+use crate::library::fines::models::LedgerEntry;
+use crate::library::fines::service as fines_service;
+use crate::library::holds::models::Hold;
+use crate::library::interlibrary::models::IllRequest;
+use crate::library::loans::models::Loan;
+use crate::library::serials::models::SerialIssue;
+use std::fs::File;
+use std::io::ErrorKind;
+
+pub fn read_from_json(file_path: &str) -> Result<Archive, DeleteError> {
+    let file = match File::open(file_path) {
+        Ok(file) => file,
+        Err(err) => {
+            if err.kind() == ErrorKind::NotFound {
+                return Ok(Archive::default());
+            } else {
+                return Err(DeleteError::IoError(err));
+            }
+        }
+    };
+
+    let archive: Archive = serde_json::from_reader(file).map_err(DeleteError::JsonError)?;
+
+    Ok(archive)
+}
+
+pub fn save_to_json(file_path: &str, archive: &Archive) -> Result<(), DeleteError> {
+    let file = File::create(file_path)?;
+    serde_json::to_writer_pretty(file, archive)?;
+    Ok(())
+}
+
+fn loan_refers_to(loan: &Loan, subject: Subject) -> bool {
+    match subject {
+        Subject::Book(id) => loan.book_id == id,
+        Subject::User(id) => loan.user_id == id,
+    }
+}
+
+fn hold_refers_to(hold: &Hold, subject: Subject) -> bool {
+    match subject {
+        Subject::Book(id) => hold.book_id == id,
+        Subject::User(id) => hold.user_id == id,
+    }
+}
+
+/// Ledger entries belong to users; a book reaches them through its loans.
+fn entry_refers_to(entry: &LedgerEntry, subject: Subject, loans: &[Loan]) -> bool {
+    match subject {
+        Subject::User(id) => entry.user_id == id,
+        Subject::Book(_) => entry.loan_id.is_some_and(|loan_id| {
+            loans
+                .iter()
+                .any(|l| l.id == loan_id && loan_refers_to(l, subject))
+        }),
+    }
+}
+
+pub fn dependents(
+    loans: &[Loan],
+    holds: &[Hold],
+    ledger: &[LedgerEntry],
+    subject: Subject,
+) -> Dependents {
+    let (active_loans, closed_loans) =
+        loans
+            .iter()
+            .filter(|l| loan_refers_to(l, subject))
+            .fold((0, 0), |(active, closed), l| {
+                if l.is_active() {
+                    (active + 1, closed)
+                } else {
+                    (active, closed + 1)
+                }
+            });
+    let (pending_holds, closed_holds) =
+        holds
+            .iter()
+            .filter(|h| hold_refers_to(h, subject))
+            .fold((0, 0), |(pending, closed), h| {
+                if h.is_pending() {
+                    (pending + 1, closed)
+                } else {
+                    (pending, closed + 1)
+                }
+            });
+    let ledger_entries = ledger
+        .iter()
+        .filter(|e| entry_refers_to(e, subject, loans))
+        .count();
+    let balance_cents = match subject {
+        Subject::User(id) => fines_service::balance(ledger, id),
+        Subject::Book(_) => 0,
+    };
+
+    Dependents {
+        active_loans,
+        pending_holds,
+        balance_cents,
+        open_ill_requests: 0,
+        open_transfers: 0,
+        history_records: closed_loans + closed_holds + ledger_entries,
+    }
+}
+
+/// Open interlibrary requests for `subject`: a copy lent to a partner or a
+/// patron still waiting on (or holding) a borrowed item.
+pub fn open_ill_requests(requests: &[IllRequest], subject: Subject) -> usize {
+    requests
+        .iter()
+        .filter(|r| r.is_open())
+        .filter(|r| match subject {
+            Subject::Book(id) => r.book_id == Some(id),
+            Subject::User(id) => r.user_id == Some(id),
+        })
+        .count()
+}
+
+/// Transfers of `subject` still in transit between branches.
+pub fn open_transfers(transfers: &[Transfer], subject: Subject) -> usize {
+    match subject {
+        Subject::Book(id) => transfers
+            .iter()
+            .filter(|t| t.book_id == id && t.is_in_transit())
+            .count(),
+        Subject::User(_) => 0,
+    }
+}
+
+/// Digital loans not yet ended and waitlist entries still waiting, in that
+/// order. Only users have any.
+pub fn open_digital(collection: &DigitalCollection, subject: Subject) -> (usize, usize) {
+    let Subject::User(id) = subject else {
+        return (0, 0);
+    };
+    let loans = collection
+        .loans
+        .iter()
+        .filter(|l| l.user_id == id && l.ended_on.is_none())
+        .count();
+    let holds = collection
+        .waitlist
+        .iter()
+        .filter(|h| h.user_id == id && h.is_waiting())
+        .count();
+    (loans, holds)
+}
+
+/// Serial issues catalogued as `subject`. Users never have any.
+pub fn catalogued_issues(issues: &[SerialIssue], subject: Subject) -> usize {
+    match subject {
+        Subject::Book(id) => issues.iter().filter(|i| i.book_id == Some(id)).count(),
+        Subject::User(_) => 0,
+    }
+}
+
+/// Unlinks serial issues from a deleted book; the issue stays checked in.
+pub fn detach_issues(issues: &mut [SerialIssue], subject: Subject) {
+    if let Subject::Book(id) = subject {
+        for issue in issues.iter_mut().filter(|i| i.book_id == Some(id)) {
+            issue.book_id = None;
+        }
+    }
+}
+
+/// Active loans, open interlibrary requests and transfers, pending holds and
+/// unpaid fines always prevent deletion; closed history only does under
+/// `DeletePolicy::Block`.
+pub fn check_deletable(dependents: &Dependents, policy: DeletePolicy) -> Result<(), DeleteError> {
+    if dependents.active_loans > 0 {
+        return Err(DeleteError::HasActiveLoans(dependents.active_loans));
+    }
+    if dependents.open_ill_requests > 0 {
+        return Err(DeleteError::HasOpenIllRequests(
+            dependents.open_ill_requests,
+        ));
+    }
+    if dependents.open_transfers > 0 {
+        return Err(DeleteError::HasOpenTransfers(dependents.open_transfers));
+    }
+    if dependents.pending_holds > 0 {
+        return Err(DeleteError::HasPendingHolds(dependents.pending_holds));
+    }
+    if dependents.balance_cents > 0 {
+        return Err(DeleteError::HasUnpaidFines {
+            balance_cents: dependents.balance_cents,
+        });
+    }
+    if policy == DeletePolicy::Block && dependents.history_records > 0 {
+        return Err(DeleteError::HasHistory {
+            records: dependents.history_records,
+        });
+    }
+    Ok(())
+}
+
+/// Moves every loan, hold and ledger entry referring to `subject` into the archive.
+pub fn archive_history(
+    loans: &mut Vec<Loan>,
+    holds: &mut Vec<Hold>,
+    ledger: &mut Vec<LedgerEntry>,
+    archive: &mut Archive,
+    subject: Subject,
+) {
+    let (archived, kept): (Vec<LedgerEntry>, Vec<LedgerEntry>) = ledger
+        .drain(..)
+        .partition(|e| entry_refers_to(e, subject, loans));
+    archive.ledger.extend(archived);
+    *ledger = kept;
+
+    let (archived, kept): (Vec<Loan>, Vec<Loan>) =
+        loans.drain(..).partition(|l| loan_refers_to(l, subject));
+    archive.loans.extend(archived);
+    *loans = kept;
+
+    let (archived, kept): (Vec<Hold>, Vec<Hold>) =
+        holds.drain(..).partition(|h| hold_refers_to(h, subject));
+    archive.holds.extend(archived);
+    *holds = kept;
+}
+
+/// Points every loan, hold and ledger entry referring to `subject` at
+/// `ANONYMOUS_ID`, keeping dates and amounts for statistics.
+pub fn anonymize_history(
+    loans: &mut [Loan],
+    holds: &mut [Hold],
+    ledger: &mut [LedgerEntry],
+    subject: Subject,
+) {
+    for loan in loans.iter_mut() {
+        match subject {
+            Subject::Book(id) if loan.book_id == id => loan.book_id = ANONYMOUS_ID,
+            Subject::User(id) if loan.user_id == id => loan.user_id = ANONYMOUS_ID,
+            _ => {}
+        }
+    }
+    for hold in holds.iter_mut() {
+        match subject {
+            Subject::Book(id) if hold.book_id == id => hold.book_id = ANONYMOUS_ID,
+            Subject::User(id) if hold.user_id == id => hold.user_id = ANONYMOUS_ID,
+            _ => {}
+        }
+    }
+    if let Subject::User(id) = subject {
+        for entry in ledger.iter_mut().filter(|e| e.user_id == id) {
+            entry.user_id = ANONYMOUS_ID;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::fines::models::ChargeReason;
+    use chrono::NaiveDate;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).expect("Data inválida")
+    }
+
+    fn returned_loan(id: u32, user_id: u32, book_id: u32) -> Loan {
+        Loan {
+            return_date: Some(date(2024, 1, 20)),
+            ..Loan::new(id, user_id, book_id, date(2024, 1, 1), date(2024, 1, 15))
+        }
+    }
+
+    fn overdue_charge(user_id: u32, loan_id: u32) -> LedgerEntry {
+        LedgerEntry::charge(
+            user_id,
+            date(2024, 1, 20),
+            ChargeReason::Overdue,
+            250,
+            Some(loan_id),
+        )
+    }
+
+    #[test]
+    fn test_active_loan_blocks_every_policy() {
+        let loans = vec![Loan::new(1, 1, 1, date(2024, 1, 1), date(2024, 1, 15))];
+        let found = dependents(&loans, &[], &[], Subject::Book(1));
+        assert_eq!(found.active_loans, 1);
+        for policy in [
+            DeletePolicy::Block,
+            DeletePolicy::CascadeArchive,
+            DeletePolicy::Anonymize,
+        ] {
+            assert!(matches!(
+                check_deletable(&found, policy),
+                Err(DeleteError::HasActiveLoans(1))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_unpaid_fines_and_holds_block_user_deletion() {
+        let loans = vec![returned_loan(1, 1, 1)];
+        let ledger = vec![overdue_charge(1, 1)];
+        let found = dependents(&loans, &[], &ledger, Subject::User(1));
+        assert!(matches!(
+            check_deletable(&found, DeletePolicy::Anonymize),
+            Err(DeleteError::HasUnpaidFines { balance_cents: 250 })
+        ));
+
+        let holds = vec![Hold::new(1, 1, 2, date(2024, 2, 1))];
+        let found = dependents(&[], &holds, &[], Subject::User(1));
+        assert!(matches!(
+            check_deletable(&found, DeletePolicy::CascadeArchive),
+            Err(DeleteError::HasPendingHolds(1))
+        ));
+    }
+
+    #[test]
+    fn test_block_policy_refuses_history() {
+        let loans = vec![returned_loan(1, 1, 1)];
+        let found = dependents(&loans, &[], &[], Subject::User(1));
+        assert!(matches!(
+            check_deletable(&found, DeletePolicy::Block),
+            Err(DeleteError::HasHistory { records: 1 })
+        ));
+        assert!(check_deletable(&found, DeletePolicy::Anonymize).is_ok());
+    }
+
+    #[test]
+    fn test_archive_book_history() {
+        let mut loans = vec![returned_loan(1, 1, 1), returned_loan(2, 1, 2)];
+        let mut holds = vec![Hold::new(1, 2, 1, date(2024, 1, 5))];
+        let mut ledger = vec![overdue_charge(1, 1), overdue_charge(1, 2)];
+        let mut archive = Archive::default();
+
+        archive_history(
+            &mut loans,
+            &mut holds,
+            &mut ledger,
+            &mut archive,
+            Subject::Book(1),
+        );
+        assert_eq!(loans.len(), 1);
+        assert_eq!(loans[0].book_id, 2);
+        assert!(holds.is_empty());
+        assert_eq!(ledger.len(), 1);
+        assert_eq!(ledger[0].loan_id, Some(2));
+        assert_eq!(archive.loans.len(), 1);
+        assert_eq!(archive.holds.len(), 1);
+        assert_eq!(archive.ledger.len(), 1);
+    }
+
+    #[test]
+    fn test_anonymize_user_history() {
+        let mut loans = vec![returned_loan(1, 1, 1), returned_loan(2, 2, 1)];
+        let mut holds = Vec::new();
+        let mut ledger = vec![overdue_charge(1, 1)];
+
+        anonymize_history(&mut loans, &mut holds, &mut ledger, Subject::User(1));
+        assert_eq!(loans[0].user_id, ANONYMOUS_ID);
+        assert_eq!(loans[1].user_id, 2);
+        assert_eq!(ledger[0].user_id, ANONYMOUS_ID);
+        assert_eq!(
+            dependents(&loans, &holds, &ledger, Subject::User(1)),
+            Dependents::default()
+        );
+    }
+}
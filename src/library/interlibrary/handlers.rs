@@ -0,0 +1,115 @@
+use super::service;
+use crate::library::books::models::Book;
+use crate::library::ids::models::IdAllocator;
+use crate::library::interlibrary::models::{IllError, IllRequest, Interlibrary, PartnerLibrary};
+use crate::library::users::models::User;
+use chrono::NaiveDate;
+
+pub(crate) fn save_interlibrary(records: &Interlibrary) -> Result<(), IllError> {
+    service::save_to_json("interlibrary.json", records)
+}
+
+pub(crate) fn read_interlibrary() -> Result<Interlibrary, IllError> {
+    service::read_from_json("interlibrary.json")
+}
+
+pub(crate) fn add_partner(
+    partners: &mut Vec<PartnerLibrary>,
+    ids: &mut IdAllocator,
+    name: String,
+    contact: String,
+) -> Result<u32, IllError> {
+    service::add_partner(partners, ids, name, contact)
+}
+
+pub(crate) fn open_request(
+    records: &mut Interlibrary,
+    users: &[User],
+    books: &[Book],
+    ids: &mut IdAllocator,
+    request: IllRequest,
+) -> Result<u32, IllError> {
+    service::open_request(records, users, books, ids, request)
+}
+
+pub(crate) fn ship(
+    records: &mut Interlibrary,
+    books: &mut [Book],
+    request_id: u32,
+    date: NaiveDate,
+    due_date: NaiveDate,
+) -> Result<IllRequest, IllError> {
+    service::ship(records, books, request_id, date, due_date)
+}
+
+pub(crate) fn receive(
+    records: &mut Interlibrary,
+    request_id: u32,
+    date: NaiveDate,
+) -> Result<IllRequest, IllError> {
+    service::receive(records, request_id, date)
+}
+
+pub(crate) fn return_item(
+    records: &mut Interlibrary,
+    books: &mut [Book],
+    request_id: u32,
+    date: NaiveDate,
+) -> Result<IllRequest, IllError> {
+    service::return_item(records, books, request_id, date)
+}
+
+pub(crate) fn cancel(
+    records: &mut Interlibrary,
+    request_id: u32,
+    date: NaiveDate,
+) -> Result<IllRequest, IllError> {
+    service::cancel(records, request_id, date)
+}
+
+pub(crate) fn get_open_requests(requests: &[IllRequest]) -> Vec<IllRequest> {
+    service::get_open_requests(requests)
+}
+
+pub(crate) fn get_overdue_requests(requests: &[IllRequest], as_of: NaiveDate) -> Vec<IllRequest> {
+    service::get_overdue_requests(requests, as_of)
+}
+
+pub(crate) fn print_partners(partners: &[PartnerLibrary]) {
+    for partner in partners {
+        println!("Partner ID: {}", partner.id);
+        println!("Name: {}", partner.name);
+        if !partner.contact.is_empty() {
+            println!("Contact: {}", partner.contact);
+        }
+        println!();
+    }
+}
+
+pub(crate) fn print_requests(requests: &[IllRequest], partners: &[PartnerLibrary]) {
+    for request in requests {
+        println!("ILL ID: {}", request.id);
+        println!("Type: {}", request.direction);
+        println!(
+            "Partner: {} (ID {})",
+            service::partner_name(partners, request.partner_id),
+            request.partner_id
+        );
+        println!("Title: {}", request.title);
+        if !request.author.is_empty() {
+            println!("Author: {}", request.author);
+        }
+        if let Some(user_id) = request.user_id {
+            println!("For User ID: {}", user_id);
+        }
+        if let Some(book_id) = request.book_id {
+            println!("Our Book ID: {}", book_id);
+        }
+        println!("Requested On: {}", request.requested_on);
+        println!("Status: {}", request.status);
+        if let Some(due_date) = request.due_date {
+            println!("Due Date: {}", due_date);
+        }
+        println!();
+    }
+}
@@ -0,0 +1,241 @@
+use crate::library::books::models::BookError;
+use crate::library::ids::models::IdError;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io;
+
+/// Another library we borrow from and lend to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartnerLibrary {
+    pub id: u32,
+    pub name: String,
+    #[serde(default)]
+    pub contact: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IllDirection {
+    /// We ask a partner for an item on behalf of one of our patrons.
+    Outgoing,
+    /// A partner asks us for one of our items.
+    Incoming,
+}
+
+impl fmt::Display for IllDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IllDirection::Outgoing => write!(f, "Borrowed from partner"),
+            IllDirection::Incoming => write!(f, "Lent to partner"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IllStatus {
+    Requested,
+    /// The lending library has sent the item.
+    Shipped,
+    /// The borrowing library has the item.
+    Received,
+    /// The item is back at the lending library.
+    Returned,
+    Cancelled,
+}
+
+impl IllStatus {
+    pub fn can_transition_to(self, next: IllStatus) -> bool {
+        use IllStatus::*;
+
+        matches!(
+            (self, next),
+            (Requested, Shipped)
+                | (Requested, Cancelled)
+                | (Shipped, Received)
+                | (Received, Returned)
+        )
+    }
+}
+
+impl fmt::Display for IllStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IllStatus::Requested => write!(f, "Requested"),
+            IllStatus::Shipped => write!(f, "Shipped"),
+            IllStatus::Received => write!(f, "Received"),
+            IllStatus::Returned => write!(f, "Returned"),
+            IllStatus::Cancelled => write!(f, "Cancelled"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IllStatusChange {
+    pub status: IllStatus,
+    pub date: NaiveDate,
+}
+
+/// An interlibrary loan. Outgoing requests name the patron the item is for
+/// and describe an item outside our catalog; incoming requests name the copy
+/// of ours that the partner borrows.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IllRequest {
+    pub id: u32,
+    pub direction: IllDirection,
+    pub partner_id: u32,
+    pub title: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub user_id: Option<u32>,
+    #[serde(default)]
+    pub book_id: Option<u32>,
+    pub requested_on: NaiveDate,
+    pub status: IllStatus,
+    /// Set by the lending library when the item ships.
+    #[serde(default)]
+    pub due_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub history: Vec<IllStatusChange>,
+}
+
+impl IllRequest {
+    /// A request to a partner for an item one of our patrons needs.
+    pub fn outgoing(
+        partner_id: u32,
+        user_id: u32,
+        title: String,
+        author: String,
+        requested_on: NaiveDate,
+    ) -> Self {
+        Self {
+            id: 0,
+            direction: IllDirection::Outgoing,
+            partner_id,
+            title,
+            author,
+            user_id: Some(user_id),
+            book_id: None,
+            requested_on,
+            status: IllStatus::Requested,
+            due_date: None,
+            history: Vec::new(),
+        }
+    }
+
+    /// A partner's request for one of our copies.
+    pub fn incoming(partner_id: u32, book_id: u32, requested_on: NaiveDate) -> Self {
+        Self {
+            id: 0,
+            direction: IllDirection::Incoming,
+            partner_id,
+            title: String::new(),
+            author: String::new(),
+            user_id: None,
+            book_id: Some(book_id),
+            requested_on,
+            status: IllStatus::Requested,
+            due_date: None,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        !matches!(self.status, IllStatus::Returned | IllStatus::Cancelled)
+    }
+
+    /// Whether the item is out past its due date as of `date`.
+    pub fn is_overdue(&self, date: NaiveDate) -> bool {
+        matches!(self.status, IllStatus::Shipped | IllStatus::Received)
+            && self.due_date.is_some_and(|due| due < date)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IllRules {
+    /// Loan period we give partners when lending them our items.
+    pub lending_period_days: u32,
+}
+
+impl Default for IllRules {
+    fn default() -> Self {
+        Self {
+            lending_period_days: 28,
+        }
+    }
+}
+
+/// Partners and interlibrary requests, persisted in `interlibrary.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Interlibrary {
+    #[serde(default)]
+    pub partners: Vec<PartnerLibrary>,
+    #[serde(default)]
+    pub requests: Vec<IllRequest>,
+}
+
+#[derive(Debug)]
+pub enum IllError {
+    IoError(io::Error),
+    JsonError(serde_json::Error),
+    BookError(BookError),
+    PartnerNotFound,
+    PartnerAlreadyExists(String),
+    RequestNotFound,
+    UserNotFound,
+    BookNotFound,
+    InvalidTransition { from: IllStatus, to: IllStatus },
+    MissingDueDate,
+    IdError(IdError),
+}
+
+impl fmt::Display for IllError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IllError::IoError(err) => write!(f, "IO Error: {}", err),
+            IllError::JsonError(err) => write!(f, "JSON Error: {}", err),
+            IllError::IdError(err) => write!(f, "{}", err),
+            IllError::BookError(err) => write!(f, "{}", err),
+            IllError::PartnerNotFound => write!(f, "Partner library not found"),
+            IllError::PartnerAlreadyExists(name) => {
+                write!(f, "Partner library '{}' already exists", name)
+            }
+            IllError::RequestNotFound => write!(f, "Interlibrary request not found"),
+            IllError::UserNotFound => write!(f, "User not found"),
+            IllError::BookNotFound => write!(f, "Book not found"),
+            IllError::InvalidTransition { from, to } => {
+                write!(f, "Cannot change request from {} to {}", from, to)
+            }
+            IllError::MissingDueDate => {
+                write!(f, "The lending library's due date is required")
+            }
+        }
+    }
+}
+
+impl std::error::Error for IllError {}
+
+impl From<io::Error> for IllError {
+    fn from(err: io::Error) -> Self {
+        IllError::IoError(err)
+    }
+}
+
+impl From<serde_json::Error> for IllError {
+    fn from(err: serde_json::Error) -> Self {
+        IllError::JsonError(err)
+    }
+}
+
+impl From<IdError> for IllError {
+    fn from(err: IdError) -> Self {
+        IllError::IdError(err)
+    }
+}
+
+impl From<BookError> for IllError {
+    fn from(err: BookError) -> Self {
+        IllError::BookError(err)
+    }
+}
@@ -0,0 +1,454 @@
+use super::models::{
+    IllDirection, IllError, IllRequest, IllStatus, IllStatusChange, Interlibrary, PartnerLibrary,
+};
+use crate::library::books::models::{Book, ItemStatus};
+use crate::library::ids::models::IdAllocator;
+use crate::library::users::models::User;
+use chrono::NaiveDate;
+use std::fs::File;
+use std::io::ErrorKind;
+
+pub fn read_from_json(file_path: &str) -> Result<Interlibrary, IllError> {
+    let file = match File::open(file_path) {
+        Ok(file) => file,
+        Err(err) => {
+            if err.kind() == ErrorKind::NotFound {
+                return Ok(Interlibrary::default());
+            } else {
+                return Err(IllError::IoError(err));
+            }
+        }
+    };
+
+    let records: Interlibrary = serde_json::from_reader(file).map_err(IllError::JsonError)?;
+
+    Ok(records)
+}
+
+pub fn save_to_json(file_path: &str, records: &Interlibrary) -> Result<(), IllError> {
+    let file = File::create(file_path)?;
+    serde_json::to_writer_pretty(file, records)?;
+    Ok(())
+}
+
+pub fn add_partner(
+    partners: &mut Vec<PartnerLibrary>,
+    ids: &mut IdAllocator,
+    name: String,
+    contact: String,
+) -> Result<u32, IllError> {
+    if partners.iter().any(|p| p.name.eq_ignore_ascii_case(&name)) {
+        return Err(IllError::PartnerAlreadyExists(name));
+    }
+
+    let id = ids.allocate()?;
+    partners.push(PartnerLibrary { id, name, contact });
+    Ok(id)
+}
+
+/// Records a new request and returns its id. Outgoing requests must name
+/// one of our patrons; incoming ones take their title from our copy.
+pub fn open_request(
+    records: &mut Interlibrary,
+    users: &[User],
+    books: &[Book],
+    ids: &mut IdAllocator,
+    mut request: IllRequest,
+) -> Result<u32, IllError> {
+    if !records.partners.iter().any(|p| p.id == request.partner_id) {
+        return Err(IllError::PartnerNotFound);
+    }
+
+    match request.direction {
+        IllDirection::Outgoing => {
+            let user_id = request.user_id.ok_or(IllError::UserNotFound)?;
+            if !users.iter().any(|u| u.id == user_id) {
+                return Err(IllError::UserNotFound);
+            }
+        }
+        IllDirection::Incoming => {
+            let book = request
+                .book_id
+                .and_then(|id| books.iter().find(|b| b.id == id))
+                .ok_or(IllError::BookNotFound)?;
+            request.title = book.title.clone();
+            request.author = book.author.clone();
+        }
+    }
+
+    request.id = ids.allocate()?;
+    request.status = IllStatus::Requested;
+    request.history = vec![IllStatusChange {
+        status: IllStatus::Requested,
+        date: request.requested_on,
+    }];
+    let id = request.id;
+    records.requests.push(request);
+    Ok(id)
+}
+
+/// The lending library sends the item and sets its due date. When we are
+/// the lender, our copy leaves the shelf.
+pub fn ship(
+    records: &mut Interlibrary,
+    books: &mut [Book],
+    request_id: u32,
+    date: NaiveDate,
+    due_date: NaiveDate,
+) -> Result<IllRequest, IllError> {
+    let request = find_request(records, request_id)?;
+    check_transition(request, IllStatus::Shipped)?;
+
+    if let (IllDirection::Incoming, Some(book_id)) = (request.direction, request.book_id) {
+        let partner = partner_name(&records.partners, request.partner_id);
+        change_book_status(
+            books,
+            book_id,
+            ItemStatus::OnLoan,
+            &format!("Lent to {} (ILL {})", partner, request_id),
+            date,
+        )?;
+    }
+
+    let request = find_request_mut(records, request_id)?;
+    request.due_date = Some(due_date);
+    advance(request, IllStatus::Shipped, date);
+    Ok(request.clone())
+}
+
+/// The borrowing library has the item in hand.
+pub fn receive(
+    records: &mut Interlibrary,
+    request_id: u32,
+    date: NaiveDate,
+) -> Result<IllRequest, IllError> {
+    let request = find_request_mut(records, request_id)?;
+    check_transition(request, IllStatus::Received)?;
+    advance(request, IllStatus::Received, date);
+    Ok(request.clone())
+}
+
+/// The item is back with the lending library. Our own copies go back on
+/// the shelf.
+pub fn return_item(
+    records: &mut Interlibrary,
+    books: &mut [Book],
+    request_id: u32,
+    date: NaiveDate,
+) -> Result<IllRequest, IllError> {
+    let request = find_request(records, request_id)?;
+    check_transition(request, IllStatus::Returned)?;
+
+    if let (IllDirection::Incoming, Some(book_id)) = (request.direction, request.book_id) {
+        change_book_status(
+            books,
+            book_id,
+            ItemStatus::Available,
+            &format!("Returned by partner (ILL {})", request_id),
+            date,
+        )?;
+    }
+
+    let request = find_request_mut(records, request_id)?;
+    advance(request, IllStatus::Returned, date);
+    Ok(request.clone())
+}
+
+pub fn cancel(
+    records: &mut Interlibrary,
+    request_id: u32,
+    date: NaiveDate,
+) -> Result<IllRequest, IllError> {
+    let request = find_request_mut(records, request_id)?;
+    check_transition(request, IllStatus::Cancelled)?;
+    advance(request, IllStatus::Cancelled, date);
+    Ok(request.clone())
+}
+
+pub fn get_open_requests(requests: &[IllRequest]) -> Vec<IllRequest> {
+    requests.iter().filter(|r| r.is_open()).cloned().collect()
+}
+
+/// Items out past the lending library's due date, in either direction.
+pub fn get_overdue_requests(requests: &[IllRequest], as_of: NaiveDate) -> Vec<IllRequest> {
+    requests
+        .iter()
+        .filter(|r| r.is_overdue(as_of))
+        .cloned()
+        .collect()
+}
+
+pub fn partner_name(partners: &[PartnerLibrary], partner_id: u32) -> String {
+    partners
+        .iter()
+        .find(|p| p.id == partner_id)
+        .map(|p| p.name.clone())
+        .unwrap_or_else(|| format!("Partner {}", partner_id))
+}
+
+fn find_request(records: &Interlibrary, request_id: u32) -> Result<&IllRequest, IllError> {
+    records
+        .requests
+        .iter()
+        .find(|r| r.id == request_id)
+        .ok_or(IllError::RequestNotFound)
+}
+
+fn find_request_mut(
+    records: &mut Interlibrary,
+    request_id: u32,
+) -> Result<&mut IllRequest, IllError> {
+    records
+        .requests
+        .iter_mut()
+        .find(|r| r.id == request_id)
+        .ok_or(IllError::RequestNotFound)
+}
+
+fn check_transition(request: &IllRequest, next: IllStatus) -> Result<(), IllError> {
+    if request.status.can_transition_to(next) {
+        Ok(())
+    } else {
+        Err(IllError::InvalidTransition {
+            from: request.status,
+            to: next,
+        })
+    }
+}
+
+fn advance(request: &mut IllRequest, status: IllStatus, date: NaiveDate) {
+    request.status = status;
+    request.history.push(IllStatusChange { status, date });
+}
+
+fn change_book_status(
+    books: &mut [Book],
+    book_id: u32,
+    status: ItemStatus,
+    reason: &str,
+    date: NaiveDate,
+) -> Result<(), IllError> {
+    let book = books
+        .iter_mut()
+        .find(|b| b.id == book_id)
+        .ok_or(IllError::BookNotFound)?;
+    book.transition(status, reason, date)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).expect("Data inválida")
+    }
+
+    fn records() -> Interlibrary {
+        Interlibrary {
+            partners: vec![PartnerLibrary {
+                id: 1,
+                name: "Biblioteca Central".to_string(),
+                contact: String::new(),
+            }],
+            requests: Vec::new(),
+        }
+    }
+
+    fn books() -> Vec<Book> {
+        vec![Book::new(
+            1,
+            "Rust Book".to_string(),
+            "Steve".to_string(),
+            300,
+        )]
+    }
+
+    #[test]
+    fn test_outgoing_request_workflow() {
+        let mut records = records();
+        let mut books = books();
+        let users = vec![User::new(1, "Alice".to_string())];
+        let mut ids = IdAllocator::default();
+
+        let id = open_request(
+            &mut records,
+            &users,
+            &books,
+            &mut ids,
+            IllRequest::outgoing(
+                1,
+                1,
+                "Livro Raro".to_string(),
+                "Autor".to_string(),
+                date(2024, 3, 1),
+            ),
+        )
+        .expect("Falha ao registrar pedido");
+
+        let shipped = ship(
+            &mut records,
+            &mut books,
+            id,
+            date(2024, 3, 4),
+            date(2024, 4, 4),
+        )
+        .expect("Falha ao registrar envio");
+        assert_eq!(shipped.due_date, Some(date(2024, 4, 4)));
+        assert!(receive(&mut records, id, date(2024, 3, 6)).is_ok());
+        assert!(records.requests[0].is_overdue(date(2024, 4, 5)));
+
+        let returned = return_item(&mut records, &mut books, id, date(2024, 4, 2))
+            .expect("Falha ao registrar devolução");
+        assert_eq!(returned.status, IllStatus::Returned);
+        assert_eq!(returned.history.len(), 4);
+        assert!(books[0].is_available());
+    }
+
+    #[test]
+    fn test_incoming_request_takes_copy_off_the_shelf() {
+        let mut records = records();
+        let mut books = books();
+        let mut ids = IdAllocator::default();
+
+        let id = open_request(
+            &mut records,
+            &[],
+            &books,
+            &mut ids,
+            IllRequest::incoming(1, 1, date(2024, 3, 1)),
+        )
+        .expect("Falha ao registrar pedido");
+        assert_eq!(records.requests[0].title, "Rust Book");
+
+        ship(
+            &mut records,
+            &mut books,
+            id,
+            date(2024, 3, 2),
+            date(2024, 3, 30),
+        )
+        .expect("Falha ao registrar envio");
+        assert_eq!(books[0].status, ItemStatus::OnLoan);
+
+        receive(&mut records, id, date(2024, 3, 4)).expect("Falha ao registrar recebimento");
+        return_item(&mut records, &mut books, id, date(2024, 3, 28))
+            .expect("Falha ao registrar devolução");
+        assert!(books[0].is_available());
+    }
+
+    #[test]
+    fn test_open_request_validation() {
+        let mut records = records();
+        let books = books();
+        let mut ids = IdAllocator::default();
+
+        assert!(matches!(
+            open_request(
+                &mut records,
+                &[],
+                &books,
+                &mut ids,
+                IllRequest::incoming(9, 1, date(2024, 3, 1)),
+            ),
+            Err(IllError::PartnerNotFound)
+        ));
+        assert!(matches!(
+            open_request(
+                &mut records,
+                &[],
+                &books,
+                &mut ids,
+                IllRequest::outgoing(1, 1, String::new(), String::new(), date(2024, 3, 1)),
+            ),
+            Err(IllError::UserNotFound)
+        ));
+        assert!(matches!(
+            open_request(
+                &mut records,
+                &[],
+                &books,
+                &mut ids,
+                IllRequest::incoming(1, 9, date(2024, 3, 1)),
+            ),
+            Err(IllError::BookNotFound)
+        ));
+        assert!(records.requests.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_transitions() {
+        let mut records = records();
+        let mut books = books();
+        let mut ids = IdAllocator::default();
+        let id = open_request(
+            &mut records,
+            &[],
+            &books,
+            &mut ids,
+            IllRequest::incoming(1, 1, date(2024, 3, 1)),
+        )
+        .expect("Falha ao registrar pedido");
+
+        assert!(matches!(
+            receive(&mut records, id, date(2024, 3, 2)),
+            Err(IllError::InvalidTransition {
+                from: IllStatus::Requested,
+                to: IllStatus::Received,
+            })
+        ));
+        assert!(cancel(&mut records, id, date(2024, 3, 2)).is_ok());
+        assert!(matches!(
+            ship(
+                &mut records,
+                &mut books,
+                id,
+                date(2024, 3, 3),
+                date(2024, 4, 1)
+            ),
+            Err(IllError::InvalidTransition { .. })
+        ));
+        assert!(books[0].is_available());
+    }
+
+    #[test]
+    fn test_add_partner_rejects_duplicates() {
+        let mut partners = Vec::new();
+        let mut ids = IdAllocator::default();
+        assert_eq!(
+            add_partner(&mut partners, &mut ids, "Norte".to_string(), String::new()).ok(),
+            Some(1)
+        );
+        assert!(matches!(
+            add_partner(&mut partners, &mut ids, "norte".to_string(), String::new()),
+            Err(IllError::PartnerAlreadyExists(_))
+        ));
+    }
+
+    #[test]
+    fn test_save_and_read_interlibrary() {
+        let temp_file = NamedTempFile::new().expect("Não foi possível criar arquivo temporário");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let records = records();
+        assert!(save_to_json(file_path, &records).is_ok());
+
+        let loaded = read_from_json(file_path).expect("Falha ao ler registros");
+        assert_eq!(loaded.partners.len(), 1);
+        assert!(loaded.requests.is_empty());
+    }
+
+    #[test]
+    fn test_read_from_malformed_json() {
+        let mut temp_file =
+            NamedTempFile::new().expect("Não foi possível criar arquivo temporário");
+        writeln!(temp_file, "isto não é um JSON válido")
+            .expect("Falha ao escrever no arquivo temporário");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let result = read_from_json(file_path);
+        assert!(matches!(result, Err(IllError::JsonError(_))));
+    }
+}
@@ -1,57 +1,283 @@
 use super::service;
 use crate::library::books::models::Book;
-use crate::library::loans::models::{Loan, LoanError};
+use crate::library::calendar::models::LibraryCalendar;
+use crate::library::fines::service as fines_service;
+use crate::library::ids::models::{IdAllocator, IdError};
+use crate::library::loans::models::{
+    CheckoutReceipt, CheckoutRequest, HistoryFilter, Loan, LoanError, LoanHistoryEntry,
+    OverdueLoan, RecallNotice, RecallRules, ReturnReceipt,
+};
+use crate::library::policies::models::LoanPolicy;
 use crate::library::users::models::User;
+use chrono::{Local, NaiveDate};
 
 pub(crate) fn save_loans(loans: &[Loan]) -> Result<(), LoanError> {
     service::save_to_json("loans.json", loans)
 }
 
-pub(crate) fn read_loans() -> Result<Vec<Loan>, LoanError> {
-    service::read_from_json("loans.json")
+/// Loans `read_loans` could not read are moved here so saving `loans.json`
+/// does not lose them.
+pub(crate) const SET_ASIDE_FILE: &str = "loans_set_aside.json";
+
+/// Reads the loans and returns how many records had to be set aside.
+pub(crate) fn read_loans() -> Result<(Vec<Loan>, usize), LoanError> {
+    let loaded = service::read_from_json("loans.json")?;
+    if !loaded.set_aside.is_empty() {
+        service::set_aside(SET_ASIDE_FILE, &loaded.set_aside)?;
+    }
+    Ok((loaded.loans, loaded.set_aside.len()))
+}
+
+pub(crate) fn parse_date(input: &str) -> Result<NaiveDate, LoanError> {
+    service::parse_date(input, Local::now().date_naive())
 }
 
 pub(crate) fn add_loan(
     loans: &mut Vec<Loan>,
     users: &[User],
     books: &mut [Book],
-    user_id: u32,
-    book_id: u32,
-    loan_date: String,
+    ids: &mut IdAllocator,
+    request: CheckoutRequest,
+) -> Result<Loan, LoanError> {
+    service::add_loan(loans, users, books, ids, request)
+}
+
+pub(crate) fn check_checkout(
+    loans: &[Loan],
+    users: &[User],
+    books: &[Book],
+    request: &CheckoutRequest,
+    pending: &[u32],
 ) -> Result<(), LoanError> {
-    service::add_loan(loans, users, books, user_id, book_id, loan_date)
+    service::check_checkout(loans, users, books, request, pending)
 }
 
-pub(crate) fn return_loan(
-    loans: &mut Vec<Loan>,
+pub(crate) fn check_patron(
+    user: &User,
+    date: NaiveDate,
+    balance_cents: i64,
+    max_balance_cents: i64,
+) -> Result<(), LoanError> {
+    service::check_patron(user, date, balance_cents, max_balance_cents)
+}
+
+pub(crate) fn assign_missing_ids(loans: &mut [Loan], ids: &mut IdAllocator) -> Result<(), IdError> {
+    service::assign_missing_ids(loans, ids)
+}
+
+pub(crate) fn check_in(
+    loans: &mut [Loan],
     books: &mut [Book],
     book_id: u32,
-    return_date: String,
-) -> Result<(), LoanError> {
-    service::return_loan(loans, books, book_id, return_date)
+    return_date: NaiveDate,
+    condition_note: Option<String>,
+) -> Result<Loan, LoanError> {
+    service::check_in(loans, books, book_id, return_date, condition_note)
+}
+
+pub(crate) fn return_damaged(
+    loans: &mut [Loan],
+    books: &mut [Book],
+    book_id: u32,
+    return_date: NaiveDate,
+) -> Result<Loan, LoanError> {
+    service::return_damaged(loans, books, book_id, return_date)
 }
 
+pub(crate) fn declare_lost(
+    loans: &mut [Loan],
+    books: &mut [Book],
+    book_id: u32,
+    date: NaiveDate,
+) -> Result<Loan, LoanError> {
+    service::declare_lost(loans, books, book_id, date)
+}
+
+pub(crate) fn return_found_item(
+    loans: &mut [Loan],
+    books: &mut [Book],
+    book_id: u32,
+    date: NaiveDate,
+) -> Result<Loan, LoanError> {
+    service::return_found_item(loans, books, book_id, date)
+}
+
+pub(crate) fn renew_loan(
+    loans: &mut [Loan],
+    loan_id: u32,
+    date: NaiveDate,
+    policy: &LoanPolicy,
+    pending_holds: usize,
+    calendar: &LibraryCalendar,
+) -> Result<Loan, LoanError> {
+    service::renew_loan(loans, loan_id, date, policy, pending_holds, calendar)
+}
+
+pub(crate) fn recall_loan(
+    loans: &mut [Loan],
+    loan_id: u32,
+    date: NaiveDate,
+    rules: &RecallRules,
+    calendar: &LibraryCalendar,
+    hold_id: Option<u32>,
+) -> Result<Loan, LoanError> {
+    service::recall_loan(loans, loan_id, date, rules, calendar, hold_id)
+}
+
+#[allow(dead_code)]
 pub(crate) fn delete_loan(loans: &mut Vec<Loan>, book_id: u32) -> Result<(), LoanError> {
     service::delete_loan(loans, book_id)
 }
 
 pub(crate) fn print_loans(loans: &[Loan]) {
     for loan in loans {
+        println!("Loan ID: {}", loan.id);
         println!("User ID: {}", loan.user_id);
         println!("Book ID: {}", loan.book_id);
         println!("Loan Date: {}", loan.loan_date);
+        println!("Due Date: {}", loan.due_date);
+        if !loan.renewals.is_empty() {
+            println!("Renewals: {}", loan.renewals.len());
+        }
         match &loan.return_date {
             Some(date) => println!("Return Date: {}", date),
             None => println!("Return Date: Not returned yet"),
         }
+        if let Some(outcome) = loan.outcome {
+            println!("Outcome: {}", outcome);
+        }
+        if let Some(recall) = &loan.recall {
+            println!("Recalled: {}", recall.date);
+        }
+        for check in &loan.checklist {
+            let state = if check.ok { "OK" } else { "Problem" };
+            match &check.note {
+                Some(note) => println!("Checklist: {} - {} ({})", check.item, state, note),
+                None => println!("Checklist: {} - {}", check.item, state),
+            }
+        }
         println!();
     }
 }
 
+pub(crate) fn print_return_receipt(receipt: &ReturnReceipt) {
+    let loan = &receipt.loan;
+
+    println!("--- Return Receipt ---");
+    println!("Book: {} (ID {})", receipt.title, loan.book_id);
+    println!("Borrower: {} (ID {})", receipt.borrower, loan.user_id);
+    println!("Loan Date: {}", loan.loan_date);
+    println!("Due Date: {}", loan.due_date);
+    if let Some(date) = loan.return_date {
+        println!("Returned: {}", date);
+    }
+    if receipt.days_overdue > 0 {
+        println!("Late by {} day(s)", receipt.days_overdue);
+    } else {
+        println!("Returned on time");
+    }
+    if receipt.fine_cents > 0 {
+        println!("Fine: {}", fines_service::format_amount(receipt.fine_cents));
+    }
+    if let Some(note) = &loan.condition_note {
+        println!("Condition: {}", note);
+    }
+    if let Some(user_id) = receipt.held_for {
+        println!("Hold waiting: place on hold shelf for user {}", user_id);
+    }
+    if let Some(branch) = &receipt.transfer_to {
+        println!("Send to: {}", branch);
+    }
+}
+
+pub(crate) fn print_recall_notice(notice: &RecallNotice) {
+    let loan = &notice.loan;
+
+    println!("--- Recall Notice ---");
+    println!("To: {} (ID {})", notice.borrower, loan.user_id);
+    println!(
+        "The item \"{}\" (ID {}) is needed by another patron.",
+        notice.title, loan.book_id
+    );
+    if let Some(recall) = &loan.recall {
+        println!("Previous Due Date: {}", recall.previous_due_date);
+    }
+    println!("Please return it by: {}", loan.due_date);
+}
+
+pub(crate) fn print_checkout_receipt(receipt: &CheckoutReceipt) {
+    println!("--- Checkout Receipt ---");
+    println!("Borrower: {} (ID {})", receipt.borrower, receipt.user_id);
+    println!("Date: {}", receipt.date);
+    for item in &receipt.items {
+        println!(
+            "{} (ID {}) - Due {}",
+            item.title, item.loan.book_id, item.loan.due_date
+        );
+    }
+    println!("Items: {}", receipt.items.len());
+}
+
 pub(crate) fn get_active_loans(loans: &[Loan]) -> Vec<Loan> {
     service::get_active_loans(loans)
 }
 
+pub(crate) fn get_overdue_loans(loans: &[Loan], as_of: NaiveDate) -> Vec<OverdueLoan> {
+    service::get_overdue_loans(loans, as_of)
+}
+
+/// Overdue loans grouped by patron, patrons in id order.
+pub(crate) fn print_overdue_report(overdue: &[OverdueLoan], users: &[User], books: &[Book]) {
+    let mut user_ids: Vec<u32> = overdue.iter().map(|o| o.loan.user_id).collect();
+    user_ids.sort_unstable();
+    user_ids.dedup();
+
+    for user_id in user_ids {
+        let name = users
+            .iter()
+            .find(|u| u.id == user_id)
+            .map(|u| u.name.as_str())
+            .unwrap_or("Unknown user");
+        println!("{} (ID {})", name, user_id);
+
+        for entry in overdue.iter().filter(|o| o.loan.user_id == user_id) {
+            let title = books
+                .iter()
+                .find(|b| b.id == entry.loan.book_id)
+                .map(|b| b.title.as_str())
+                .unwrap_or("Unknown book");
+            println!(
+                "  Loan {}: {} (Book ID {}), due {}, {} day(s) overdue",
+                entry.loan.id, title, entry.loan.book_id, entry.loan.due_date, entry.days_overdue
+            );
+        }
+        println!();
+    }
+}
+
 pub(crate) fn get_loans_by_user(loans: &[Loan], user_id: u32) -> Vec<Loan> {
     service::get_loans_by_user(loans, user_id)
 }
+
+pub(crate) fn get_loans_by_book(loans: &[Loan], book_id: u32) -> Vec<Loan> {
+    service::get_loans_by_book(loans, book_id)
+}
+
+pub(crate) fn filter_history(loans: Vec<Loan>, filter: &HistoryFilter) -> Vec<Loan> {
+    service::filter_history(loans, filter)
+}
+
+pub(crate) fn print_history(entries: &[LoanHistoryEntry]) {
+    for entry in entries {
+        let loan = &entry.loan;
+        let status = match (loan.return_date, loan.outcome) {
+            (None, _) => format!("Out, due {}", loan.due_date),
+            (Some(date), Some(outcome)) => format!("{} {}", outcome, date),
+            (Some(date), None) => format!("Returned {}", date),
+        };
+        println!(
+            "{} | {} (ID {}) | {} (ID {}) | {}",
+            loan.loan_date, entry.title, loan.book_id, entry.borrower, loan.user_id, status
+        );
+    }
+}
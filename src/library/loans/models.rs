@@ -1,35 +1,324 @@
+use crate::library::books::models::{BookError, MaterialFormat};
+use crate::library::branches::models::BranchError;
+use crate::library::calendar::models::LibraryCalendar;
+use crate::library::fines::models::FineError;
+use crate::library::fines::service as fines_service;
+use crate::library::holds::models::HoldError;
+use crate::library::ids::models::IdError;
+use crate::library::policies::models::LoanPolicy;
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::fmt;
 use std::io;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Loan {
+    #[serde(default)]
+    pub id: u32,
     pub user_id: u32,
     pub book_id: u32,
-    pub loan_date: String,
-    pub return_date: Option<String>,
+    pub loan_date: NaiveDate,
+    pub due_date: NaiveDate,
+    /// The date the loan was closed, whatever its outcome.
+    pub return_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub renewals: Vec<Renewal>,
+    #[serde(default)]
+    pub outcome: Option<LoanOutcome>,
+    /// Condition noted at check-in, e.g. "water damage".
+    #[serde(default)]
+    pub condition_note: Option<String>,
+    #[serde(default)]
+    pub recall: Option<Recall>,
+    /// Condition of equipment and its accessories when it went out.
+    #[serde(default)]
+    pub checklist: Vec<ConditionCheck>,
+    /// Branch the item was checked out at.
+    #[serde(default)]
+    pub branch_id: Option<u32>,
+    /// Branch the item was returned to.
+    #[serde(default)]
+    pub return_branch_id: Option<u32>,
+}
+
+/// One line of the condition checklist filled in when equipment is lent.
+/// What was read from a loans file. Records that could not be read as loans,
+/// e.g. a legacy loan whose date does not parse, are kept as stored instead
+/// of failing the whole file.
+#[derive(Debug, Default)]
+pub struct LoanFile {
+    pub loans: Vec<Loan>,
+    pub set_aside: Vec<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConditionCheck {
+    pub item: String,
+    /// Present and in working order.
+    pub ok: bool,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// The library asked for the item back before its original due date.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recall {
+    pub date: NaiveDate,
+    pub previous_due_date: NaiveDate,
+    /// The hold that triggered the recall, if any.
+    #[serde(default)]
+    pub hold_id: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecallRules {
+    /// Days the borrower gets to bring a recalled item back.
+    pub return_within_days: u32,
+    /// A recall never makes a loan shorter than this many days.
+    pub minimum_loan_days: u32,
+    /// Overdue recalled items are fined at this multiple of the daily rate.
+    pub fine_multiplier: u32,
+}
+
+impl Default for RecallRules {
+    fn default() -> Self {
+        Self {
+            return_within_days: 7,
+            minimum_loan_days: 14,
+            fine_multiplier: 2,
+        }
+    }
+}
+
+/// Sent to the borrower of a recalled item.
+#[derive(Debug, Clone)]
+pub struct RecallNotice {
+    pub loan: Loan,
+    pub borrower: String,
+    pub title: String,
+}
+
+/// How a closed loan ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LoanOutcome {
+    Returned,
+    ReturnedDamaged,
+    Lost,
+    /// Declared lost, then found and brought back.
+    LostAndFound,
+}
+
+impl fmt::Display for LoanOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoanOutcome::Returned => write!(f, "Returned"),
+            LoanOutcome::ReturnedDamaged => write!(f, "Returned damaged"),
+            LoanOutcome::Lost => write!(f, "Lost"),
+            LoanOutcome::LostAndFound => write!(f, "Lost, later found"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Renewal {
+    pub date: NaiveDate,
+    pub previous_due_date: NaiveDate,
+    pub new_due_date: NaiveDate,
 }
 
 impl Loan {
-    pub fn new(user_id: u32, book_id: u32, loan_date: String) -> Self {
+    pub fn new(
+        id: u32,
+        user_id: u32,
+        book_id: u32,
+        loan_date: NaiveDate,
+        due_date: NaiveDate,
+    ) -> Self {
         Self {
+            id,
             user_id,
             book_id,
             loan_date,
+            due_date,
             return_date: None,
+            renewals: Vec::new(),
+            outcome: None,
+            condition_note: None,
+            recall: None,
+            checklist: Vec::new(),
+            branch_id: None,
+            return_branch_id: None,
         }
     }
+
+    pub fn is_active(&self) -> bool {
+        self.return_date.is_none()
+    }
+
+    pub fn is_recalled(&self) -> bool {
+        self.recall.is_some()
+    }
+
+    /// Days past the due date as of `date`, or as of the return for returned loans.
+    pub fn days_overdue(&self, date: NaiveDate) -> i64 {
+        let end = self.return_date.unwrap_or(date);
+        (end - self.due_date).num_days().max(0)
+    }
+}
+
+/// Items a patron is borrowing at the desk; they are checked out together
+/// when the session is committed.
+#[derive(Debug, Clone)]
+pub struct CheckoutSession {
+    pub user_id: u32,
+    pub date: NaiveDate,
+    pub book_ids: Vec<u32>,
+    pub loan_limit: u32,
+    /// Loans the patron already had when the session started.
+    pub active_loans: usize,
+}
+
+impl CheckoutSession {
+    /// Items the patron may still add before reaching their loan limit.
+    pub fn remaining(&self) -> usize {
+        (self.loan_limit as usize).saturating_sub(self.active_loans + self.book_ids.len())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckoutReceiptItem {
+    pub loan: Loan,
+    pub title: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckoutReceipt {
+    pub user_id: u32,
+    pub borrower: String,
+    pub date: NaiveDate,
+    pub items: Vec<CheckoutReceiptItem>,
+}
+
+/// Everything the desk reports back after a check-in.
+#[derive(Debug, Clone)]
+pub struct ReturnReceipt {
+    pub loan: Loan,
+    pub borrower: String,
+    pub title: String,
+    pub days_overdue: i64,
+    pub fine_cents: i64,
+    /// The patron the copy was put on the hold shelf for, if anyone was waiting.
+    pub held_for: Option<u32>,
+    /// The branch the copy is being sent back to, if it was returned away
+    /// from its home branch.
+    pub transfer_to: Option<String>,
+}
+
+/// Narrows a loan history; the default keeps every loan.
+#[derive(Debug, Clone, Default)]
+pub struct HistoryFilter {
+    pub active_only: bool,
+    /// Only loans made on or after this date.
+    pub from: Option<NaiveDate>,
+    /// Only loans made on or before this date.
+    pub to: Option<NaiveDate>,
+}
+
+impl HistoryFilter {
+    pub fn matches(&self, loan: &Loan) -> bool {
+        (!self.active_only || loan.is_active())
+            && self.from.is_none_or(|from| loan.loan_date >= from)
+            && self.to.is_none_or(|to| loan.loan_date <= to)
+    }
+}
+
+/// A loan joined with the title of the item and the name of the borrower.
+#[derive(Debug, Clone)]
+pub struct LoanHistoryEntry {
+    pub loan: Loan,
+    pub title: String,
+    pub borrower: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct OverdueLoan {
+    pub loan: Loan,
+    pub days_overdue: i64,
+}
+
+/// A checkout as resolved by `Library`: who borrows what, and under which policy.
+#[derive(Debug, Clone)]
+pub struct CheckoutRequest {
+    pub user_id: u32,
+    pub book_id: u32,
+    pub loan_date: NaiveDate,
+    pub policy: LoanPolicy,
+    pub balance_cents: i64,
+    /// Checkouts are refused while `balance_cents` is above this amount.
+    pub max_balance_cents: i64,
+    /// The patron is collecting an item held for them on the hold shelf.
+    pub collecting_hold: bool,
+    /// Due dates are moved off days the library is closed.
+    pub calendar: LibraryCalendar,
+    /// Required for equipment: one check per item on its checklist.
+    pub checklist: Vec<ConditionCheck>,
+    /// Branch the checkout happens at; the item must be shelved there.
+    pub branch_id: Option<u32>,
 }
 
 #[derive(Debug)]
 pub enum LoanError {
     IoError(io::Error),
     JsonError(serde_json::Error),
+    BookError(BookError),
+    FineError(FineError),
+    BranchError(BranchError),
+    /// Boxed because a `HoldError` can itself carry a `LoanError`.
+    HoldError(Box<HoldError>),
     LoanNotFound,
     LoanAlreadyExists,
     BookNotAvailable,
+    ReservedForAnotherPatron,
+    NothingToCheckOut,
+    LoanRecalled,
     UserNotFound,
     BookNotFound,
+    MembershipExpired,
+    PatronSuspended,
+    PatronBlocked,
+    FormatNotAllowed(MaterialFormat),
+    ChecklistIncomplete(String),
+    /// The item is shelved at the branch with this id.
+    ItemAtAnotherBranch(u32),
+    LoanLimitReached {
+        limit: u32,
+        current: usize,
+    },
+    /// The patron's household already has its limit of active loans.
+    HouseholdLimitReached {
+        limit: u32,
+        current: usize,
+    },
+    OutstandingFines {
+        balance_cents: i64,
+        threshold_cents: i64,
+    },
+    RenewalLimitReached {
+        limit: u32,
+    },
+    ItemHasPendingHolds,
+    OverdueTooLong {
+        days_overdue: i64,
+        limit: u32,
+    },
+    InvalidDate(String),
+    ReturnBeforeLoan {
+        loan_date: NaiveDate,
+        return_date: NaiveDate,
+    },
+    IdError(IdError),
 }
 
 impl fmt::Display for LoanError {
@@ -37,11 +326,80 @@ impl fmt::Display for LoanError {
         match self {
             LoanError::IoError(err) => write!(f, "IO Error: {}", err),
             LoanError::JsonError(err) => write!(f, "JSON Error: {}", err),
+            LoanError::IdError(err) => write!(f, "{}", err),
+            LoanError::BookError(err) => write!(f, "Book Error: {}", err),
+            LoanError::FineError(err) => write!(f, "Fine Error: {}", err),
+            LoanError::BranchError(err) => write!(f, "Branch Error: {}", err),
+            LoanError::HoldError(err) => write!(f, "Hold Error: {}", err),
             LoanError::LoanNotFound => write!(f, "Loan not found"),
             LoanError::LoanAlreadyExists => write!(f, "Loan already exists"),
             LoanError::BookNotAvailable => write!(f, "Book is not available"),
+            LoanError::ReservedForAnotherPatron => {
+                write!(f, "Book is on the hold shelf for another patron")
+            }
+            LoanError::NothingToCheckOut => write!(f, "No items to check out"),
+            LoanError::LoanRecalled => write!(f, "Loan has been recalled"),
             LoanError::UserNotFound => write!(f, "User not found"),
             LoanError::BookNotFound => write!(f, "Book not found"),
+            LoanError::MembershipExpired => write!(f, "Patron membership has expired"),
+            LoanError::PatronSuspended => write!(f, "Patron account is suspended"),
+            LoanError::PatronBlocked => write!(f, "Patron account is blocked"),
+            LoanError::FormatNotAllowed(format) => {
+                write!(f, "Patron category may not borrow {} items", format)
+            }
+            LoanError::ChecklistIncomplete(item) => {
+                write!(f, "Condition checklist is missing '{}'", item)
+            }
+            LoanError::ItemAtAnotherBranch(branch_id) => {
+                write!(f, "Item is at branch {}", branch_id)
+            }
+            LoanError::LoanLimitReached { limit, current } => write!(
+                f,
+                "Patron has {} active loans and the limit is {}",
+                current, limit
+            ),
+            LoanError::HouseholdLimitReached { limit, current } => write!(
+                f,
+                "Household has {} active loans and the limit is {}",
+                current, limit
+            ),
+            LoanError::OutstandingFines {
+                balance_cents,
+                threshold_cents,
+            } => write!(
+                f,
+                "Patron owes {}, above the limit of {}",
+                fines_service::format_amount(*balance_cents),
+                fines_service::format_amount(*threshold_cents)
+            ),
+            LoanError::RenewalLimitReached { limit } => {
+                write!(f, "Loan has already been renewed {} time(s)", limit)
+            }
+            LoanError::ItemHasPendingHolds => {
+                write!(
+                    f,
+                    "Item cannot be renewed because other patrons are waiting for it"
+                )
+            }
+            LoanError::OverdueTooLong {
+                days_overdue,
+                limit,
+            } => write!(
+                f,
+                "Loan is {} day(s) overdue; renewals are only allowed up to {} day(s)",
+                days_overdue, limit
+            ),
+            LoanError::InvalidDate(input) => {
+                write!(f, "Invalid date '{}', expected YYYY-MM-DD", input)
+            }
+            LoanError::ReturnBeforeLoan {
+                loan_date,
+                return_date,
+            } => write!(
+                f,
+                "Return date {} is before the loan date {}",
+                return_date, loan_date
+            ),
         }
     }
 }
@@ -59,3 +417,33 @@ impl From<serde_json::Error> for LoanError {
         LoanError::JsonError(err)
     }
 }
+
+impl From<IdError> for LoanError {
+    fn from(err: IdError) -> Self {
+        LoanError::IdError(err)
+    }
+}
+
+impl From<BookError> for LoanError {
+    fn from(err: BookError) -> Self {
+        LoanError::BookError(err)
+    }
+}
+
+impl From<FineError> for LoanError {
+    fn from(err: FineError) -> Self {
+        LoanError::FineError(err)
+    }
+}
+
+impl From<HoldError> for LoanError {
+    fn from(err: HoldError) -> Self {
+        LoanError::HoldError(Box::new(err))
+    }
+}
+
+impl From<BranchError> for LoanError {
+    fn from(err: BranchError) -> Self {
+        LoanError::BranchError(err)
+    }
+}
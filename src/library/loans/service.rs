@@ -1,24 +1,95 @@
-use super::models::{Loan, LoanError};
-use crate::library::books::models::Book;
-use crate::library::users::models::User;
+use super::models::{
+    CheckoutRequest, HistoryFilter, Loan, LoanError, LoanFile, LoanOutcome, OverdueLoan, Recall,
+    RecallRules, Renewal,
+};
+use crate::library::books::models::{Book, ItemStatus};
+use crate::library::calendar::models::LibraryCalendar;
+use crate::library::calendar::service as calendar_service;
+use crate::library::ids::models::{IdAllocator, IdError};
+use crate::library::policies::models::LoanPolicy;
+use crate::library::users::models::{AccountStatus, User};
+use chrono::{Days, NaiveDate};
+use serde_json::Value;
 use std::fs::File;
 use std::io::ErrorKind;
 
-pub fn read_from_json(file_path: &str) -> Result<Vec<Loan>, LoanError> {
+/// Loan period assumed for loans saved before due dates were recorded.
+pub const LEGACY_LOAN_PERIOD_DAYS: u64 = 14;
+
+pub fn read_from_json(file_path: &str) -> Result<LoanFile, LoanError> {
     let file = match File::open(file_path) {
         Ok(file) => file,
         Err(err) => {
             if err.kind() == ErrorKind::NotFound {
-                return Ok(Vec::new());
+                return Ok(LoanFile::default());
             } else {
                 return Err(LoanError::IoError(err));
             }
         }
     };
 
-    let loans: Vec<Loan> = serde_json::from_reader(file).map_err(LoanError::JsonError)?;
+    let records: Vec<Value> = serde_json::from_reader(file).map_err(LoanError::JsonError)?;
+
+    let mut loaded = LoanFile::default();
+    for record in records {
+        let mut migrated = record.clone();
+        migrate_legacy_fields(&mut migrated);
+        match serde_json::from_value(migrated) {
+            Ok(loan) => loaded.loans.push(loan),
+            Err(_) => loaded.set_aside.push(record),
+        }
+    }
+
+    Ok(loaded)
+}
+
+/// Adds `records` to the set-aside file at `file_path`, skipping any that
+/// are already there.
+pub fn set_aside(file_path: &str, records: &[Value]) -> Result<(), LoanError> {
+    let mut kept: Vec<Value> = match File::open(file_path) {
+        Ok(file) => serde_json::from_reader(file)?,
+        Err(err) if err.kind() == ErrorKind::NotFound => Vec::new(),
+        Err(err) => return Err(LoanError::IoError(err)),
+    };
+    for record in records {
+        if !kept.contains(record) {
+            kept.push(record.clone());
+        }
+    }
 
-    Ok(loans)
+    let file = File::create(file_path)?;
+    serde_json::to_writer_pretty(file, &kept)?;
+    Ok(())
+}
+
+/// Older files have no `due_date`; derive it from the loan date. Loans
+/// closed before outcomes were recorded were plain returns.
+fn migrate_legacy_fields(record: &mut Value) {
+    if let Some(fields) = record.as_object_mut() {
+        let returned = fields
+            .get("return_date")
+            .is_some_and(|date| !date.is_null());
+        if returned && !fields.contains_key("outcome") {
+            fields.insert(
+                "outcome".to_string(),
+                serde_json::json!(LoanOutcome::Returned),
+            );
+        }
+
+        if fields.contains_key("due_date") {
+            return;
+        }
+
+        let due_date = fields
+            .get("loan_date")
+            .and_then(Value::as_str)
+            .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+            .and_then(|date| date.checked_add_days(Days::new(LEGACY_LOAN_PERIOD_DAYS)));
+
+        if let Some(due_date) = due_date {
+            fields.insert("due_date".to_string(), serde_json::json!(due_date));
+        }
+    }
 }
 
 pub fn save_to_json(file_path: &str, loans: &[Loan]) -> Result<(), LoanError> {
@@ -27,63 +98,355 @@ pub fn save_to_json(file_path: &str, loans: &[Loan]) -> Result<(), LoanError> {
     Ok(())
 }
 
-pub fn add_loan(
-    loans: &mut Vec<Loan>,
+/// Parses an operator-typed date; an empty input means `today`.
+pub fn parse_date(input: &str, today: NaiveDate) -> Result<NaiveDate, LoanError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(today);
+    }
+
+    NaiveDate::parse_from_str(input, "%Y-%m-%d")
+        .map_err(|_| LoanError::InvalidDate(input.to_string()))
+}
+
+/// Refuses patrons who may not borrow at all on `date`, whatever the item.
+pub fn check_patron(
+    user: &User,
+    date: NaiveDate,
+    balance_cents: i64,
+    max_balance_cents: i64,
+) -> Result<(), LoanError> {
+    match user.status_on(date) {
+        AccountStatus::Active => {}
+        AccountStatus::Expired => return Err(LoanError::MembershipExpired),
+        AccountStatus::Suspended => return Err(LoanError::PatronSuspended),
+        AccountStatus::Blocked => return Err(LoanError::PatronBlocked),
+    }
+
+    if balance_cents > max_balance_cents {
+        return Err(LoanError::OutstandingFines {
+            balance_cents,
+            threshold_cents: max_balance_cents,
+        });
+    }
+    Ok(())
+}
+
+/// Checks every rule `add_loan` enforces without changing anything. Items in
+/// `pending` are already set aside for the same patron in an unfinished
+/// checkout: they count towards the loan limit and cannot be taken twice.
+pub fn check_checkout(
+    loans: &[Loan],
     users: &[User],
-    books: &mut [Book],
-    user_id: u32,
-    book_id: u32,
-    loan_date: String,
+    books: &[Book],
+    request: &CheckoutRequest,
+    pending: &[u32],
 ) -> Result<(), LoanError> {
-    if !users.iter().any(|u| u.id == user_id) {
-        return Err(LoanError::UserNotFound);
+    let user = users
+        .iter()
+        .find(|u| u.id == request.user_id)
+        .ok_or(LoanError::UserNotFound)?;
+
+    check_patron(
+        user,
+        request.loan_date,
+        request.balance_cents,
+        request.max_balance_cents,
+    )?;
+
+    let limit = user.loan_limit(&request.policy);
+    let current = get_loans_by_user(loans, request.user_id)
+        .iter()
+        .filter(|l| l.is_active())
+        .count()
+        + pending.len();
+    if current >= limit as usize {
+        return Err(LoanError::LoanLimitReached { limit, current });
     }
 
-    match books.iter_mut().find(|b| b.id == book_id) {
-        Some(book) => {
-            if book.is_borrowed {
-                return Err(LoanError::BookNotAvailable);
-            } else {
-                book.is_borrowed = true;
-            }
+    let book = books
+        .iter()
+        .find(|b| b.id == request.book_id)
+        .ok_or(LoanError::BookNotFound)?;
+    let on_hold_shelf = book.status == ItemStatus::OnHoldShelf;
+    if pending.contains(&book.id) {
+        return Err(LoanError::BookNotAvailable);
+    } else if on_hold_shelf && !request.collecting_hold {
+        return Err(LoanError::ReservedForAnotherPatron);
+    } else if !book.is_available() && !on_hold_shelf {
+        return Err(LoanError::BookNotAvailable);
+    } else if !request.policy.allows(book.format()) {
+        return Err(LoanError::FormatNotAllowed(book.format()));
+    }
+
+    if let (Some(desk), Some(shelved)) = (request.branch_id, book.current_branch) {
+        if desk != shelved {
+            return Err(LoanError::ItemAtAnotherBranch(shelved));
         }
-        None => return Err(LoanError::BookNotFound),
+    }
+
+    if let Some(missing) = book
+        .details
+        .checklist_items()
+        .into_iter()
+        .find(|item| !request.checklist.iter().any(|check| &check.item == item))
+    {
+        return Err(LoanError::ChecklistIncomplete(missing));
     }
 
     if loans
         .iter()
-        .any(|l| l.book_id == book_id && l.return_date.is_none())
+        .any(|l| l.book_id == request.book_id && l.return_date.is_none())
     {
         return Err(LoanError::LoanAlreadyExists);
     }
 
-    let loan = Loan::new(user_id, book_id, loan_date);
-    loans.push(loan);
     Ok(())
 }
 
-pub fn return_loan(
+pub fn add_loan(
     loans: &mut Vec<Loan>,
+    users: &[User],
     books: &mut [Book],
-    book_id: u32,
-    return_date: String,
-) -> Result<(), LoanError> {
-    match loans
+    ids: &mut IdAllocator,
+    request: CheckoutRequest,
+) -> Result<Loan, LoanError> {
+    check_checkout(loans, users, books, &request, &[])?;
+
+    let CheckoutRequest {
+        user_id,
+        book_id,
+        loan_date,
+        policy,
+        calendar,
+        checklist,
+        branch_id,
+        ..
+    } = request;
+
+    let due_date = calendar_service::due_date(&calendar, loan_date, policy.loan_period_days)
+        .ok_or_else(|| LoanError::InvalidDate(loan_date.to_string()))?;
+
+    let book = books
         .iter_mut()
-        .find(|l| l.book_id == book_id && l.return_date.is_none())
-    {
-        Some(loan) => {
-            loan.return_date = Some(return_date);
+        .find(|b| b.id == book_id)
+        .ok_or(LoanError::BookNotFound)?;
+    book.transition(
+        ItemStatus::OnLoan,
+        &format!("Checked out to user {}", user_id),
+        loan_date,
+    )?;
+    let loan = Loan {
+        checklist,
+        branch_id,
+        ..Loan::new(ids.allocate()?, user_id, book_id, loan_date, due_date)
+    };
+    loans.push(loan.clone());
+    Ok(loan)
+}
 
-            if let Some(book) = books.iter_mut().find(|b| b.id == book_id) {
-                book.is_borrowed = false;
-            }
-            Ok(())
+/// Loans saved before loans had ids are read back with id 0.
+pub fn assign_missing_ids(loans: &mut [Loan], ids: &mut IdAllocator) -> Result<(), IdError> {
+    for loan in loans.iter_mut().filter(|l| l.id == 0) {
+        loan.id = ids.allocate()?;
+    }
+    Ok(())
+}
+
+/// Returns the copy, keeping any condition noted at the desk on the loan.
+pub fn check_in(
+    loans: &mut [Loan],
+    books: &mut [Book],
+    book_id: u32,
+    return_date: NaiveDate,
+    condition_note: Option<String>,
+) -> Result<Loan, LoanError> {
+    let mut loan = close_loan(loans, books, book_id, return_date, LoanOutcome::Returned)?;
+
+    if let Some(note) = condition_note.filter(|n| !n.trim().is_empty()) {
+        if let Some(stored) = loans.iter_mut().find(|l| l.id == loan.id) {
+            stored.condition_note = Some(note.clone());
         }
-        None => Err(LoanError::LoanNotFound),
+        loan.condition_note = Some(note);
+    }
+    Ok(loan)
+}
+
+pub fn return_damaged(
+    loans: &mut [Loan],
+    books: &mut [Book],
+    book_id: u32,
+    return_date: NaiveDate,
+) -> Result<Loan, LoanError> {
+    close_loan(
+        loans,
+        books,
+        book_id,
+        return_date,
+        LoanOutcome::ReturnedDamaged,
+    )
+}
+
+pub fn declare_lost(
+    loans: &mut [Loan],
+    books: &mut [Book],
+    book_id: u32,
+    date: NaiveDate,
+) -> Result<Loan, LoanError> {
+    close_loan(loans, books, book_id, date, LoanOutcome::Lost)
+}
+
+/// Closes the active loan of `book_id` and moves the copy to the status
+/// matching `outcome`.
+fn close_loan(
+    loans: &mut [Loan],
+    books: &mut [Book],
+    book_id: u32,
+    date: NaiveDate,
+    outcome: LoanOutcome,
+) -> Result<Loan, LoanError> {
+    let loan = loans
+        .iter_mut()
+        .find(|l| l.book_id == book_id && l.is_active())
+        .ok_or(LoanError::LoanNotFound)?;
+
+    if date < loan.loan_date {
+        return Err(LoanError::ReturnBeforeLoan {
+            loan_date: loan.loan_date,
+            return_date: date,
+        });
+    }
+
+    let (status, reason) = match outcome {
+        LoanOutcome::Returned | LoanOutcome::LostAndFound => (ItemStatus::Available, "Returned"),
+        LoanOutcome::ReturnedDamaged => (ItemStatus::Damaged, "Returned damaged"),
+        LoanOutcome::Lost => (ItemStatus::Lost, "Lost"),
+    };
+    if let Some(book) = books.iter_mut().find(|b| b.id == book_id) {
+        book.transition(
+            status,
+            &format!("{} by user {}", reason, loan.user_id),
+            date,
+        )?;
+    }
+
+    loan.return_date = Some(date);
+    loan.outcome = Some(outcome);
+    Ok(loan.clone())
+}
+
+/// Puts a copy declared lost back on the shelf and returns the loan it was
+/// lost on, now marked as found.
+pub fn return_found_item(
+    loans: &mut [Loan],
+    books: &mut [Book],
+    book_id: u32,
+    date: NaiveDate,
+) -> Result<Loan, LoanError> {
+    let book = books
+        .iter_mut()
+        .find(|b| b.id == book_id)
+        .ok_or(LoanError::BookNotFound)?;
+    let loan = loans
+        .iter_mut()
+        .filter(|l| l.book_id == book_id && l.outcome == Some(LoanOutcome::Lost))
+        .max_by_key(|l| l.return_date)
+        .ok_or(LoanError::LoanNotFound)?;
+
+    book.transition(
+        ItemStatus::Available,
+        &format!("Found after loss by user {}", loan.user_id),
+        date,
+    )?;
+    loan.outcome = Some(LoanOutcome::LostAndFound);
+    Ok(loan.clone())
+}
+
+/// Extends an active loan by the policy's loan period, counted from the
+/// renewal date but never shortening the current due date.
+pub fn renew_loan(
+    loans: &mut [Loan],
+    loan_id: u32,
+    date: NaiveDate,
+    policy: &LoanPolicy,
+    pending_holds: usize,
+    calendar: &LibraryCalendar,
+) -> Result<Loan, LoanError> {
+    let loan = loans
+        .iter_mut()
+        .find(|l| l.id == loan_id && l.is_active())
+        .ok_or(LoanError::LoanNotFound)?;
+
+    if loan.renewals.len() >= policy.max_renewals as usize {
+        return Err(LoanError::RenewalLimitReached {
+            limit: policy.max_renewals,
+        });
+    }
+
+    if loan.is_recalled() {
+        return Err(LoanError::LoanRecalled);
+    }
+
+    if pending_holds > 0 {
+        return Err(LoanError::ItemHasPendingHolds);
+    }
+
+    let days_overdue = loan.days_overdue(date);
+    if days_overdue > i64::from(policy.max_overdue_days_for_renewal) {
+        return Err(LoanError::OverdueTooLong {
+            days_overdue,
+            limit: policy.max_overdue_days_for_renewal,
+        });
+    }
+
+    let new_due_date = calendar_service::due_date(calendar, date, policy.loan_period_days)
+        .ok_or_else(|| LoanError::InvalidDate(date.to_string()))?
+        .max(loan.due_date);
+
+    loan.renewals.push(Renewal {
+        date,
+        previous_due_date: loan.due_date,
+        new_due_date,
+    });
+    loan.due_date = new_due_date;
+    Ok(loan.clone())
+}
+
+/// Recalls an active loan: the due date moves to `date` plus the recall
+/// return period, but never earlier than the minimum loan period and never
+/// later than the current due date.
+pub fn recall_loan(
+    loans: &mut [Loan],
+    loan_id: u32,
+    date: NaiveDate,
+    rules: &RecallRules,
+    calendar: &LibraryCalendar,
+    hold_id: Option<u32>,
+) -> Result<Loan, LoanError> {
+    let loan = loans
+        .iter_mut()
+        .find(|l| l.id == loan_id && l.is_active())
+        .ok_or(LoanError::LoanNotFound)?;
+    if loan.is_recalled() {
+        return Err(LoanError::LoanRecalled);
     }
+
+    let invalid = || LoanError::InvalidDate(date.to_string());
+    let recall_due =
+        calendar_service::due_date(calendar, date, rules.return_within_days).ok_or_else(invalid)?;
+    let earliest = calendar_service::due_date(calendar, loan.loan_date, rules.minimum_loan_days)
+        .ok_or_else(invalid)?;
+
+    loan.recall = Some(Recall {
+        date,
+        previous_due_date: loan.due_date,
+        hold_id,
+    });
+    loan.due_date = recall_due.max(earliest).min(loan.due_date);
+    Ok(loan.clone())
 }
 
+#[allow(dead_code)]
 pub fn delete_loan(loans: &mut Vec<Loan>, book_id: u32) -> Result<(), LoanError> {
     let index = loans
         .iter()
@@ -105,6 +468,20 @@ pub fn get_active_loans(loans: &[Loan]) -> Vec<Loan> {
         .collect()
 }
 
+/// Active loans past their due date as of `as_of`, most overdue first.
+pub fn get_overdue_loans(loans: &[Loan], as_of: NaiveDate) -> Vec<OverdueLoan> {
+    let mut overdue: Vec<OverdueLoan> = loans
+        .iter()
+        .filter(|l| l.is_active() && l.due_date < as_of)
+        .map(|l| OverdueLoan {
+            loan: l.clone(),
+            days_overdue: l.days_overdue(as_of),
+        })
+        .collect();
+    overdue.sort_by_key(|o| std::cmp::Reverse(o.days_overdue));
+    overdue
+}
+
 pub fn get_loans_by_user(loans: &[Loan], user_id: u32) -> Vec<Loan> {
     loans
         .iter()
@@ -113,84 +490,342 @@ pub fn get_loans_by_user(loans: &[Loan], user_id: u32) -> Vec<Loan> {
         .collect()
 }
 
+pub fn get_loans_by_book(loans: &[Loan], book_id: u32) -> Vec<Loan> {
+    loans
+        .iter()
+        .filter(|l| l.book_id == book_id)
+        .cloned()
+        .collect()
+}
+
+/// The loans matching `filter`, oldest first.
+pub fn filter_history(mut loans: Vec<Loan>, filter: &HistoryFilter) -> Vec<Loan> {
+    loans.retain(|l| filter.matches(l));
+    loans.sort_by_key(|l| (l.loan_date, l.id));
+    loans
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::library::books::models::{MaterialDetails, MaterialFormat};
+    use crate::library::loans::models::ConditionCheck;
+    use std::fs;
     use std::io::Write;
     use tempfile::NamedTempFile;
 
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).expect("Data inválida")
+    }
+
+    fn request(user_id: u32, book_id: u32, loan_date: NaiveDate) -> CheckoutRequest {
+        CheckoutRequest {
+            user_id,
+            book_id,
+            loan_date,
+            policy: LoanPolicy::default(),
+            balance_cents: 0,
+            max_balance_cents: 1000,
+            collecting_hold: false,
+            calendar: LibraryCalendar::default(),
+            checklist: Vec::new(),
+            branch_id: None,
+        }
+    }
+
     #[test]
     fn test_add_loan_success() {
         let mut loans = Vec::new();
-        let users = vec![User {
-            id: 1,
-            name: "Alice".to_string(),
-        }];
-        let mut books = vec![Book {
-            id: 1,
-            title: "Rust Book".to_string(),
-            author: "Steve".to_string(),
-            pages: 300,
-            is_borrowed: false,
-        }];
+        let users = vec![User::new(1, "Alice".to_string())];
+        let mut books = vec![Book::new(
+            1,
+            "Rust Book".to_string(),
+            "Steve".to_string(),
+            300,
+        )];
 
         let result = add_loan(
             &mut loans,
             &users,
             &mut books,
-            1,
-            1,
-            "2023-10-01".to_string(),
+            &mut IdAllocator::default(),
+            request(1, 1, date(2023, 10, 1)),
         );
         assert!(result.is_ok());
         assert_eq!(loans.len(), 1);
-        assert!(books[0].is_borrowed);
+        assert_eq!(loans[0].due_date, date(2023, 10, 15));
+        assert_eq!(books[0].status, ItemStatus::OnLoan);
     }
 
     #[test]
     fn test_add_loan_user_not_found() {
         let mut loans = Vec::new();
-        let users = vec![User {
-            id: 1,
-            name: "Alice".to_string(),
-        }];
-        let mut books = vec![Book {
-            id: 1,
-            title: "Rust Book".to_string(),
-            author: "Steve".to_string(),
-            pages: 300,
-            is_borrowed: false,
-        }];
+        let users = vec![User::new(1, "Alice".to_string())];
+        let mut books = vec![Book::new(
+            1,
+            "Rust Book".to_string(),
+            "Steve".to_string(),
+            300,
+        )];
 
         let result = add_loan(
             &mut loans,
             &users,
             &mut books,
-            2,
-            1,
-            "2023-10-01".to_string(),
+            &mut IdAllocator::default(),
+            request(2, 1, date(2023, 10, 1)),
         );
         assert!(matches!(result, Err(LoanError::UserNotFound)));
         assert_eq!(loans.len(), 0);
-        assert!(!books[0].is_borrowed);
+        assert!(books[0].is_available());
     }
 
     #[test]
-    fn test_add_loan_book_not_found() {
+    fn test_add_loan_refuses_inactive_patrons() {
+        let mut books = vec![Book::new(
+            1,
+            "Rust Book".to_string(),
+            "Steve".to_string(),
+            300,
+        )];
+        let users = vec![
+            User {
+                membership_expiry: NaiveDate::from_ymd_opt(2000, 1, 1),
+                ..User::new(1, "Alice".to_string())
+            },
+            User {
+                status: AccountStatus::Suspended,
+                ..User::new(2, "Bob".to_string())
+            },
+            User {
+                status: AccountStatus::Blocked,
+                ..User::new(3, "Carol".to_string())
+            },
+            User {
+                status: AccountStatus::Expired,
+                ..User::new(4, "Dave".to_string())
+            },
+        ];
+        let mut loans = Vec::new();
+        let mut ids = IdAllocator::default();
+
+        let mut attempt = |user_id| {
+            add_loan(
+                &mut loans,
+                &users,
+                &mut books,
+                &mut ids,
+                request(user_id, 1, date(2023, 10, 1)),
+            )
+        };
+        assert!(matches!(attempt(1), Err(LoanError::MembershipExpired)));
+        assert!(matches!(attempt(2), Err(LoanError::PatronSuspended)));
+        assert!(matches!(attempt(3), Err(LoanError::PatronBlocked)));
+        assert!(matches!(attempt(4), Err(LoanError::MembershipExpired)));
+        assert!(loans.is_empty());
+        assert!(books[0].is_available());
+    }
+
+    #[test]
+    fn test_equipment_requires_condition_checklist() {
+        let mut loans = Vec::new();
+        let users = vec![User::new(1, "Alice".to_string())];
+        let mut books = vec![Book::with_details(
+            1,
+            "Notebook".to_string(),
+            String::new(),
+            MaterialDetails::Equipment {
+                serial_number: "SN-1".to_string(),
+                accessories: vec!["Carregador".to_string()],
+            },
+        )];
+        let policy = LoanPolicy {
+            allowed_formats: vec![MaterialFormat::Equipment],
+            ..LoanPolicy::default()
+        };
+        let check = |item: &str| ConditionCheck {
+            item: item.to_string(),
+            ok: true,
+            note: None,
+        };
+
+        let result = add_loan(
+            &mut loans,
+            &users,
+            &mut books,
+            &mut IdAllocator::default(),
+            CheckoutRequest {
+                policy: policy.clone(),
+                checklist: vec![check("Device")],
+                ..request(1, 1, date(2023, 10, 1))
+            },
+        );
+        assert!(
+            matches!(result, Err(LoanError::ChecklistIncomplete(item)) if item == "Carregador")
+        );
+        assert!(books[0].is_available());
+
+        let loan = add_loan(
+            &mut loans,
+            &users,
+            &mut books,
+            &mut IdAllocator::default(),
+            CheckoutRequest {
+                policy,
+                checklist: vec![check("Device"), check("Carregador")],
+                ..request(1, 1, date(2023, 10, 1))
+            },
+        )
+        .expect("Falha ao emprestar equipamento");
+        assert_eq!(loan.checklist.len(), 2);
+    }
+
+    #[test]
+    fn test_add_loan_format_not_allowed() {
+        let mut loans = Vec::new();
+        let users = vec![User::new(1, "Alice".to_string())];
+        let mut books = vec![Book::with_details(
+            1,
+            "Notebook".to_string(),
+            String::new(),
+            MaterialDetails::blank(MaterialFormat::Equipment),
+        )];
+
+        let result = add_loan(
+            &mut loans,
+            &users,
+            &mut books,
+            &mut IdAllocator::default(),
+            request(1, 1, date(2023, 10, 1)),
+        );
+        assert!(matches!(
+            result,
+            Err(LoanError::FormatNotAllowed(MaterialFormat::Equipment))
+        ));
+        assert!(books[0].is_available());
+    }
+
+    #[test]
+    fn test_add_loan_limit_reached() {
+        let mut loans = Vec::new();
+        let users = vec![User::new(1, "Alice".to_string())];
+        let mut books = vec![
+            Book::new(1, "Livro Um".to_string(), "Autor A".to_string(), 100),
+            Book::new(2, "Livro Dois".to_string(), "Autor B".to_string(), 200),
+        ];
+        let mut ids = IdAllocator::default();
+        let policy = LoanPolicy {
+            max_concurrent_loans: 1,
+            ..LoanPolicy::default()
+        };
+
+        let mut checkout = |book_id| {
+            add_loan(
+                &mut loans,
+                &users,
+                &mut books,
+                &mut ids,
+                CheckoutRequest {
+                    policy: policy.clone(),
+                    ..request(1, book_id, date(2023, 10, 1))
+                },
+            )
+        };
+        assert!(checkout(1).is_ok());
+        assert!(matches!(
+            checkout(2),
+            Err(LoanError::LoanLimitReached {
+                limit: 1,
+                current: 1
+            })
+        ));
+        assert!(books[1].is_available());
+    }
+
+    #[test]
+    fn test_add_loan_limit_override() {
         let mut loans = Vec::new();
         let users = vec![User {
-            id: 1,
-            name: "Alice".to_string(),
+            max_loans_override: Some(2),
+            ..User::new(1, "Professora".to_string())
         }];
+        let mut books = vec![
+            Book::new(1, "Livro Um".to_string(), "Autor A".to_string(), 100),
+            Book::new(2, "Livro Dois".to_string(), "Autor B".to_string(), 200),
+            Book::new(3, "Livro Três".to_string(), "Autor C".to_string(), 300),
+        ];
+        let mut ids = IdAllocator::default();
+        let policy = LoanPolicy {
+            max_concurrent_loans: 1,
+            ..LoanPolicy::default()
+        };
+
+        let mut checkout = |book_id| {
+            add_loan(
+                &mut loans,
+                &users,
+                &mut books,
+                &mut ids,
+                CheckoutRequest {
+                    policy: policy.clone(),
+                    ..request(1, book_id, date(2023, 10, 1))
+                },
+            )
+        };
+        assert!(checkout(1).is_ok());
+        assert!(checkout(2).is_ok());
+        assert!(matches!(
+            checkout(3),
+            Err(LoanError::LoanLimitReached {
+                limit: 2,
+                current: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn test_add_loan_blocked_by_outstanding_fines() {
+        let mut loans = Vec::new();
+        let users = vec![User::new(1, "Alice".to_string())];
+        let mut books = vec![Book::new(
+            1,
+            "Rust Book".to_string(),
+            "Steve".to_string(),
+            300,
+        )];
+
+        let result = add_loan(
+            &mut loans,
+            &users,
+            &mut books,
+            &mut IdAllocator::default(),
+            CheckoutRequest {
+                balance_cents: 1001,
+                ..request(1, 1, date(2023, 10, 1))
+            },
+        );
+        assert!(matches!(
+            result,
+            Err(LoanError::OutstandingFines {
+                balance_cents: 1001,
+                threshold_cents: 1000
+            })
+        ));
+        assert!(books[0].is_available());
+    }
+
+    #[test]
+    fn test_add_loan_book_not_found() {
+        let mut loans = Vec::new();
+        let users = vec![User::new(1, "Alice".to_string())];
         let mut books = vec![];
 
         let result = add_loan(
             &mut loans,
             &users,
             &mut books,
-            1,
-            1,
-            "2023-10-01".to_string(),
+            &mut IdAllocator::default(),
+            request(1, 1, date(2023, 10, 1)),
         );
         assert!(matches!(result, Err(LoanError::BookNotFound)));
         assert_eq!(loans.len(), 0);
@@ -199,107 +834,501 @@ mod tests {
     #[test]
     fn test_add_loan_book_already_borrowed() {
         let mut loans = Vec::new();
-        let users = vec![User {
-            id: 1,
-            name: "Alice".to_string(),
-        }];
+        let users = vec![User::new(1, "Alice".to_string())];
         let mut books = vec![Book {
-            id: 1,
-            title: "Rust Book".to_string(),
-            author: "Steve".to_string(),
-            pages: 300,
-            is_borrowed: true,
+            status: ItemStatus::OnLoan,
+            ..Book::new(1, "Rust Book".to_string(), "Steve".to_string(), 300)
         }];
 
         let result = add_loan(
             &mut loans,
             &users,
             &mut books,
-            1,
-            1,
-            "2023-10-01".to_string(),
+            &mut IdAllocator::default(),
+            request(1, 1, date(2023, 10, 1)),
         );
         assert!(matches!(result, Err(LoanError::BookNotAvailable)));
         assert_eq!(loans.len(), 0);
     }
 
     #[test]
-    fn test_add_loan_already_exists() {
-        let mut loans = vec![Loan {
-            user_id: 1,
-            book_id: 1,
-            loan_date: "2023-10-01".to_string(),
-            return_date: None,
-        }];
-        let users = vec![User {
-            id: 1,
-            name: "Alice".to_string(),
+    fn test_add_loan_book_in_repair() {
+        let mut loans = Vec::new();
+        let users = vec![User::new(1, "Alice".to_string())];
+        let mut books = vec![Book {
+            status: ItemStatus::InRepair,
+            ..Book::new(1, "Rust Book".to_string(), "Steve".to_string(), 300)
         }];
+
+        let result = add_loan(
+            &mut loans,
+            &users,
+            &mut books,
+            &mut IdAllocator::default(),
+            request(1, 1, date(2023, 10, 1)),
+        );
+        assert!(matches!(result, Err(LoanError::BookNotAvailable)));
+        assert_eq!(books[0].status, ItemStatus::InRepair);
+    }
+
+    #[test]
+    fn test_add_loan_from_hold_shelf() {
+        let mut loans = Vec::new();
+        let users = vec![User::new(1, "Alice".to_string())];
         let mut books = vec![Book {
-            id: 1,
-            title: "Rust Book".to_string(),
-            author: "Steve".to_string(),
-            pages: 300,
-            is_borrowed: false,
+            status: ItemStatus::OnHoldShelf,
+            ..Book::new(1, "Rust Book".to_string(), "Steve".to_string(), 300)
         }];
+        let mut ids = IdAllocator::default();
 
         let result = add_loan(
             &mut loans,
             &users,
             &mut books,
+            &mut ids,
+            request(1, 1, date(2023, 10, 1)),
+        );
+        assert!(matches!(result, Err(LoanError::ReservedForAnotherPatron)));
+
+        let result = add_loan(
+            &mut loans,
+            &users,
+            &mut books,
+            &mut ids,
+            CheckoutRequest {
+                collecting_hold: true,
+                ..request(1, 1, date(2023, 10, 1))
+            },
+        );
+        assert!(result.is_ok());
+        assert_eq!(books[0].status, ItemStatus::OnLoan);
+    }
+
+    #[test]
+    fn test_add_loan_already_exists() {
+        let mut loans = vec![Loan::new(1, 1, 1, date(2023, 10, 1), date(2023, 10, 15))];
+        let users = vec![User::new(1, "Alice".to_string())];
+        let mut books = vec![Book::new(
             1,
-            1,
-            "2023-10-02".to_string(),
+            "Rust Book".to_string(),
+            "Steve".to_string(),
+            300,
+        )];
+
+        let result = add_loan(
+            &mut loans,
+            &users,
+            &mut books,
+            &mut IdAllocator::default(),
+            request(1, 1, date(2023, 10, 2)),
         );
         assert!(matches!(result, Err(LoanError::LoanAlreadyExists)));
         assert_eq!(loans.len(), 1);
+        assert!(books[0].is_available());
+        assert!(books[0].status_history.is_empty());
+    }
+
+    #[test]
+    fn test_add_loan_allocates_ids() {
+        let mut loans = Vec::new();
+        let users = vec![User::new(1, "Alice".to_string())];
+        let mut books = vec![
+            Book::new(1, "Rust Book".to_string(), "Steve".to_string(), 300),
+            Book::new(2, "Go Book".to_string(), "Rob".to_string(), 250),
+        ];
+        let mut ids = IdAllocator::default();
+        ids.reserve(41).unwrap();
+
+        add_loan(
+            &mut loans,
+            &users,
+            &mut books,
+            &mut ids,
+            request(1, 1, date(2023, 10, 1)),
+        )
+        .expect("Falha ao emprestar");
+        add_loan(
+            &mut loans,
+            &users,
+            &mut books,
+            &mut ids,
+            request(1, 2, date(2023, 10, 1)),
+        )
+        .expect("Falha ao emprestar");
+        assert_eq!(loans[0].id, 42);
+        assert_eq!(loans[1].id, 43);
+    }
+
+    #[test]
+    fn test_assign_missing_ids() {
+        let mut loans = vec![
+            Loan::new(0, 1, 1, date(2023, 10, 1), date(2023, 10, 15)),
+            Loan::new(5, 1, 2, date(2023, 10, 1), date(2023, 10, 15)),
+        ];
+        let mut ids = IdAllocator::default();
+        ids.reserve(5).unwrap();
+        assign_missing_ids(&mut loans, &mut ids).expect("Falha ao numerar empréstimos");
+        assert_eq!(loans[0].id, 6);
+        assert_eq!(loans[1].id, 5);
     }
 
     #[test]
     fn test_return_loan_success() {
-        let mut loans = vec![Loan {
-            user_id: 1,
-            book_id: 1,
-            loan_date: "2023-10-01".to_string(),
-            return_date: None,
+        let mut loans = vec![Loan::new(1, 1, 1, date(2023, 10, 1), date(2023, 10, 15))];
+        let mut books = vec![Book {
+            status: ItemStatus::OnLoan,
+            ..Book::new(1, "Rust Book".to_string(), "Steve".to_string(), 300)
         }];
+
+        let result = check_in(&mut loans, &mut books, 1, date(2023, 10, 10), None);
+        assert_eq!(result.expect("Falha ao devolver").id, 1);
+        assert_eq!(loans[0].return_date, Some(date(2023, 10, 10)));
+        assert!(books[0].is_available());
+        assert_eq!(books[0].status_history.len(), 1);
+        assert_eq!(books[0].status_history[0].from, ItemStatus::OnLoan);
+        assert_eq!(books[0].status_history[0].at.date(), date(2023, 10, 10));
+    }
+
+    #[test]
+    fn test_check_in_records_condition_note() {
+        let mut loans = vec![Loan::new(1, 1, 1, date(2023, 10, 1), date(2023, 10, 15))];
         let mut books = vec![Book {
-            id: 1,
-            title: "Rust Book".to_string(),
-            author: "Steve".to_string(),
-            pages: 300,
-            is_borrowed: true,
+            status: ItemStatus::OnLoan,
+            ..Book::new(1, "Rust Book".to_string(), "Steve".to_string(), 300)
         }];
 
-        let result = return_loan(&mut loans, &mut books, 1, "2023-10-10".to_string());
-        assert!(result.is_ok());
-        assert_eq!(loans[0].return_date, Some("2023-10-10".to_string()));
-        assert!(!books[0].is_borrowed);
+        let loan = check_in(
+            &mut loans,
+            &mut books,
+            1,
+            date(2023, 10, 10),
+            Some("Capa molhada".to_string()),
+        )
+        .expect("Falha ao devolver");
+        assert_eq!(loan.condition_note.as_deref(), Some("Capa molhada"));
+        assert_eq!(loans[0].condition_note.as_deref(), Some("Capa molhada"));
+        assert_eq!(loans[0].outcome, Some(LoanOutcome::Returned));
+        assert!(books[0].is_available());
     }
 
     #[test]
-    fn test_return_loan_not_found() {
-        let mut loans = vec![];
+    fn test_return_loan_before_loan_date() {
+        let mut loans = vec![Loan::new(1, 1, 1, date(2023, 10, 1), date(2023, 10, 15))];
         let mut books = vec![Book {
-            id: 1,
-            title: "Rust Book".to_string(),
-            author: "Steve".to_string(),
-            pages: 300,
-            is_borrowed: false,
+            status: ItemStatus::OnLoan,
+            ..Book::new(1, "Rust Book".to_string(), "Steve".to_string(), 300)
         }];
 
-        let result = return_loan(&mut loans, &mut books, 1, "2023-10-10".to_string());
+        let result = check_in(&mut loans, &mut books, 1, date(2023, 9, 30), None);
+        assert!(matches!(result, Err(LoanError::ReturnBeforeLoan { .. })));
+        assert!(loans[0].return_date.is_none());
+        assert_eq!(books[0].status, ItemStatus::OnLoan);
+    }
+
+    #[test]
+    fn test_declare_lost_and_found() {
+        let mut loans = vec![Loan::new(1, 1, 1, date(2023, 10, 1), date(2023, 10, 15))];
+        let mut books = vec![Book {
+            status: ItemStatus::OnLoan,
+            ..Book::new(1, "Rust Book".to_string(), "Steve".to_string(), 300)
+        }];
+
+        let lost = declare_lost(&mut loans, &mut books, 1, date(2023, 11, 1))
+            .expect("Falha ao declarar perda");
+        assert_eq!(lost.outcome, Some(LoanOutcome::Lost));
+        assert!(!loans[0].is_active());
+        assert_eq!(books[0].status, ItemStatus::Lost);
+
+        let found = return_found_item(&mut loans, &mut books, 1, date(2023, 12, 1))
+            .expect("Falha ao devolver item encontrado");
+        assert_eq!(found.id, 1);
+        assert_eq!(loans[0].outcome, Some(LoanOutcome::LostAndFound));
+        assert!(books[0].is_available());
+        assert!(matches!(
+            return_found_item(&mut loans, &mut books, 1, date(2023, 12, 1)),
+            Err(LoanError::LoanNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_return_damaged() {
+        let mut loans = vec![Loan::new(1, 1, 1, date(2023, 10, 1), date(2023, 10, 15))];
+        let mut books = vec![Book {
+            status: ItemStatus::OnLoan,
+            ..Book::new(1, "Rust Book".to_string(), "Steve".to_string(), 300)
+        }];
+
+        let loan = return_damaged(&mut loans, &mut books, 1, date(2023, 10, 10))
+            .expect("Falha ao devolver");
+        assert_eq!(loan.outcome, Some(LoanOutcome::ReturnedDamaged));
+        assert_eq!(books[0].status, ItemStatus::Damaged);
+    }
+
+    #[test]
+    fn test_parse_date() {
+        let today = date(2024, 5, 20);
+        assert_eq!(parse_date("2024-02-29", today).unwrap(), date(2024, 2, 29));
+        assert_eq!(parse_date("  ", today).unwrap(), today);
+        assert!(matches!(
+            parse_date("2024-13-45", today),
+            Err(LoanError::InvalidDate(_))
+        ));
+        assert!(matches!(
+            parse_date("tomorrow", today),
+            Err(LoanError::InvalidDate(_))
+        ));
+        assert!(matches!(
+            parse_date("2023-02-29", today),
+            Err(LoanError::InvalidDate(_))
+        ));
+    }
+
+    #[test]
+    fn test_read_legacy_string_dates() {
+        let mut temp_file =
+            NamedTempFile::new().expect("Não foi possível criar arquivo temporário");
+        write!(
+            temp_file,
+            r#"[{{"user_id": 1, "book_id": 1, "loan_date": "2024-11-07", "return_date": "2024-11-14"}}]"#
+        )
+        .expect("Falha ao escrever no arquivo temporário");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let loans = read_from_json(file_path)
+            .expect("Falha ao ler empréstimos")
+            .loans;
+        assert_eq!(loans[0].loan_date, date(2024, 11, 7));
+        assert_eq!(loans[0].due_date, date(2024, 11, 21));
+        assert_eq!(loans[0].return_date, Some(date(2024, 11, 14)));
+        assert_eq!(loans[0].outcome, Some(LoanOutcome::Returned));
+    }
+
+    #[test]
+    fn test_read_legacy_file_sets_aside_unparseable_date() {
+        let mut temp_file =
+            NamedTempFile::new().expect("Não foi possível criar arquivo temporário");
+        write!(
+            temp_file,
+            r#"[{{"user_id": 1, "book_id": 1, "loan_date": "2024-11-07"}},
+                {{"user_id": 2, "book_id": 2, "loan_date": "tomorrow"}}]"#
+        )
+        .expect("Falha ao escrever no arquivo temporário");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let loaded = read_from_json(file_path).expect("Falha ao ler empréstimos");
+        assert_eq!(loaded.loans.len(), 1);
+        assert_eq!(loaded.loans[0].due_date, date(2024, 11, 21));
+        assert_eq!(
+            loaded.set_aside,
+            vec![serde_json::json!({"user_id": 2, "book_id": 2, "loan_date": "tomorrow"})]
+        );
+
+        let aside_file = NamedTempFile::new().expect("Não foi possível criar arquivo temporário");
+        let aside_path = aside_file.path().to_str().unwrap();
+        fs::write(aside_path, "[]").expect("Falha ao escrever no arquivo temporário");
+        set_aside(aside_path, &loaded.set_aside).expect("Falha ao separar registros");
+        set_aside(aside_path, &loaded.set_aside).expect("Falha ao separar registros");
+        let kept: Vec<Value> =
+            serde_json::from_str(&fs::read_to_string(aside_path).unwrap()).unwrap();
+        assert_eq!(kept, loaded.set_aside);
+    }
+
+    #[test]
+    fn test_return_loan_not_found() {
+        let mut loans = vec![];
+        let mut books = vec![Book::new(
+            1,
+            "Rust Book".to_string(),
+            "Steve".to_string(),
+            300,
+        )];
+
+        let result = check_in(&mut loans, &mut books, 1, date(2023, 10, 10), None);
         assert!(matches!(result, Err(LoanError::LoanNotFound)));
     }
 
     #[test]
-    fn test_delete_loan_success() {
+    fn test_renew_loan_extends_due_date() {
+        let mut loans = vec![Loan::new(1, 1, 1, date(2023, 10, 1), date(2023, 10, 15))];
+        let policy = LoanPolicy::default();
+
+        let renewed = renew_loan(
+            &mut loans,
+            1,
+            date(2023, 10, 14),
+            &policy,
+            0,
+            &LibraryCalendar::default(),
+        )
+        .expect("Falha ao renovar");
+        assert_eq!(renewed.due_date, date(2023, 10, 28));
+        assert_eq!(loans[0].renewals.len(), 1);
+        assert_eq!(loans[0].renewals[0].previous_due_date, date(2023, 10, 15));
+    }
+
+    #[test]
+    fn test_renew_loan_never_shortens_due_date() {
+        let mut loans = vec![Loan::new(1, 1, 1, date(2023, 10, 1), date(2023, 10, 31))];
+        let renewed = renew_loan(
+            &mut loans,
+            1,
+            date(2023, 10, 2),
+            &LoanPolicy::default(),
+            0,
+            &LibraryCalendar::default(),
+        )
+        .expect("Falha ao renovar");
+        assert_eq!(renewed.due_date, date(2023, 10, 31));
+    }
+
+    #[test]
+    fn test_renew_loan_limit_reached() {
+        let mut loans = vec![Loan::new(1, 1, 1, date(2023, 10, 1), date(2023, 10, 15))];
+        let policy = LoanPolicy {
+            max_renewals: 1,
+            ..LoanPolicy::default()
+        };
+
+        assert!(renew_loan(
+            &mut loans,
+            1,
+            date(2023, 10, 10),
+            &policy,
+            0,
+            &LibraryCalendar::default()
+        )
+        .is_ok());
+        let result = renew_loan(
+            &mut loans,
+            1,
+            date(2023, 10, 20),
+            &policy,
+            0,
+            &LibraryCalendar::default(),
+        );
+        assert!(matches!(
+            result,
+            Err(LoanError::RenewalLimitReached { limit: 1 })
+        ));
+        assert_eq!(loans[0].renewals.len(), 1);
+    }
+
+    #[test]
+    fn test_renew_loan_refused_with_pending_holds() {
+        let mut loans = vec![Loan::new(1, 1, 1, date(2023, 10, 1), date(2023, 10, 15))];
+        let result = renew_loan(
+            &mut loans,
+            1,
+            date(2023, 10, 10),
+            &LoanPolicy::default(),
+            1,
+            &LibraryCalendar::default(),
+        );
+        assert!(matches!(result, Err(LoanError::ItemHasPendingHolds)));
+        assert_eq!(loans[0].due_date, date(2023, 10, 15));
+    }
+
+    #[test]
+    fn test_renew_loan_overdue_too_long() {
+        let mut loans = vec![Loan::new(1, 1, 1, date(2023, 10, 1), date(2023, 10, 15))];
+        let policy = LoanPolicy {
+            max_overdue_days_for_renewal: 3,
+            ..LoanPolicy::default()
+        };
+
+        assert!(renew_loan(
+            &mut loans.clone(),
+            1,
+            date(2023, 10, 18),
+            &policy,
+            0,
+            &LibraryCalendar::default()
+        )
+        .is_ok());
+        let result = renew_loan(
+            &mut loans,
+            1,
+            date(2023, 10, 19),
+            &policy,
+            0,
+            &LibraryCalendar::default(),
+        );
+        assert!(matches!(
+            result,
+            Err(LoanError::OverdueTooLong {
+                days_overdue: 4,
+                limit: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn test_renew_returned_loan_not_found() {
         let mut loans = vec![Loan {
-            user_id: 1,
-            book_id: 1,
-            loan_date: "2023-10-01".to_string(),
-            return_date: None,
+            return_date: Some(date(2023, 10, 5)),
+            ..Loan::new(1, 1, 1, date(2023, 10, 1), date(2023, 10, 15))
         }];
+        let result = renew_loan(
+            &mut loans,
+            1,
+            date(2023, 10, 10),
+            &LoanPolicy::default(),
+            0,
+            &LibraryCalendar::default(),
+        );
+        assert!(matches!(result, Err(LoanError::LoanNotFound)));
+    }
+
+    #[test]
+    fn test_recall_shortens_due_date() {
+        let mut loans = vec![Loan::new(1, 1, 1, date(2024, 3, 1), date(2024, 3, 31))];
+        let rules = RecallRules::default();
+
+        let recalled = recall_loan(
+            &mut loans,
+            1,
+            date(2024, 3, 20),
+            &rules,
+            &LibraryCalendar::default(),
+            Some(4),
+        )
+        .expect("Falha ao recolher empréstimo");
+        assert_eq!(recalled.due_date, date(2024, 3, 27));
+        assert!(loans[0].is_recalled());
+        assert!(matches!(
+            recall_loan(
+                &mut loans,
+                1,
+                date(2024, 3, 21),
+                &rules,
+                &LibraryCalendar::default(),
+                None
+            ),
+            Err(LoanError::LoanRecalled)
+        ));
+    }
+
+    #[test]
+    fn test_recall_respects_minimum_loan_period() {
+        let mut loans = vec![
+            Loan::new(1, 1, 1, date(2024, 3, 1), date(2024, 3, 31)),
+            Loan::new(2, 1, 2, date(2024, 3, 1), date(2024, 3, 10)),
+        ];
+        let rules = RecallRules::default();
+        let calendar = LibraryCalendar::default();
+
+        let recalled = recall_loan(&mut loans, 1, date(2024, 3, 2), &rules, &calendar, None)
+            .expect("Falha ao recolher empréstimo");
+        assert_eq!(recalled.due_date, date(2024, 3, 15));
+
+        let recalled = recall_loan(&mut loans, 2, date(2024, 3, 8), &rules, &calendar, None)
+            .expect("Falha ao recolher empréstimo");
+        assert_eq!(recalled.due_date, date(2024, 3, 10));
+    }
+
+    #[test]
+    fn test_delete_loan_success() {
+        let mut loans = vec![Loan::new(1, 1, 1, date(2023, 10, 1), date(2023, 10, 15))];
         let result = delete_loan(&mut loans, 1);
         assert!(result.is_ok());
         assert!(loans.is_empty());
@@ -315,17 +1344,10 @@ mod tests {
     #[test]
     fn test_get_active_loans() {
         let loans = vec![
+            Loan::new(1, 1, 1, date(2023, 10, 1), date(2023, 10, 15)),
             Loan {
-                user_id: 1,
-                book_id: 1,
-                loan_date: "2023-10-01".to_string(),
-                return_date: None,
-            },
-            Loan {
-                user_id: 2,
-                book_id: 2,
-                loan_date: "2023-09-01".to_string(),
-                return_date: Some("2023-09-15".to_string()),
+                return_date: Some(date(2023, 9, 15)),
+                ..Loan::new(2, 2, 2, date(2023, 9, 1), date(2023, 9, 15))
             },
         ];
         let active_loans = get_active_loans(&loans);
@@ -334,25 +1356,101 @@ mod tests {
     }
 
     #[test]
-    fn test_get_loans_by_user() {
+    fn test_add_loan_due_date_follows_policy() {
+        let mut loans = Vec::new();
+        let users = vec![User::new(1, "Alice".to_string())];
+        let mut books = vec![Book::new(
+            1,
+            "Rust Book".to_string(),
+            "Steve".to_string(),
+            300,
+        )];
+
+        let result = add_loan(
+            &mut loans,
+            &users,
+            &mut books,
+            &mut IdAllocator::default(),
+            CheckoutRequest {
+                policy: LoanPolicy {
+                    loan_period_days: 7,
+                    ..LoanPolicy::default()
+                },
+                ..request(1, 1, date(2024, 2, 26))
+            },
+        );
+        assert!(result.is_ok());
+        assert_eq!(loans[0].due_date, date(2024, 3, 4));
+    }
+
+    #[test]
+    fn test_add_loan_due_date_skips_closed_days() {
+        let mut loans = Vec::new();
+        let users = vec![User::new(1, "Alice".to_string())];
+        let mut books = vec![Book::new(
+            1,
+            "Rust Book".to_string(),
+            "Steve".to_string(),
+            300,
+        )];
+        let mut calendar = LibraryCalendar::default();
+        calendar
+            .open_weekdays
+            .retain(|w| *w != chrono::Weekday::Sun);
+        calendar_service::add_closure(&mut calendar, date(2024, 4, 1), "Feriado".to_string())
+            .expect("Falha ao adicionar fechamento");
+
+        let result = add_loan(
+            &mut loans,
+            &users,
+            &mut books,
+            &mut IdAllocator::default(),
+            CheckoutRequest {
+                calendar,
+                ..request(1, 1, date(2024, 3, 17))
+            },
+        );
+        assert!(result.is_ok());
+        assert_eq!(loans[0].due_date, date(2024, 4, 2));
+    }
+
+    #[test]
+    fn test_get_overdue_loans() {
         let loans = vec![
+            Loan::new(1, 1, 1, date(2023, 10, 1), date(2023, 10, 15)),
+            Loan::new(2, 2, 2, date(2023, 10, 1), date(2023, 10, 20)),
+            Loan::new(3, 1, 3, date(2023, 10, 10), date(2023, 10, 24)),
             Loan {
-                user_id: 1,
-                book_id: 1,
-                loan_date: "2023-10-01".to_string(),
-                return_date: None,
+                return_date: Some(date(2023, 10, 18)),
+                ..Loan::new(4, 2, 4, date(2023, 9, 1), date(2023, 9, 15))
             },
+        ];
+        let overdue = get_overdue_loans(&loans, date(2023, 10, 22));
+        let summary: Vec<(u32, i64)> = overdue
+            .iter()
+            .map(|o| (o.loan.id, o.days_overdue))
+            .collect();
+        assert_eq!(summary, vec![(1, 7), (2, 2)]);
+    }
+
+    #[test]
+    fn test_loan_not_overdue_on_due_date() {
+        let loans = vec![Loan::new(1, 1, 1, date(2023, 10, 1), date(2023, 10, 15))];
+        assert!(get_overdue_loans(&loans, date(2023, 10, 15)).is_empty());
+        assert_eq!(loans[0].days_overdue(date(2023, 10, 15)), 0);
+    }
+
+    #[test]
+    fn test_get_loans_by_user() {
+        let loans = vec![
+            Loan::new(1, 1, 1, date(2023, 10, 1), date(2023, 10, 15)),
             Loan {
-                user_id: 1,
-                book_id: 2,
-                loan_date: "2023-09-01".to_string(),
-                return_date: Some("2023-09-15".to_string()),
+                return_date: Some(date(2023, 9, 15)),
+                ..Loan::new(2, 1, 2, date(2023, 9, 1), date(2023, 9, 15))
             },
             Loan {
-                user_id: 2,
-                book_id: 3,
-                loan_date: "2023-08-01".to_string(),
-                return_date: Some("2023-08-15".to_string()),
+                return_date: Some(date(2023, 8, 15)),
+                ..Loan::new(3, 2, 3, date(2023, 8, 1), date(2023, 8, 15))
             },
         ];
         let user_loans = get_loans_by_user(&loans, 1);
@@ -360,37 +1458,72 @@ mod tests {
         assert!(user_loans.iter().all(|l| l.user_id == 1));
     }
 
+    #[test]
+    fn test_filter_history() {
+        let loans = vec![
+            Loan {
+                return_date: Some(date(2024, 2, 10)),
+                ..Loan::new(1, 1, 1, date(2024, 2, 1), date(2024, 2, 15))
+            },
+            Loan::new(3, 2, 1, date(2024, 4, 1), date(2024, 4, 15)),
+            Loan {
+                return_date: Some(date(2024, 3, 10)),
+                ..Loan::new(2, 1, 1, date(2024, 3, 1), date(2024, 3, 15))
+            },
+            Loan::new(4, 1, 2, date(2024, 4, 1), date(2024, 4, 15)),
+        ];
+
+        let copy_history = filter_history(get_loans_by_book(&loans, 1), &HistoryFilter::default());
+        let ids: Vec<u32> = copy_history.iter().map(|l| l.id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+
+        let active = filter_history(
+            get_loans_by_user(&loans, 1),
+            &HistoryFilter {
+                active_only: true,
+                ..HistoryFilter::default()
+            },
+        );
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].id, 4);
+
+        let march = filter_history(
+            loans.clone(),
+            &HistoryFilter {
+                from: Some(date(2024, 3, 1)),
+                to: Some(date(2024, 3, 31)),
+                ..HistoryFilter::default()
+            },
+        );
+        assert_eq!(march.len(), 1);
+        assert_eq!(march[0].id, 2);
+    }
+
     #[test]
     fn test_save_and_read_loans() {
         let temp_file = NamedTempFile::new().expect("Não foi possível criar arquivo temporário");
         let file_path = temp_file.path().to_str().unwrap();
 
         let loans = vec![
+            Loan::new(1, 1, 1, date(2023, 10, 1), date(2023, 10, 15)),
             Loan {
-                user_id: 1,
-                book_id: 1,
-                loan_date: "2023-10-01".to_string(),
-                return_date: None,
-            },
-            Loan {
-                user_id: 2,
-                book_id: 2,
-                loan_date: "2023-09-01".to_string(),
-                return_date: Some("2023-09-15".to_string()),
+                return_date: Some(date(2023, 9, 15)),
+                ..Loan::new(2, 2, 2, date(2023, 9, 1), date(2023, 9, 15))
             },
         ];
 
         assert!(save_to_json(file_path, &loans).is_ok());
 
-        let loaded_loans = read_from_json(file_path).expect("Falha ao ler empréstimos");
-        assert_eq!(loaded_loans.len(), 2);
+        let loaded = read_from_json(file_path).expect("Falha ao ler empréstimos");
+        assert_eq!(loaded.loans.len(), 2);
+        assert!(loaded.set_aside.is_empty());
     }
 
     #[test]
     fn test_read_from_nonexistent_file() {
         let result = read_from_json("arquivo_que_nao_existe.json");
         assert!(result.is_ok());
-        assert!(result.unwrap().is_empty());
+        assert!(result.unwrap().loans.is_empty());
     }
 
     #[test]
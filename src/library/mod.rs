@@ -1,24 +1,118 @@
 pub(crate) mod books;
-mod loans;
+pub(crate) mod branches;
+mod calendar;
+pub(crate) mod digital;
+mod fines;
+mod holds;
+pub(crate) mod households;
+mod ids;
+pub(crate) mod integrity;
+pub(crate) mod interlibrary;
+pub(crate) mod loans;
+mod policies;
+pub(crate) mod serials;
 pub(crate) mod users;
 
+use std::error::Error;
 use std::fs::File;
 use std::io::Write;
 use std::path::Path;
 
 use books::handlers as book_handlers;
+use branches::handlers as branch_handlers;
+use calendar::handlers as calendar_handlers;
+use digital::handlers as digital_handlers;
+use fines::handlers as fine_handlers;
+use holds::handlers as hold_handlers;
+use households::handlers as household_handlers;
+use ids::handlers as id_handlers;
+use integrity::handlers as integrity_handlers;
+use interlibrary::handlers as interlibrary_handlers;
 use loans::handlers as loan_handlers;
+use policies::handlers as policy_handlers;
+use serials::handlers as serial_handlers;
 use users::handlers as user_handlers;
 
-use books::models::{Book, BookError};
-use loans::models::{Loan, LoanError};
-use users::models::{User, UserError};
+use books::classification::{CallNumber, Classification};
+use books::models::{Book, BookError, ItemStatus, MaterialDetails};
+use branches::models::{BranchAvailability, BranchError, Branches, Transfer};
+use calendar::models::{CalendarError, LibraryCalendar};
+use digital::models::{DigitalCollection, DigitalError, DigitalLoan, DigitalTitle, License};
+use fines::models::{ChargeReason, FineError, LedgerEntry};
+use holds::models::{Hold, HoldError, HoldStatus};
+use households::models::{Household, HouseholdError};
+use ids::models::IdCounters;
+use integrity::models::{Archive, DeleteError, DeletePolicy, Subject};
+use interlibrary::models::{IllDirection, IllError, IllRequest, Interlibrary};
+use loans::models::{
+    CheckoutReceipt, CheckoutReceiptItem, CheckoutRequest, CheckoutSession, ConditionCheck,
+    HistoryFilter, Loan, LoanError, LoanHistoryEntry, OverdueLoan, RecallNotice, ReturnReceipt,
+};
+use policies::models::{LibraryPolicies, LoanPolicy};
+use serials::models::{Frequency, IssueClaim, SerialError, SerialIssue, SerialTitle, Serials};
+use users::models::{AccountStatus, ContactDetails, PatronCategory, User, UserError};
+
+use chrono::{Local, NaiveDate, Weekday};
 
 pub struct Library {
     pub(crate) books: Vec<Book>,
     pub(crate) users: Vec<User>,
     loans: Vec<Loan>,
+    ids: IdCounters,
+    policies: LibraryPolicies,
+    ledger: Vec<LedgerEntry>,
+    holds: Vec<Hold>,
+    calendar: LibraryCalendar,
+    archive: Archive,
+    interlibrary: Interlibrary,
+    serials: Serials,
+    digital: DigitalCollection,
+    branches: Branches,
+    households: Vec<Household>,
+    /// Files that failed to load; `save_data` leaves them as they are.
+    unloaded: Vec<&'static str>,
+    set_aside_loans: usize,
+}
+
+/// The collections a transaction may change. The ledger is append-only, so
+/// only its length is kept and a rollback drops whatever was posted after it.
+struct Snapshot {
+    books: Vec<Book>,
+    loans: Vec<Loan>,
+    holds: Vec<Hold>,
+    ids: IdCounters,
+    ledger_len: usize,
+    interlibrary: Interlibrary,
+    serials: Serials,
+    branches: Branches,
+}
+
+impl Snapshot {
+    fn take(library: &Library) -> Self {
+        Self {
+            books: library.books.clone(),
+            loans: library.loans.clone(),
+            holds: library.holds.clone(),
+            ids: library.ids.clone(),
+            ledger_len: library.ledger.len(),
+            interlibrary: library.interlibrary.clone(),
+            serials: library.serials.clone(),
+            branches: library.branches.clone(),
+        }
+    }
+
+    fn restore(self, library: &mut Library) {
+        library.books = self.books;
+        library.loans = self.loans;
+        library.holds = self.holds;
+        library.ids = self.ids;
+        library.ledger.truncate(self.ledger_len);
+        library.interlibrary = self.interlibrary;
+        library.serials = self.serials;
+        library.branches = self.branches;
+    }
 }
+
 #[warn(dead_code)]
 impl Library {
     pub fn new() -> Self {
@@ -26,6 +120,19 @@ impl Library {
             books: Vec::new(),
             users: Vec::new(),
             loans: Vec::new(),
+            ids: IdCounters::default(),
+            policies: LibraryPolicies::default(),
+            ledger: Vec::new(),
+            holds: Vec::new(),
+            calendar: LibraryCalendar::default(),
+            archive: Archive::default(),
+            interlibrary: Interlibrary::default(),
+            serials: Serials::default(),
+            digital: DigitalCollection::default(),
+            branches: Branches::default(),
+            households: Vec::new(),
+            unloaded: Vec::new(),
+            set_aside_loans: 0,
         }
     }
     fn ensure_file_exists(file_path: &str) -> std::io::Result<()> {
@@ -36,66 +143,2461 @@ impl Library {
         Ok(())
     }
 
-    pub fn load_data(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        Self::ensure_file_exists("books.json")?;
-        Self::ensure_file_exists("users.json")?;
-        Self::ensure_file_exists("loans.json")?;
+    /// Runs `read` for `file`. A failure is noted in `failures` and leaves
+    /// the collection empty.
+    fn load_file<T: Default>(
+        failures: &mut Vec<(&'static str, Box<dyn Error>)>,
+        file: &'static str,
+        read: impl FnOnce() -> Result<T, Box<dyn Error>>,
+    ) -> T {
+        read().unwrap_or_else(|err| {
+            failures.push((file, err));
+            T::default()
+        })
+    }
+
+    /// Loads every file it can. Files that fail are reported together and
+    /// are not saved back by `save_data`, so their contents are not lost.
+    pub fn load_data(&mut self) -> Result<(), Box<dyn Error>> {
+        let mut failures = Vec::new();
+        let failed = &mut failures;
+
+        self.books = Self::load_file(failed, "books.json", || {
+            Self::ensure_file_exists("books.json")?;
+            Ok(book_handlers::read_books()?)
+        });
+        self.users = Self::load_file(failed, "users.json", || {
+            Self::ensure_file_exists("users.json")?;
+            Ok(user_handlers::read_users()?)
+        });
+        (self.loans, self.set_aside_loans) = Self::load_file(failed, "loans.json", || {
+            Self::ensure_file_exists("loans.json")?;
+            Ok(loan_handlers::read_loans()?)
+        });
+        self.ids = Self::load_file(failed, "ids.json", || Ok(id_handlers::read_counters()?));
+        self.policies = Self::load_file(failed, "policies.json", || {
+            Ok(policy_handlers::read_policies()?)
+        });
+        self.ledger = Self::load_file(failed, "ledger.json", || Ok(fine_handlers::read_ledger()?));
+        self.holds = Self::load_file(failed, "holds.json", || Ok(hold_handlers::read_holds()?));
+        self.calendar = Self::load_file(failed, "calendar.json", || {
+            Ok(calendar_handlers::read_calendar()?)
+        });
+        self.archive = Self::load_file(failed, "archive.json", || {
+            Ok(integrity_handlers::read_archive()?)
+        });
+        self.interlibrary = Self::load_file(failed, "interlibrary.json", || {
+            Ok(interlibrary_handlers::read_interlibrary()?)
+        });
+        self.serials = Self::load_file(failed, "serials.json", || {
+            Ok(serial_handlers::read_serials()?)
+        });
+        self.digital = Self::load_file(failed, "digital.json", || {
+            Ok(digital_handlers::read_collection()?)
+        });
+        self.branches = Self::load_file(failed, "branches.json", || {
+            Ok(branch_handlers::read_branches()?)
+        });
+        self.households = Self::load_file(failed, "households.json", || {
+            Ok(household_handlers::read_households()?)
+        });
+        self.unloaded = failures.iter().map(|(file, _)| *file).collect();
+
+        id_handlers::sync_with_existing(&mut self.ids.books, self.books.iter().map(|b| b.id))?;
+        id_handlers::sync_with_existing(&mut self.ids.users, self.users.iter().map(|u| u.id))?;
+        id_handlers::sync_with_existing(&mut self.ids.loans, self.loans.iter().map(|l| l.id))?;
+        id_handlers::sync_with_existing(&mut self.ids.ledger, self.ledger.iter().map(|e| e.id))?;
+        id_handlers::sync_with_existing(&mut self.ids.holds, self.holds.iter().map(|h| h.id))?;
+        id_handlers::sync_with_existing(
+            &mut self.ids.partners,
+            self.interlibrary.partners.iter().map(|p| p.id),
+        )?;
+        id_handlers::sync_with_existing(
+            &mut self.ids.ill_requests,
+            self.interlibrary.requests.iter().map(|r| r.id),
+        )?;
+        id_handlers::sync_with_existing(
+            &mut self.ids.serials,
+            self.serials.titles.iter().map(|s| s.id),
+        )?;
+        id_handlers::sync_with_existing(
+            &mut self.ids.serial_issues,
+            self.serials.issues.iter().map(|i| i.id),
+        )?;
+        id_handlers::sync_with_existing(
+            &mut self.ids.digital_titles,
+            self.digital.titles.iter().map(|t| t.id),
+        )?;
+        id_handlers::sync_with_existing(
+            &mut self.ids.licenses,
+            self.digital
+                .titles
+                .iter()
+                .flat_map(|t| t.licenses.iter().map(|l| l.id)),
+        )?;
+        id_handlers::sync_with_existing(
+            &mut self.ids.digital_loans,
+            self.digital.loans.iter().map(|l| l.id),
+        )?;
+        id_handlers::sync_with_existing(
+            &mut self.ids.digital_holds,
+            self.digital.waitlist.iter().map(|h| h.id),
+        )?;
+        id_handlers::sync_with_existing(
+            &mut self.ids.branches,
+            self.branches.branches.iter().map(|b| b.id),
+        )?;
+        id_handlers::sync_with_existing(
+            &mut self.ids.transfers,
+            self.branches.transfers.iter().map(|t| t.id),
+        )?;
+        id_handlers::sync_with_existing(
+            &mut self.ids.households,
+            self.households.iter().map(|h| h.id),
+        )?;
+        loan_handlers::assign_missing_ids(&mut self.loans, &mut self.ids.loans)?;
+
+        if failures.is_empty() {
+            return Ok(());
+        }
+        let messages: Vec<String> = failures
+            .iter()
+            .map(|(file, err)| format!("{}: {}", file, err))
+            .collect();
+        Err(messages.join("; ").into())
+    }
+
+    /// How many records `load_data` moved out of `loans.json` because they
+    /// could not be read as loans.
+    pub fn set_aside_loans(&self) -> usize {
+        self.set_aside_loans
+    }
+
+    /// Runs `save` unless `file` failed to load.
+    fn save_file<E: Error + 'static>(
+        &self,
+        file: &str,
+        save: impl FnOnce() -> Result<(), E>,
+    ) -> Result<(), Box<dyn Error>> {
+        if self.unloaded.contains(&file) {
+            return Ok(());
+        }
+        Ok(save()?)
+    }
+
+    /// Saves every file except those that failed to load, which are named in
+    /// the returned error.
+    pub fn save_data(&self) -> Result<(), Box<dyn Error>> {
+        self.save_file("books.json", || book_handlers::save_books(&self.books))?;
+        self.save_file("users.json", || user_handlers::save_users(&self.users))?;
+        self.save_file("loans.json", || loan_handlers::save_loans(&self.loans))?;
+        self.save_file("ids.json", || id_handlers::save_counters(&self.ids))?;
+        self.save_file("policies.json", || {
+            policy_handlers::save_policies(&self.policies)
+        })?;
+        self.save_file("ledger.json", || fine_handlers::save_ledger(&self.ledger))?;
+        self.save_file("holds.json", || hold_handlers::save_holds(&self.holds))?;
+        self.save_file("calendar.json", || {
+            calendar_handlers::save_calendar(&self.calendar)
+        })?;
+        self.save_file("archive.json", || {
+            integrity_handlers::save_archive(&self.archive)
+        })?;
+        self.save_file("interlibrary.json", || {
+            interlibrary_handlers::save_interlibrary(&self.interlibrary)
+        })?;
+        self.save_file("serials.json", || {
+            serial_handlers::save_serials(&self.serials)
+        })?;
+        self.save_file("digital.json", || {
+            digital_handlers::save_collection(&self.digital)
+        })?;
+        self.save_file("branches.json", || {
+            branch_handlers::save_branches(&self.branches)
+        })?;
+        self.save_file("households.json", || {
+            household_handlers::save_households(&self.households)
+        })?;
+
+        if self.unloaded.is_empty() {
+            return Ok(());
+        }
+        Err(format!(
+            "not saved because they failed to load: {}",
+            self.unloaded.join(", ")
+        )
+        .into())
+    }
+
+    /// Runs `operation` as one unit: if it fails, every change it made is
+    /// undone and the library is left exactly as it was.
+    ///
+    /// Only the circulation state is copied for the rollback (see
+    /// `Snapshot`); an operation run here must not change anything else.
+    pub fn transaction<T, E>(
+        &mut self,
+        operation: impl FnOnce(&mut Library) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let snapshot = Snapshot::take(self);
+        let result = operation(self);
+        if result.is_err() {
+            snapshot.restore(self);
+        }
+        result
+    }
+
+    /// Adds a book and returns its id. A book with id 0 gets the next free id.
+    pub fn add_book(&mut self, mut book: Book) -> Result<u32, BookError> {
+        if book.id == 0 {
+            book.id = self.ids.books.next_id;
+        }
+        if book.current_branch.is_none() {
+            book.current_branch = book.home_branch;
+        }
+
+        let id = book.id;
+        let mut ids = self.ids.books.clone();
+        ids.reserve(id)?;
+        book_handlers::add_book(&mut self.books, book)?;
+        self.ids.books = ids;
+        Ok(id)
+    }
+
+    /// Deletes a book that is not on loan or on hold, dealing with its
+    /// history as `policy` says.
+    pub fn remove_book(&mut self, book_id: u32, policy: DeletePolicy) -> Result<(), DeleteError> {
+        if !self.books.iter().any(|b| b.id == book_id) {
+            return Err(BookError::BookNotFound.into());
+        }
+        self.remove_history(Subject::Book(book_id), policy)?;
+        Ok(book_handlers::delete_book_by_id(&mut self.books, book_id)?)
+    }
+
+    /// Deletes a patron with no books out, pending holds or unpaid fines,
+    /// dealing with their history as `policy` says. Deleting a guardian
+    /// dissolves their household.
+    pub fn remove_user(&mut self, user_id: u32, policy: DeletePolicy) -> Result<(), DeleteError> {
+        if !self.users.iter().any(|u| u.id == user_id) {
+            return Err(UserError::UserNotFound.into());
+        }
+        self.remove_history(Subject::User(user_id), policy)?;
+        user_handlers::delete_user(&mut self.users, user_id)?;
+        household_handlers::forget_user(&mut self.households, user_id);
+        Ok(())
+    }
+
+    fn remove_history(
+        &mut self,
+        subject: Subject,
+        policy: DeletePolicy,
+    ) -> Result<(), DeleteError> {
+        let mut dependents =
+            integrity_handlers::dependents(&self.loans, &self.holds, &self.ledger, subject);
+        dependents.open_ill_requests =
+            integrity_handlers::open_ill_requests(&self.interlibrary.requests, subject);
+        dependents.open_transfers =
+            integrity_handlers::open_transfers(&self.branches.transfers, subject);
+        let (digital_loans, digital_holds) =
+            integrity_handlers::open_digital(&self.digital, subject);
+        dependents.active_loans += digital_loans;
+        dependents.pending_holds += digital_holds;
+        dependents.history_records +=
+            integrity_handlers::catalogued_issues(&self.serials.issues, subject);
+        integrity_handlers::check_deletable(&dependents, policy)?;
 
-        self.books = book_handlers::read_books()?;
-        self.users = user_handlers::read_users()?;
-        self.loans = loan_handlers::read_loans()?;
+        match policy {
+            DeletePolicy::Block => {}
+            DeletePolicy::CascadeArchive => integrity_handlers::archive_history(
+                &mut self.loans,
+                &mut self.holds,
+                &mut self.ledger,
+                &mut self.archive,
+                subject,
+            ),
+            DeletePolicy::Anonymize => integrity_handlers::anonymize_history(
+                &mut self.loans,
+                &mut self.holds,
+                &mut self.ledger,
+                subject,
+            ),
+        }
+        if policy != DeletePolicy::Block {
+            integrity_handlers::detach_issues(&mut self.serials.issues, subject);
+        }
         Ok(())
     }
 
-    pub fn save_data(&self) -> Result<(), Box<dyn std::error::Error>> {
-        book_handlers::save_books(&self.books)?;
-        user_handlers::save_users(&self.users)?;
-        loan_handlers::save_loans(&self.loans)?;
+    pub fn set_book_location(
+        &mut self,
+        book_id: u32,
+        call_number: Option<CallNumber>,
+        shelf_location: Option<String>,
+    ) -> Result<(), BookError> {
+        book_handlers::set_location(&mut self.books, book_id, call_number, shelf_location)
+    }
+
+    pub fn shelf_list(
+        &self,
+        scheme: Classification,
+        from: &str,
+        to: &str,
+    ) -> Result<Vec<Book>, BookError> {
+        let from = CallNumber::parse(scheme, from)?;
+        let to = CallNumber::parse(scheme, to)?;
+        Ok(book_handlers::shelf_list(&self.books, &from, &to))
+    }
+
+    pub fn print_shelf_list(
+        &self,
+        scheme: Classification,
+        from: &str,
+        to: &str,
+    ) -> Result<(), BookError> {
+        let books = self.shelf_list(scheme, from, to)?;
+        book_handlers::print_shelf_list(&books);
         Ok(())
     }
 
-    pub fn add_book(&mut self, book: Book) -> Result<(), BookError> {
-        book_handlers::add_book(&mut self.books, book)
+    pub fn send_book_to_repair(
+        &mut self,
+        book_id: u32,
+        reason: &str,
+        date: NaiveDate,
+    ) -> Result<(), BookError> {
+        self.check_not_on_loan(book_id)?;
+        book_handlers::send_to_repair(&mut self.books, book_id, reason, date)
+    }
+
+    pub fn declare_book_lost(
+        &mut self,
+        book_id: u32,
+        reason: &str,
+        date: NaiveDate,
+    ) -> Result<(), BookError> {
+        self.check_not_on_loan(book_id)?;
+        book_handlers::declare_lost(&mut self.books, book_id, reason, date)
+    }
+
+    pub fn withdraw_book(
+        &mut self,
+        book_id: u32,
+        reason: &str,
+        date: NaiveDate,
+    ) -> Result<(), BookError> {
+        self.check_not_on_loan(book_id)?;
+        book_handlers::withdraw_book(&mut self.books, book_id, reason, date)
+    }
+
+    pub fn restore_book_to_shelf(
+        &mut self,
+        book_id: u32,
+        reason: &str,
+        date: NaiveDate,
+    ) -> Result<(), BookError> {
+        self.check_not_on_loan(book_id)?;
+        book_handlers::restore_to_shelf(&mut self.books, book_id, reason, date)
+    }
+
+    /// Manual status changes would leave an active loan open with no way
+    /// to check it in.
+    fn check_not_on_loan(&self, book_id: u32) -> Result<(), BookError> {
+        match self
+            .loans
+            .iter()
+            .find(|l| l.book_id == book_id && l.is_active())
+        {
+            Some(loan) => Err(BookError::OnLoan(loan.id)),
+            None => Ok(()),
+        }
     }
 
-    pub fn remove_book(&mut self, book_id: u32) -> Result<(), BookError> {
-        book_handlers::delete_book_by_id(&mut self.books, book_id)
+    /// Corrects the title, author or material details of a copy.
+    pub fn update_book(
+        &mut self,
+        book_id: u32,
+        title: Option<String>,
+        author: Option<String>,
+        details: Option<MaterialDetails>,
+    ) -> Result<(), BookError> {
+        book_handlers::update_book(&mut self.books, book_id, title, author, details)
     }
 
     pub fn search_books(&self, query: &str) -> Vec<Book> {
         book_handlers::search_books(&self.books, query)
     }
 
+    /// Lists the collection branch by branch, by where each copy is now.
     pub fn list_books(&self) {
-        book_handlers::print_books(&self.books);
+        if self.branches.branches.is_empty() {
+            book_handlers::print_books(&self.books);
+            return;
+        }
+
+        for branch in &self.branches.branches {
+            let here: Vec<Book> = self
+                .books
+                .iter()
+                .filter(|b| b.current_branch == Some(branch.id))
+                .cloned()
+                .collect();
+            println!("=== {} ===", branch.name);
+            book_handlers::print_books(&here);
+        }
+
+        let unassigned: Vec<Book> = self
+            .books
+            .iter()
+            .filter(|b| b.current_branch.is_none())
+            .cloned()
+            .collect();
+        if !unassigned.is_empty() {
+            println!("=== No branch ===");
+            book_handlers::print_books(&unassigned);
+        }
+    }
+
+    /// Adds a user and returns their id. A user with id 0 gets the next free id.
+    pub fn add_user(&mut self, mut user: User) -> Result<u32, UserError> {
+        if user.id == 0 {
+            user.id = self.ids.users.next_id;
+        }
+
+        let id = user.id;
+        let mut ids = self.ids.users.clone();
+        ids.reserve(id)?;
+        user_handlers::add_user(&mut self.users, user)?;
+        self.ids.users = ids;
+        Ok(id)
+    }
+
+    pub fn rename_user(&mut self, user_id: u32, name: String) -> Result<(), UserError> {
+        user_handlers::update_user(&mut self.users, user_id, name)
+    }
+
+    pub fn search_users(&self, query: &str) -> Vec<User> {
+        user_handlers::search_users(&self.users, query)
+    }
+
+    pub fn update_user_contact(
+        &mut self,
+        user_id: u32,
+        contact: ContactDetails,
+        date_of_birth: Option<NaiveDate>,
+    ) -> Result<(), UserError> {
+        user_handlers::update_contact(&mut self.users, user_id, contact, date_of_birth)
+    }
+
+    pub fn set_user_status(
+        &mut self,
+        user_id: u32,
+        status: AccountStatus,
+    ) -> Result<(), UserError> {
+        user_handlers::set_status(&mut self.users, user_id, status)
+    }
+
+    pub fn set_user_category(
+        &mut self,
+        user_id: u32,
+        category: PatronCategory,
+    ) -> Result<(), UserError> {
+        user_handlers::set_category(&mut self.users, user_id, category)
+    }
+
+    /// Sets a loan limit for one patron that replaces their category's limit.
+    /// `None` goes back to the category limit.
+    pub fn set_user_loan_limit(
+        &mut self,
+        user_id: u32,
+        limit: Option<u32>,
+    ) -> Result<(), UserError> {
+        user_handlers::set_loan_limit_override(&mut self.users, user_id, limit)
+    }
+
+    pub fn policy_for_user(&self, user_id: u32) -> Option<LoanPolicy> {
+        self.users
+            .iter()
+            .find(|u| u.id == user_id)
+            .map(|u| policy_handlers::resolve(&self.policies, u.category))
+    }
+
+    /// The patron's policy with the rules for the item's material applied.
+    fn policy_for_item(&self, user_id: u32, book_id: u32) -> Option<LoanPolicy> {
+        let user = self.users.iter().find(|u| u.id == user_id)?;
+        Some(match self.books.iter().find(|b| b.id == book_id) {
+            Some(book) => {
+                policy_handlers::resolve_for_format(&self.policies, user.category, book.format())
+            }
+            None => policy_handlers::resolve(&self.policies, user.category),
+        })
+    }
+
+    pub fn list_policies(&self) {
+        policy_handlers::print_policies(&self.policies);
+    }
+
+    pub fn set_opening_days(&mut self, weekdays: Vec<Weekday>) -> Result<(), CalendarError> {
+        calendar_handlers::set_open_weekdays(&mut self.calendar, weekdays)
+    }
+
+    pub fn add_closure(&mut self, date: NaiveDate, reason: String) -> Result<(), CalendarError> {
+        calendar_handlers::add_closure(&mut self.calendar, date, reason)
+    }
+
+    pub fn remove_closure(&mut self, date: NaiveDate) -> Result<(), CalendarError> {
+        calendar_handlers::remove_closure(&mut self.calendar, date)
+    }
+
+    pub fn print_calendar(&self) {
+        calendar_handlers::print_calendar(&self.calendar);
+    }
+
+    pub fn renew_membership(&mut self, user_id: u32, expiry: NaiveDate) -> Result<(), UserError> {
+        user_handlers::renew_membership(&mut self.users, user_id, expiry)
+    }
+
+    pub fn list_users(&self) {
+        user_handlers::print_users(&self.users, Local::now().date_naive());
     }
 
-    pub fn add_user(&mut self, user: User) -> Result<(), UserError> {
-        user_handlers::add_user(&mut self.users, user)
+    /// Parses a date typed at a prompt; an empty input means today.
+    pub fn parse_date(input: &str) -> Result<NaiveDate, LoanError> {
+        loan_handlers::parse_date(input)
     }
 
     pub fn loan_book(
         &mut self,
         user_id: u32,
         book_id: u32,
-        loan_date: String,
-    ) -> Result<(), LoanError> {
-        loan_handlers::add_loan(
-            &mut self.loans,
-            &self.users,
-            &mut self.books,
+        loan_date: NaiveDate,
+    ) -> Result<Loan, LoanError> {
+        self.check_out(None, user_id, book_id, loan_date, Vec::new())
+    }
+
+    /// Checks out a copy at a branch desk; the copy must be shelved there.
+    pub fn loan_book_at(
+        &mut self,
+        branch_id: u32,
+        user_id: u32,
+        book_id: u32,
+        loan_date: NaiveDate,
+    ) -> Result<Loan, LoanError> {
+        self.branch(branch_id)?;
+        self.check_out(Some(branch_id), user_id, book_id, loan_date, Vec::new())
+    }
+
+    /// Lends equipment, recording the condition of the device and each of
+    /// its accessories as it goes out.
+    pub fn loan_equipment(
+        &mut self,
+        user_id: u32,
+        book_id: u32,
+        loan_date: NaiveDate,
+        checklist: Vec<ConditionCheck>,
+    ) -> Result<Loan, LoanError> {
+        self.check_out(None, user_id, book_id, loan_date, checklist)
+    }
+
+    /// What has to be checked before the item goes out; empty for anything
+    /// but equipment.
+    pub fn equipment_checklist(&self, book_id: u32) -> Vec<String> {
+        self.books
+            .iter()
+            .find(|b| b.id == book_id)
+            .map(|b| b.details.checklist_items())
+            .unwrap_or_default()
+    }
+
+    fn check_out(
+        &mut self,
+        branch_id: Option<u32>,
+        user_id: u32,
+        book_id: u32,
+        loan_date: NaiveDate,
+        checklist: Vec<ConditionCheck>,
+    ) -> Result<Loan, LoanError> {
+        self.transaction(|library| {
+            library.check_household_limit(user_id, 0)?;
+            let request =
+                library.checkout_request(branch_id, user_id, book_id, loan_date, checklist)?;
+            let ready_hold = hold_handlers::ready_hold(&library.holds, user_id, book_id);
+
+            let loan = loan_handlers::add_loan(
+                &mut library.loans,
+                &library.users,
+                &mut library.books,
+                &mut library.ids.loans,
+                request,
+            )?;
+
+            if let Some(hold) = ready_hold {
+                hold_handlers::fulfill_hold(&mut library.holds, hold.id, loan_date)?;
+            }
+            Ok(loan)
+        })
+    }
+
+    /// Gathers what the checkout rules need to know about a patron taking
+    /// `book_id` on `loan_date`.
+    fn checkout_request(
+        &self,
+        branch_id: Option<u32>,
+        user_id: u32,
+        book_id: u32,
+        loan_date: NaiveDate,
+        checklist: Vec<ConditionCheck>,
+    ) -> Result<CheckoutRequest, LoanError> {
+        let policy = self
+            .policy_for_item(user_id, book_id)
+            .ok_or(LoanError::UserNotFound)?;
+        Ok(CheckoutRequest {
             user_id,
             book_id,
             loan_date,
+            policy,
+            balance_cents: self.blocking_balance(user_id),
+            max_balance_cents: self.policies.fines.block_threshold_cents,
+            collecting_hold: hold_handlers::ready_hold(&self.holds, user_id, book_id).is_some(),
+            calendar: self.calendar.clone(),
+            checklist,
+            branch_id,
+        })
+    }
+
+    /// Opens a desk checkout for one patron, refusing straight away a patron
+    /// who may not borrow anything.
+    pub fn start_checkout(
+        &self,
+        user_id: u32,
+        date: NaiveDate,
+    ) -> Result<CheckoutSession, LoanError> {
+        let user = self
+            .users
+            .iter()
+            .find(|u| u.id == user_id)
+            .ok_or(LoanError::UserNotFound)?;
+        let policy = self.policy_for_user(user_id).unwrap_or_default();
+        loan_handlers::check_patron(
+            user,
+            date,
+            self.blocking_balance(user_id),
+            self.policies.fines.block_threshold_cents,
+        )?;
+
+        Ok(CheckoutSession {
+            user_id,
+            date,
+            book_ids: Vec::new(),
+            loan_limit: user.loan_limit(&policy),
+            active_loans: self
+                .loans
+                .iter()
+                .filter(|l| l.user_id == user_id && l.is_active())
+                .count(),
+        })
+    }
+
+    /// Adds an item to the session if the whole session, this item included,
+    /// would pass every checkout rule. Nothing is checked out yet.
+    pub fn add_to_checkout(
+        &self,
+        session: &mut CheckoutSession,
+        book_id: u32,
+    ) -> Result<(), LoanError> {
+        let pending = &session.book_ids;
+        self.check_household_limit(session.user_id, pending.len())?;
+        let request =
+            self.checkout_request(None, session.user_id, book_id, session.date, Vec::new())?;
+        loan_handlers::check_checkout(&self.loans, &self.users, &self.books, &request, pending)?;
+
+        session.book_ids.push(book_id);
+        Ok(())
+    }
+
+    /// Checks out every item in the session, or none if any of them fails.
+    pub fn commit_checkout(
+        &mut self,
+        session: CheckoutSession,
+    ) -> Result<CheckoutReceipt, LoanError> {
+        if session.book_ids.is_empty() {
+            return Err(LoanError::NothingToCheckOut);
+        }
+
+        let loans = self.transaction(|library| {
+            library.check_out_all(session.user_id, &session.book_ids, session.date)
+        })?;
+        let items = loans
+            .into_iter()
+            .map(|loan| CheckoutReceiptItem {
+                title: self
+                    .books
+                    .iter()
+                    .find(|b| b.id == loan.book_id)
+                    .map(|b| b.title.clone())
+                    .unwrap_or_default(),
+                loan,
+            })
+            .collect();
+
+        Ok(CheckoutReceipt {
+            user_id: session.user_id,
+            borrower: self
+                .users
+                .iter()
+                .find(|u| u.id == session.user_id)
+                .map(|u| u.name.clone())
+                .unwrap_or_default(),
+            date: session.date,
+            items,
+        })
+    }
+
+    pub fn print_checkout_receipt(receipt: &CheckoutReceipt) {
+        loan_handlers::print_checkout_receipt(receipt);
+    }
+
+    fn check_out_all(
+        &mut self,
+        user_id: u32,
+        book_ids: &[u32],
+        date: NaiveDate,
+    ) -> Result<Vec<Loan>, LoanError> {
+        book_ids
+            .iter()
+            .map(|&book_id| self.loan_book(user_id, book_id, date))
+            .collect()
+    }
+
+    /// The id of the copy with this barcode or, failing that, this id.
+    pub fn find_book_id(&self, identifier: &str) -> Option<u32> {
+        book_handlers::find_by_identifier(&self.books, identifier).map(|b| b.id)
+    }
+
+    /// Desk check-in: returns the copy with an optional condition note,
+    /// charges any overdue fine, routes the copy to the next hold and
+    /// reports all of it on a receipt.
+    pub fn check_in(
+        &mut self,
+        book_id: u32,
+        return_date: NaiveDate,
+        condition_note: Option<String>,
+    ) -> Result<ReturnReceipt, LoanError> {
+        self.check_in_branch(None, book_id, return_date, condition_note)
+    }
+
+    /// Check-in at a branch desk. A copy that belongs to another branch and
+    /// is not needed for a hold here is sent back to its home branch.
+    pub fn check_in_at(
+        &mut self,
+        branch_id: u32,
+        book_id: u32,
+        return_date: NaiveDate,
+        condition_note: Option<String>,
+    ) -> Result<ReturnReceipt, LoanError> {
+        self.branch(branch_id)?;
+        self.check_in_branch(Some(branch_id), book_id, return_date, condition_note)
+    }
+
+    fn check_in_branch(
+        &mut self,
+        branch_id: Option<u32>,
+        book_id: u32,
+        return_date: NaiveDate,
+        condition_note: Option<String>,
+    ) -> Result<ReturnReceipt, LoanError> {
+        self.transaction(|library| {
+            let mut loan = loan_handlers::check_in(
+                &mut library.loans,
+                &mut library.books,
+                book_id,
+                return_date,
+                condition_note,
+            )?;
+            if let Some(branch_id) = branch_id {
+                library.record_return_branch(&mut loan, branch_id);
+            }
+            let fine_cents = library.charge_overdue_fine(&loan)?;
+            library.route_to_next_hold(book_id, return_date)?;
+            let transfer_to = library.send_home(book_id, return_date)?;
+
+            let held_for = library
+                .holds
+                .iter()
+                .find(|h| h.book_id == book_id && h.status == HoldStatus::ReadyForPickup)
+                .map(|h| h.user_id);
+            let borrower = library
+                .users
+                .iter()
+                .find(|u| u.id == loan.user_id)
+                .map(|u| u.name.clone())
+                .unwrap_or_default();
+            let title = library
+                .books
+                .iter()
+                .find(|b| b.id == book_id)
+                .map(|b| b.title.clone())
+                .unwrap_or_default();
+
+            Ok(ReturnReceipt {
+                days_overdue: loan.days_overdue(return_date),
+                loan,
+                borrower,
+                title,
+                fine_cents,
+                held_for,
+                transfer_to,
+            })
+        })
+    }
+
+    fn record_return_branch(&mut self, loan: &mut Loan, branch_id: u32) {
+        loan.return_branch_id = Some(branch_id);
+        if let Some(stored) = self.loans.iter_mut().find(|l| l.id == loan.id) {
+            stored.return_branch_id = Some(branch_id);
+        }
+        if let Some(book) = self.books.iter_mut().find(|b| b.id == loan.book_id) {
+            book.current_branch = Some(branch_id);
+        }
+    }
+
+    /// Starts a transfer back to the home branch for a copy on the shelf
+    /// somewhere else. Returns the name of the home branch if one started.
+    fn send_home(&mut self, book_id: u32, date: NaiveDate) -> Result<Option<String>, BranchError> {
+        let Some(book) = self.books.iter().find(|b| b.id == book_id) else {
+            return Ok(None);
+        };
+        let Some(home) = book.home_branch else {
+            return Ok(None);
+        };
+        if !book.is_available() || book.current_branch.is_none_or(|at| at == home) {
+            return Ok(None);
+        }
+
+        branch_handlers::send_transfer(
+            &mut self.branches,
+            &mut self.books,
+            &mut self.ids.transfers,
+            book_id,
+            home,
+            date,
+            "Return to home branch".to_string(),
+        )?;
+        Ok(Some(branch_handlers::branch_name(
+            &self.branches.branches,
+            home,
+        )))
+    }
+
+    pub fn print_return_receipt(receipt: &ReturnReceipt) {
+        loan_handlers::print_return_receipt(receipt);
+    }
+
+    /// Closes the loan of a copy returned damaged, charging any overdue fine
+    /// plus the replacement cost. Returns the total charged in cents.
+    pub fn return_damaged(
+        &mut self,
+        book_id: u32,
+        return_date: NaiveDate,
+    ) -> Result<i64, LoanError> {
+        self.transaction(|library| {
+            let loan = loan_handlers::return_damaged(
+                &mut library.loans,
+                &mut library.books,
+                book_id,
+                return_date,
+            )?;
+            let fine = library.charge_overdue_fine(&loan)?;
+            let replacement =
+                library.charge_replacement(&loan, ChargeReason::DamagedItem, return_date)?;
+            Ok(fine + replacement)
+        })
+    }
+
+    /// Closes the loan of a copy the borrower has lost and bills its
+    /// replacement cost. Returns the amount charged in cents.
+    pub fn declare_loan_lost(&mut self, book_id: u32, date: NaiveDate) -> Result<i64, LoanError> {
+        self.transaction(|library| {
+            let loan =
+                loan_handlers::declare_lost(&mut library.loans, &mut library.books, book_id, date)?;
+            Ok(library.charge_replacement(&loan, ChargeReason::LostItem, date)?)
+        })
+    }
+
+    /// Takes back a copy that was declared lost and credits the patron for
+    /// the part of the replacement charge that was not waived. Returns the
+    /// credit in cents.
+    pub fn return_found_item(&mut self, book_id: u32, date: NaiveDate) -> Result<i64, LoanError> {
+        self.transaction(|library| {
+            let loan = loan_handlers::return_found_item(
+                &mut library.loans,
+                &mut library.books,
+                book_id,
+                date,
+            )?;
+            let charged =
+                fine_handlers::charged_for_loan(&library.ledger, loan.id, ChargeReason::LostItem);
+            let waived = fine_handlers::waived_for_loan(&library.ledger, loan.id);
+            let credit = (charged - waived).max(0);
+
+            if credit > 0 {
+                fine_handlers::post_entry(
+                    &mut library.ledger,
+                    &mut library.ids.ledger,
+                    LedgerEntry::credit(
+                        loan.user_id,
+                        date,
+                        credit,
+                        Some(loan.id),
+                        "Lost item returned".to_string(),
+                    ),
+                )?;
+            }
+            library.route_to_next_hold(book_id, date)?;
+            Ok(credit)
+        })
+    }
+
+    /// Puts a copy on the hold shelf for the next patron in its queue, or
+    /// back on the open shelf when nobody is waiting.
+    fn route_to_next_hold(&mut self, book_id: u32, date: NaiveDate) -> Result<(), BookError> {
+        let pickup_window_days = self.policies.holds.pickup_window_days;
+        let next = hold_handlers::assign_next(&mut self.holds, book_id, date, pickup_window_days);
+        let Some(book) = self.books.iter_mut().find(|b| b.id == book_id) else {
+            return Ok(());
+        };
+
+        match next {
+            Some(hold) if book.status != ItemStatus::OnHoldShelf => book.transition(
+                ItemStatus::OnHoldShelf,
+                &format!("Held for user {} (hold {})", hold.user_id, hold.id),
+                date,
+            ),
+            None if book.status == ItemStatus::OnHoldShelf => {
+                book.transition(ItemStatus::Available, "No holds waiting", date)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Places a hold. When the patron's policy allows recalls and the copy
+    /// is out, the current loan is recalled and its notice returned; if the
+    /// recall fails, no hold is placed.
+    pub fn place_hold(
+        &mut self,
+        user_id: u32,
+        book_id: u32,
+        date: NaiveDate,
+    ) -> Result<(u32, Option<RecallNotice>), HoldError> {
+        self.transaction(|library| {
+            let hold_id = hold_handlers::place_hold(
+                &mut library.holds,
+                &library.users,
+                &library.books,
+                &mut library.ids.holds,
+                user_id,
+                book_id,
+                date,
+            )?;
+
+            let can_recall = library
+                .policy_for_user(user_id)
+                .is_some_and(|policy| policy.can_recall);
+            let recallable = library
+                .loans
+                .iter()
+                .find(|l| l.book_id == book_id && l.is_active() && !l.is_recalled())
+                .map(|l| l.id);
+            let notice = match recallable {
+                Some(loan_id) if can_recall => Some(
+                    library
+                        .recall(loan_id, date, Some(hold_id))
+                        .map_err(HoldError::RecallFailed)?,
+                ),
+                _ => None,
+            };
+
+            Ok((hold_id, notice))
+        })
+    }
+
+    /// Asks the borrower to bring the item back early, shortening the loan
+    /// to the recall period.
+    pub fn recall_loan(
+        &mut self,
+        loan_id: u32,
+        date: NaiveDate,
+    ) -> Result<RecallNotice, LoanError> {
+        self.recall(loan_id, date, None)
+    }
+
+    pub fn print_recall_notice(notice: &RecallNotice) {
+        loan_handlers::print_recall_notice(notice);
+    }
+
+    fn recall(
+        &mut self,
+        loan_id: u32,
+        date: NaiveDate,
+        hold_id: Option<u32>,
+    ) -> Result<RecallNotice, LoanError> {
+        let loan = loan_handlers::recall_loan(
+            &mut self.loans,
+            loan_id,
+            date,
+            &self.policies.recalls,
+            &self.calendar,
+            hold_id,
+        )?;
+
+        Ok(RecallNotice {
+            borrower: self
+                .users
+                .iter()
+                .find(|u| u.id == loan.user_id)
+                .map(|u| u.name.clone())
+                .unwrap_or_default(),
+            title: self
+                .books
+                .iter()
+                .find(|b| b.id == loan.book_id)
+                .map(|b| b.title.clone())
+                .unwrap_or_default(),
+            loan,
+        })
+    }
+
+    pub fn cancel_hold(&mut self, hold_id: u32, date: NaiveDate) -> Result<(), HoldError> {
+        self.transaction(|library| {
+            let hold = hold_handlers::cancel_hold(&mut library.holds, hold_id, date)?;
+            if hold.status == HoldStatus::ReadyForPickup {
+                library.route_to_next_hold(hold.book_id, date)?;
+            }
+            Ok(())
+        })
+    }
+
+    pub fn hold_queue_position(&self, hold_id: u32) -> Result<usize, HoldError> {
+        hold_handlers::queue_position(&self.holds, hold_id)
+    }
+
+    pub fn hold_queue(&self, book_id: u32) -> Vec<Hold> {
+        hold_handlers::get_queue(&self.holds, book_id)
+    }
+
+    /// Expires holds not picked up in time and passes each copy on to the
+    /// next patron in line. Returns the expired holds.
+    pub fn expire_holds(&mut self, date: NaiveDate) -> Result<Vec<Hold>, HoldError> {
+        self.transaction(|library| {
+            let expired = hold_handlers::expire_holds(&mut library.holds, date);
+            for hold in &expired {
+                library.route_to_next_hold(hold.book_id, date)?;
+            }
+            Ok(expired)
+        })
+    }
+
+    pub fn list_user_holds(&self, user_id: u32) {
+        let holds = hold_handlers::get_holds_by_user(&self.holds, user_id);
+        if holds.is_empty() {
+            println!("No pending holds for user {}.", user_id);
+        } else {
+            hold_handlers::print_holds(&holds, &self.holds, &self.books);
+        }
+    }
+
+    pub fn add_partner_library(&mut self, name: String, contact: String) -> Result<u32, IllError> {
+        interlibrary_handlers::add_partner(
+            &mut self.interlibrary.partners,
+            &mut self.ids.partners,
+            name,
+            contact,
         )
     }
 
-    pub fn return_book(&mut self, book_id: u32, return_date: String) -> Result<(), LoanError> {
-        loan_handlers::return_loan(&mut self.loans, &mut self.books, book_id, return_date)
+    /// Asks a partner library for an item one of our patrons needs.
+    pub fn request_from_partner(
+        &mut self,
+        partner_id: u32,
+        user_id: u32,
+        title: String,
+        author: String,
+        date: NaiveDate,
+    ) -> Result<u32, IllError> {
+        let request = IllRequest::outgoing(partner_id, user_id, title, author, date);
+        interlibrary_handlers::open_request(
+            &mut self.interlibrary,
+            &self.users,
+            &self.books,
+            &mut self.ids.ill_requests,
+            request,
+        )
     }
 
-    pub fn list_active_loans(&self) {
-        let active_loans = loan_handlers::get_active_loans(&self.loans);
-        loan_handlers::print_loans(&active_loans);
+    /// Records a partner library's request for one of our copies.
+    pub fn lend_to_partner(
+        &mut self,
+        partner_id: u32,
+        book_id: u32,
+        date: NaiveDate,
+    ) -> Result<u32, IllError> {
+        let request = IllRequest::incoming(partner_id, book_id, date);
+        interlibrary_handlers::open_request(
+            &mut self.interlibrary,
+            &self.users,
+            &self.books,
+            &mut self.ids.ill_requests,
+            request,
+        )
+    }
+
+    /// Marks an interlibrary item as shipped. The due date comes from the
+    /// lending library: for items we lend it defaults to our own lending
+    /// period, for items we borrow it must be given.
+    pub fn ship_ill_item(
+        &mut self,
+        request_id: u32,
+        date: NaiveDate,
+        due_date: Option<NaiveDate>,
+    ) -> Result<IllRequest, IllError> {
+        let direction = self
+            .interlibrary
+            .requests
+            .iter()
+            .find(|r| r.id == request_id)
+            .map(|r| r.direction)
+            .ok_or(IllError::RequestNotFound)?;
+        let due_date = match (due_date, direction) {
+            (Some(due_date), _) => due_date,
+            (None, IllDirection::Incoming) => calendar_handlers::due_date(
+                &self.calendar,
+                date,
+                self.policies.interlibrary.lending_period_days,
+            )
+            .ok_or(IllError::MissingDueDate)?,
+            (None, IllDirection::Outgoing) => return Err(IllError::MissingDueDate),
+        };
+
+        interlibrary_handlers::ship(
+            &mut self.interlibrary,
+            &mut self.books,
+            request_id,
+            date,
+            due_date,
+        )
+    }
+
+    pub fn receive_ill_item(
+        &mut self,
+        request_id: u32,
+        date: NaiveDate,
+    ) -> Result<IllRequest, IllError> {
+        interlibrary_handlers::receive(&mut self.interlibrary, request_id, date)
+    }
+
+    /// Closes an interlibrary loan once the item is back with its lender.
+    /// Our own copies are offered to the next hold in line.
+    pub fn return_ill_item(
+        &mut self,
+        request_id: u32,
+        date: NaiveDate,
+    ) -> Result<IllRequest, IllError> {
+        self.transaction(|library| {
+            let request = interlibrary_handlers::return_item(
+                &mut library.interlibrary,
+                &mut library.books,
+                request_id,
+                date,
+            )?;
+            if let (IllDirection::Incoming, Some(book_id)) = (request.direction, request.book_id) {
+                library.route_to_next_hold(book_id, date)?;
+            }
+            Ok(request)
+        })
+    }
+
+    pub fn cancel_ill_request(
+        &mut self,
+        request_id: u32,
+        date: NaiveDate,
+    ) -> Result<IllRequest, IllError> {
+        interlibrary_handlers::cancel(&mut self.interlibrary, request_id, date)
+    }
+
+    pub fn list_partner_libraries(&self) {
+        if self.interlibrary.partners.is_empty() {
+            println!("No partner libraries registered.");
+        } else {
+            interlibrary_handlers::print_partners(&self.interlibrary.partners);
+        }
+    }
+
+    pub fn list_open_ill_requests(&self) {
+        let open = interlibrary_handlers::get_open_requests(&self.interlibrary.requests);
+        if open.is_empty() {
+            println!("No open interlibrary requests.");
+        } else {
+            interlibrary_handlers::print_requests(&open, &self.interlibrary.partners);
+        }
+    }
+
+    pub fn list_overdue_ill_requests(&self, as_of: NaiveDate) {
+        let overdue =
+            interlibrary_handlers::get_overdue_requests(&self.interlibrary.requests, as_of);
+        if overdue.is_empty() {
+            println!("No overdue interlibrary loans.");
+        } else {
+            interlibrary_handlers::print_requests(&overdue, &self.interlibrary.partners);
+        }
+    }
+
+    pub fn add_serial(
+        &mut self,
+        title: String,
+        publisher: String,
+        frequency: Frequency,
+        first_issue: NaiveDate,
+    ) -> Result<u32, SerialError> {
+        let serial = SerialTitle::new(0, title, publisher, frequency, first_issue);
+        serial_handlers::add_serial(&mut self.serials.titles, &mut self.ids.serials, serial)
+    }
+
+    /// Adds the issues of a serial expected up to `through`.
+    pub fn predict_serial_issues(
+        &mut self,
+        serial_id: u32,
+        through: NaiveDate,
+    ) -> Result<Vec<SerialIssue>, SerialError> {
+        serial_handlers::predict_issues(
+            &mut self.serials,
+            &mut self.ids.serial_issues,
+            serial_id,
+            through,
+        )
+    }
+
+    /// Checks in a received issue and catalogues it as a periodical so it
+    /// can be loaned. Returns the new book id.
+    pub fn check_in_issue(&mut self, issue_id: u32, date: NaiveDate) -> Result<u32, SerialError> {
+        let issue = serial_handlers::receivable_issue(&self.serials.issues, issue_id)?;
+        let serial = self
+            .serials
+            .titles
+            .iter()
+            .find(|s| s.id == issue.serial_id)
+            .ok_or(SerialError::SerialNotFound)?;
+
+        let book = Book::with_details(
+            0,
+            format!("{} ({})", serial.title, issue.label()),
+            serial.publisher.clone(),
+            MaterialDetails::Periodical { pages: 0 },
+        );
+
+        self.transaction(|library| {
+            let book_id = library.add_book(book)?;
+            serial_handlers::check_in_issue(&mut library.serials.issues, issue_id, date, book_id)?;
+            Ok(book_id)
+        })
+    }
+
+    pub fn claim_issue(&mut self, issue_id: u32, date: NaiveDate) -> Result<(), SerialError> {
+        serial_handlers::claim_issue(&mut self.serials.issues, issue_id, date)?;
+        Ok(())
+    }
+
+    /// Missing and late issues to claim from publishers.
+    pub fn serial_claims(&self, as_of: NaiveDate) -> Vec<IssueClaim> {
+        serial_handlers::claims_due(
+            &self.serials.issues,
+            as_of,
+            self.policies.serials.claim_grace_days,
+        )
+    }
+
+    pub fn print_serial_claims(&self, as_of: NaiveDate) {
+        let claims = self.serial_claims(as_of);
+        if claims.is_empty() {
+            println!("No issues to claim as of {}.", as_of);
+        } else {
+            serial_handlers::print_claims(&claims, &self.serials.titles);
+        }
+    }
+
+    pub fn list_serials(&self) {
+        if self.serials.titles.is_empty() {
+            println!("No serials registered.");
+        } else {
+            serial_handlers::print_serials(&self.serials.titles);
+        }
+    }
+
+    pub fn list_serial_issues(&self, serial_id: u32) {
+        let issues = serial_handlers::get_issues(&self.serials.issues, serial_id);
+        if issues.is_empty() {
+            println!("No issues for serial {}.", serial_id);
+        } else {
+            serial_handlers::print_issues(&issues);
+        }
+    }
+
+    pub fn add_digital_title(
+        &mut self,
+        title: String,
+        author: String,
+    ) -> Result<u32, DigitalError> {
+        digital_handlers::add_title(
+            &mut self.digital.titles,
+            &mut self.ids.digital_titles,
+            DigitalTitle::new(0, title, author),
+        )
+    }
+
+    /// Adds a license to a digital title. Patrons on its waitlist get the
+    /// new capacity straight away; their loans are returned.
+    pub fn add_license(
+        &mut self,
+        title_id: u32,
+        license: License,
+        date: NaiveDate,
+    ) -> Result<Vec<DigitalLoan>, DigitalError> {
+        digital_handlers::add_license(
+            &mut self.digital.titles,
+            &mut self.ids.licenses,
+            title_id,
+            license,
+        )?;
+        Ok(self.assign_digital_waitlist(date))
+    }
+
+    pub fn borrow_digital(
+        &mut self,
+        user_id: u32,
+        title_id: u32,
+        date: NaiveDate,
+    ) -> Result<DigitalLoan, DigitalError> {
+        self.expire_digital_loans(date);
+
+        let user = self
+            .users
+            .iter()
+            .find(|u| u.id == user_id)
+            .ok_or(DigitalError::UserNotFound)?;
+        loan_handlers::check_patron(
+            user,
+            date,
+            self.blocking_balance(user_id),
+            self.policies.fines.block_threshold_cents,
+        )?;
+
+        let mut patrons = self.waiting_digital_patrons(date);
+        if !patrons.iter().any(|u| u.id == user_id) {
+            patrons.push(user.clone());
+        }
+        digital_handlers::checkout(
+            &mut self.digital,
+            &patrons,
+            &mut self.ids.digital_loans,
+            title_id,
+            user_id,
+            date,
+            self.policies.digital.loan_period_days,
+        )
+    }
+
+    pub fn join_digital_waitlist(
+        &mut self,
+        user_id: u32,
+        title_id: u32,
+        date: NaiveDate,
+    ) -> Result<u32, DigitalError> {
+        self.expire_digital_loans(date);
+        digital_handlers::place_hold(
+            &mut self.digital,
+            &self.users,
+            &mut self.ids.digital_holds,
+            title_id,
+            user_id,
+            date,
+        )
+    }
+
+    pub fn leave_digital_waitlist(&mut self, hold_id: u32) -> Result<(), DigitalError> {
+        digital_handlers::cancel_hold(&mut self.digital.waitlist, hold_id)
+    }
+
+    /// Ends a digital loan early and hands the license to the waitlist.
+    /// Returns the loans created for waiting patrons.
+    pub fn return_digital(
+        &mut self,
+        loan_id: u32,
+        date: NaiveDate,
+    ) -> Result<Vec<DigitalLoan>, DigitalError> {
+        digital_handlers::return_early(&mut self.digital.loans, loan_id, date)?;
+        Ok(self.assign_digital_waitlist(date))
+    }
+
+    /// Closes digital loans that reached their due date and passes the freed
+    /// licenses to the waitlist. Returns the loans created for waiting
+    /// patrons.
+    pub fn expire_digital_loans(&mut self, date: NaiveDate) -> Vec<DigitalLoan> {
+        digital_handlers::expire_loans(&mut self.digital.loans, date);
+        self.assign_digital_waitlist(date)
+    }
+
+    fn assign_digital_waitlist(&mut self, date: NaiveDate) -> Vec<DigitalLoan> {
+        let patrons = self.waiting_digital_patrons(date);
+        digital_handlers::assign_waitlist(
+            &mut self.digital,
+            &patrons,
+            &mut self.ids.digital_loans,
+            date,
+            self.policies.digital.loan_period_days,
+        )
+    }
+
+    /// Patrons on a digital waitlist who may borrow on `date`.
+    fn waiting_digital_patrons(&self, date: NaiveDate) -> Vec<User> {
+        self.users
+            .iter()
+            .filter(|u| {
+                self.digital
+                    .waitlist
+                    .iter()
+                    .any(|h| h.user_id == u.id && h.is_waiting())
+            })
+            .filter(|u| {
+                loan_handlers::check_patron(
+                    u,
+                    date,
+                    self.blocking_balance(u.id),
+                    self.policies.fines.block_threshold_cents,
+                )
+                .is_ok()
+            })
+            .cloned()
+            .collect()
+    }
+
+    pub fn list_digital_titles(&self, date: NaiveDate) {
+        if self.digital.titles.is_empty() {
+            println!("No digital titles registered.");
+        } else {
+            digital_handlers::print_titles(&self.digital, date);
+        }
+    }
+
+    pub fn list_user_digital_loans(&self, user_id: u32, date: NaiveDate) {
+        let loans = digital_handlers::get_user_loans(&self.digital.loans, user_id, date);
+        if loans.is_empty() {
+            println!("No digital loans for user {}.", user_id);
+        } else {
+            digital_handlers::print_loans(&loans, &self.digital.titles);
+        }
+    }
+
+    pub fn add_branch(&mut self, name: String, address: String) -> Result<u32, BranchError> {
+        branch_handlers::add_branch(
+            &mut self.branches.branches,
+            &mut self.ids.branches,
+            name,
+            address,
+        )
+    }
+
+    fn branch(&self, branch_id: u32) -> Result<(), BranchError> {
+        if self.branches.branches.iter().any(|b| b.id == branch_id) {
+            Ok(())
+        } else {
+            Err(BranchError::BranchNotFound)
+        }
+    }
+
+    pub fn branch_name(&self, branch_id: u32) -> String {
+        branch_handlers::branch_name(&self.branches.branches, branch_id)
+    }
+
+    /// Makes `branch_id` the copy's home. A copy with no location yet is
+    /// taken to be shelved there.
+    pub fn set_home_branch(&mut self, book_id: u32, branch_id: u32) -> Result<(), BranchError> {
+        self.branch(branch_id)?;
+        let book = self
+            .books
+            .iter_mut()
+            .find(|b| b.id == book_id)
+            .ok_or(BranchError::BookNotFound)?;
+        book.home_branch = Some(branch_id);
+        if book.current_branch.is_none() {
+            book.current_branch = Some(branch_id);
+        }
+        Ok(())
+    }
+
+    /// Sends a copy on the shelf to another branch. Returns the transfer id.
+    pub fn send_transfer(
+        &mut self,
+        book_id: u32,
+        to_branch: u32,
+        date: NaiveDate,
+        reason: String,
+    ) -> Result<u32, BranchError> {
+        branch_handlers::send_transfer(
+            &mut self.branches,
+            &mut self.books,
+            &mut self.ids.transfers,
+            book_id,
+            to_branch,
+            date,
+            reason,
+        )
+    }
+
+    /// Books a transfer in at its destination, where the copy goes to the
+    /// hold shelf if someone is waiting for it.
+    pub fn receive_transfer(
+        &mut self,
+        transfer_id: u32,
+        date: NaiveDate,
+    ) -> Result<Transfer, BranchError> {
+        self.transaction(|library| {
+            let transfer = branch_handlers::receive_transfer(
+                &mut library.branches.transfers,
+                &mut library.books,
+                transfer_id,
+                date,
+            )?;
+            library.route_to_next_hold(transfer.book_id, date)?;
+            Ok(transfer)
+        })
+    }
+
+    /// Copies among `books` at each branch and how many are on the shelf.
+    pub fn branch_availability(&self, books: &[Book]) -> Vec<BranchAvailability> {
+        branch_handlers::availability(books, &self.branches.branches)
+    }
+
+    pub fn print_branch_availability(availability: &[BranchAvailability]) {
+        branch_handlers::print_availability(availability);
+    }
+
+    pub fn list_branches(&self) {
+        if self.branches.branches.is_empty() {
+            println!("No branches registered.");
+        } else {
+            branch_handlers::print_branches(&self.branches.branches);
+        }
+    }
+
+    pub fn list_in_transit(&self) {
+        let transfers = branch_handlers::get_in_transit(&self.branches.transfers);
+        if transfers.is_empty() {
+            println!("No items in transit.");
+        } else {
+            branch_handlers::print_transfers(&transfers, &self.branches.branches, &self.books);
+        }
+    }
+
+    fn charge_overdue_fine(&mut self, loan: &Loan) -> Result<i64, LoanError> {
+        let Some(return_date) = loan.return_date else {
+            return Ok(0);
+        };
+        let policy = self
+            .policy_for_item(loan.user_id, loan.book_id)
+            .unwrap_or_default();
+        // Days the library was closed do not count towards the fine.
+        let days_overdue =
+            calendar_handlers::open_days_between(&self.calendar, loan.due_date, return_date);
+        // Recalled items that come back late are fined at a higher rate.
+        let daily_fine_cents = if loan.is_recalled() {
+            policy
+                .daily_fine_cents
+                .saturating_mul(self.policies.recalls.fine_multiplier)
+        } else {
+            policy.daily_fine_cents
+        };
+        let fine =
+            fine_handlers::overdue_fine(days_overdue, daily_fine_cents, &self.policies.fines);
+
+        if fine > 0 {
+            fine_handlers::post_entry(
+                &mut self.ledger,
+                &mut self.ids.ledger,
+                LedgerEntry::charge(
+                    loan.user_id,
+                    return_date,
+                    ChargeReason::Overdue,
+                    fine,
+                    Some(loan.id),
+                ),
+            )?;
+        }
+        Ok(fine)
+    }
+
+    pub fn format_amount(cents: i64) -> String {
+        fine_handlers::format_amount(cents)
+    }
+
+    pub fn account_balance(&self, user_id: u32) -> i64 {
+        fine_handlers::balance(&self.ledger, user_id)
+    }
+
+    /// Bills the replacement cost of the loan's item: the book's price, or
+    /// the flat fee when no price is on record.
+    fn charge_replacement(
+        &mut self,
+        loan: &Loan,
+        reason: ChargeReason,
+        date: NaiveDate,
+    ) -> Result<i64, FineError> {
+        let price_cents = self
+            .books
+            .iter()
+            .find(|b| b.id == loan.book_id)
+            .and_then(|b| b.price_cents);
+        let amount = fine_handlers::replacement_cost(price_cents, reason, &self.policies.fines);
+
+        if amount > 0 {
+            fine_handlers::post_entry(
+                &mut self.ledger,
+                &mut self.ids.ledger,
+                LedgerEntry::charge(loan.user_id, date, reason, amount, Some(loan.id)),
+            )?;
+        }
+        Ok(amount)
+    }
+
+    pub fn pay_fine(
+        &mut self,
+        user_id: u32,
+        amount_cents: i64,
+        date: NaiveDate,
+    ) -> Result<u32, FineError> {
+        if !self.users.iter().any(|u| u.id == user_id) {
+            return Err(FineError::UserNotFound);
+        }
+        fine_handlers::post_entry(
+            &mut self.ledger,
+            &mut self.ids.ledger,
+            LedgerEntry::payment(user_id, date, amount_cents),
+        )
+    }
+
+    /// Forgives part of a patron's balance; with `loan_id`, the charges on
+    /// that loan.
+    pub fn waive_fine(
+        &mut self,
+        user_id: u32,
+        amount_cents: i64,
+        date: NaiveDate,
+        loan_id: Option<u32>,
+        note: String,
+    ) -> Result<u32, FineError> {
+        if !self.users.iter().any(|u| u.id == user_id) {
+            return Err(FineError::UserNotFound);
+        }
+        if let Some(loan_id) = loan_id {
+            if !self
+                .loans
+                .iter()
+                .any(|l| l.id == loan_id && l.user_id == user_id)
+            {
+                return Err(FineError::LoanNotFound);
+            }
+        }
+        fine_handlers::post_entry(
+            &mut self.ledger,
+            &mut self.ids.ledger,
+            LedgerEntry::waiver(user_id, date, amount_cents, loan_id, note),
+        )
+    }
+
+    pub fn print_statement(&self, user_id: u32) {
+        let lines = fine_handlers::statement(&self.ledger, user_id);
+        if lines.is_empty() {
+            println!("No account activity for user {}.", user_id);
+        } else {
+            fine_handlers::print_statement(&lines);
+        }
+        println!(
+            "Balance: {}",
+            Self::format_amount(self.account_balance(user_id))
+        );
+    }
+
+    pub fn create_household(
+        &mut self,
+        name: String,
+        guardian_id: u32,
+    ) -> Result<u32, HouseholdError> {
+        household_handlers::create_household(
+            &mut self.households,
+            &self.users,
+            &mut self.ids.households,
+            name,
+            guardian_id,
+        )
+    }
+
+    pub fn add_household_member(
+        &mut self,
+        household_id: u32,
+        user_id: u32,
+    ) -> Result<(), HouseholdError> {
+        household_handlers::add_member(&mut self.households, &self.users, household_id, user_id)
+    }
+
+    pub fn remove_household_member(
+        &mut self,
+        household_id: u32,
+        user_id: u32,
+    ) -> Result<(), HouseholdError> {
+        household_handlers::remove_member(&mut self.households, household_id, user_id)
+    }
+
+    /// Caps active loans across the household; `None` leaves only each
+    /// member's own limit.
+    pub fn set_household_loan_limit(
+        &mut self,
+        household_id: u32,
+        limit: Option<u32>,
+    ) -> Result<(), HouseholdError> {
+        household_handlers::get_household_mut(&mut self.households, household_id)?.loan_limit =
+            limit;
+        Ok(())
+    }
+
+    pub fn set_household_shared_fines(
+        &mut self,
+        household_id: u32,
+        shared: bool,
+    ) -> Result<(), HouseholdError> {
+        household_handlers::get_household_mut(&mut self.households, household_id)?.shared_fines =
+            shared;
+        Ok(())
+    }
+
+    /// Everything the guardian's household has out.
+    pub fn household_loans(
+        &self,
+        guardian_id: u32,
+    ) -> Result<Vec<LoanHistoryEntry>, HouseholdError> {
+        let household = household_handlers::guarded_by(&self.households, guardian_id)?;
+        Ok(self.history_entries(household_handlers::active_loans(household, &self.loans)))
+    }
+
+    /// Renews a household member's loan on the guardian's behalf.
+    pub fn renew_household_loan(
+        &mut self,
+        guardian_id: u32,
+        loan_id: u32,
+        date: NaiveDate,
+    ) -> Result<Loan, HouseholdError> {
+        let household = household_handlers::guarded_by(&self.households, guardian_id)?;
+        let loan = self
+            .loans
+            .iter()
+            .find(|l| l.id == loan_id)
+            .ok_or(LoanError::LoanNotFound)?;
+        if !household.has_member(loan.user_id) {
+            return Err(HouseholdError::NotAMember);
+        }
+        Ok(self.renew_loan(loan_id, date)?)
+    }
+
+    /// The combined balance of every member of the household.
+    pub fn household_balance(&self, household_id: u32) -> Result<i64, HouseholdError> {
+        let household = self
+            .households
+            .iter()
+            .find(|h| h.id == household_id)
+            .ok_or(HouseholdError::HouseholdNotFound)?;
+        Ok(household
+            .member_ids
+            .iter()
+            .map(|&id| self.account_balance(id))
+            .sum())
+    }
+
+    pub fn list_households(&self) {
+        if self.households.is_empty() {
+            println!("No households registered.");
+        } else {
+            household_handlers::print_households(&self.households, &self.users);
+        }
+    }
+
+    /// The balance checkouts are blocked on: the household's combined
+    /// balance when it shares fines, the patron's own otherwise.
+    fn blocking_balance(&self, user_id: u32) -> i64 {
+        match household_handlers::household_of(&self.households, user_id) {
+            Some(household) if household.shared_fines => {
+                self.household_balance(household.id).unwrap_or_default()
+            }
+            _ => self.account_balance(user_id),
+        }
+    }
+
+    /// Refuses a checkout that would put the patron's household over its
+    /// limit, counting `pending` items not yet checked out.
+    fn check_household_limit(&self, user_id: u32, pending: usize) -> Result<(), LoanError> {
+        let Some(household) = household_handlers::household_of(&self.households, user_id) else {
+            return Ok(());
+        };
+        let Some(limit) = household.loan_limit else {
+            return Ok(());
+        };
+
+        let current = household_handlers::active_loans(household, &self.loans).len() + pending;
+        if current >= limit as usize {
+            return Err(LoanError::HouseholdLimitReached { limit, current });
+        }
+        Ok(())
+    }
+
+    fn pending_holds_for(&self, book_id: u32) -> usize {
+        hold_handlers::get_queue(&self.holds, book_id).len()
+    }
+
+    pub fn renew_loan(&mut self, loan_id: u32, date: NaiveDate) -> Result<Loan, LoanError> {
+        let loan = self
+            .loans
+            .iter()
+            .find(|l| l.id == loan_id)
+            .ok_or(LoanError::LoanNotFound)?;
+        let policy = self
+            .policy_for_item(loan.user_id, loan.book_id)
+            .unwrap_or_default();
+        let pending_holds = self.pending_holds_for(loan.book_id);
+
+        loan_handlers::renew_loan(
+            &mut self.loans,
+            loan_id,
+            date,
+            &policy,
+            pending_holds,
+            &self.calendar,
+        )
+    }
+
+    pub fn overdue_loans(&self, as_of: NaiveDate) -> Vec<OverdueLoan> {
+        loan_handlers::get_overdue_loans(&self.loans, as_of)
+    }
+
+    pub fn print_overdue_report(&self, as_of: NaiveDate) {
+        let overdue = self.overdue_loans(as_of);
+        if overdue.is_empty() {
+            println!("No overdue loans as of {}.", as_of);
+        } else {
+            loan_handlers::print_overdue_report(&overdue, &self.users, &self.books);
+        }
+    }
+
+    /// A patron's loans matching `filter`, oldest first, with book titles.
+    pub fn loan_history(&self, user_id: u32, filter: &HistoryFilter) -> Vec<LoanHistoryEntry> {
+        let loans = loan_handlers::get_loans_by_user(&self.loans, user_id);
+        self.history_entries(loan_handlers::filter_history(loans, filter))
+    }
+
+    /// Who borrowed a copy and when, oldest first.
+    pub fn book_circulation(&self, book_id: u32, filter: &HistoryFilter) -> Vec<LoanHistoryEntry> {
+        let loans = loan_handlers::get_loans_by_book(&self.loans, book_id);
+        self.history_entries(loan_handlers::filter_history(loans, filter))
+    }
+
+    fn history_entries(&self, loans: Vec<Loan>) -> Vec<LoanHistoryEntry> {
+        loans
+            .into_iter()
+            .map(|loan| LoanHistoryEntry {
+                title: self
+                    .books
+                    .iter()
+                    .find(|b| b.id == loan.book_id)
+                    .map(|b| b.title.clone())
+                    .unwrap_or_else(|| "(deleted)".to_string()),
+                borrower: self
+                    .users
+                    .iter()
+                    .find(|u| u.id == loan.user_id)
+                    .map(|u| u.name.clone())
+                    .unwrap_or_else(|| "(deleted)".to_string()),
+                loan,
+            })
+            .collect()
+    }
+
+    pub fn print_history(entries: &[LoanHistoryEntry]) {
+        loan_handlers::print_history(entries);
+    }
+
+    pub fn list_active_loans(&self) {
+        let active_loans = loan_handlers::get_active_loans(&self.loans);
+        loan_handlers::print_loans(&active_loans);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).expect("Data inválida")
+    }
+
+    fn library_with_two_books() -> Library {
+        let mut library = Library::new();
+        library
+            .add_user(User::new(0, "Alice".to_string()))
+            .expect("Falha ao adicionar usuário");
+        for title in ["Livro Um", "Livro Dois"] {
+            library
+                .add_book(Book::new(0, title.to_string(), "Autor".to_string(), 100))
+                .expect("Falha ao adicionar livro");
+        }
+        library
+    }
+
+    #[test]
+    fn test_add_book_refuses_id_without_successor() {
+        let mut library = library_with_two_books();
+        let result = library.add_book(Book::new(
+            u32::MAX,
+            "Livro Três".to_string(),
+            "Autor".to_string(),
+            100,
+        ));
+        assert!(matches!(
+            result,
+            Err(BookError::IdError(ids::models::IdError::OutOfRange(
+                u32::MAX
+            )))
+        ));
+        assert_eq!(library.books.len(), 2);
+        assert_eq!(library.ids.books.next_id, 3);
+    }
+
+    #[test]
+    fn test_transaction_rolls_back_every_step() {
+        let mut library = library_with_two_books();
+        library
+            .declare_book_lost(2, "Não localizado", date(2024, 3, 1))
+            .expect("Falha ao declarar perda");
+
+        let result = library.transaction(|library| {
+            library.loan_book(1, 1, date(2024, 3, 1))?;
+            library.loan_book(1, 2, date(2024, 3, 1))
+        });
+
+        assert!(matches!(result, Err(LoanError::BookNotAvailable)));
+        assert!(library.loans.is_empty());
+        assert!(library.books[0].is_available());
+        assert!(library.books[0].status_history.is_empty());
+        assert_eq!(library.ids.loans.next_id, 1);
+    }
+
+    #[test]
+    fn test_transaction_drops_fines_posted_before_failure() {
+        let mut library = library_with_two_books();
+        library
+            .loan_book(1, 1, date(2024, 3, 1))
+            .expect("Falha ao emprestar livro");
+
+        let result = library.transaction(|library| {
+            library.check_in(1, date(2024, 4, 30), None)?;
+            library.loan_book(1, 2, date(2024, 4, 30))
+        });
+
+        assert!(matches!(result, Err(LoanError::OutstandingFines { .. })));
+        assert!(library.ledger.is_empty());
+        assert_eq!(library.account_balance(1), 0);
+        assert!(library.loans[0].is_active());
+    }
+
+    #[test]
+    fn test_checkout_session_enforces_limit_and_commits_together() {
+        let mut library = library_with_two_books();
+        library
+            .add_book(Book::new(
+                0,
+                "Livro Três".to_string(),
+                "Autor".to_string(),
+                100,
+            ))
+            .expect("Falha ao adicionar livro");
+        library
+            .set_user_loan_limit(1, Some(2))
+            .expect("Falha ao definir limite");
+
+        let mut session = library
+            .start_checkout(1, date(2024, 3, 1))
+            .expect("Falha ao iniciar sessão");
+        assert!(library.add_to_checkout(&mut session, 1).is_ok());
+        assert!(matches!(
+            library.add_to_checkout(&mut session, 1),
+            Err(LoanError::BookNotAvailable)
+        ));
+        assert!(library.add_to_checkout(&mut session, 2).is_ok());
+        assert_eq!(session.remaining(), 0);
+        assert!(matches!(
+            library.add_to_checkout(&mut session, 3),
+            Err(LoanError::LoanLimitReached { limit: 2, .. })
+        ));
+        assert!(library.loans.is_empty());
+
+        let receipt = library
+            .commit_checkout(session)
+            .expect("Falha ao concluir empréstimos");
+        assert_eq!(receipt.items.len(), 2);
+        assert_eq!(receipt.items[1].title, "Livro Dois");
+        assert_eq!(library.loans.len(), 2);
+    }
+
+    #[test]
+    fn test_staff_hold_recalls_loan_and_raises_fine_rate() {
+        let mut library = library_with_two_books();
+        let mut staff = User::new(0, "Bruno".to_string());
+        staff.category = PatronCategory::Staff;
+        library.add_user(staff).expect("Falha ao adicionar usuário");
+        library
+            .set_user_category(1, PatronCategory::Staff)
+            .expect("Falha ao alterar categoria");
+
+        library
+            .loan_book(1, 1, date(2024, 3, 1))
+            .expect("Falha ao emprestar livro");
+        let (_, notice) = library
+            .place_hold(2, 1, date(2024, 3, 20))
+            .expect("Falha ao reservar");
+        let notice = notice.expect("Empréstimo deveria ser recolhido");
+        assert_eq!(notice.borrower, "Alice");
+        assert_eq!(notice.loan.due_date, date(2024, 3, 27));
+        assert!(matches!(
+            library.renew_loan(notice.loan.id, date(2024, 3, 21)),
+            Err(LoanError::LoanRecalled)
+        ));
+
+        // Four days late, two of them within the grace period, at twice the
+        // staff rate.
+        let receipt = library
+            .check_in(1, date(2024, 3, 31), None)
+            .expect("Falha ao devolver livro");
+        assert_eq!(receipt.fine_cents, 100);
+    }
+
+    #[test]
+    fn test_copies_of_one_title_at_two_branches() {
+        let mut library = library_with_two_books();
+        let centro = library
+            .add_branch("Centro".to_string(), String::new())
+            .expect("Falha ao adicionar filial");
+        let norte = library
+            .add_branch("Norte".to_string(), String::new())
+            .expect("Falha ao adicionar filial");
+        for branch_id in [centro, norte] {
+            library
+                .add_book(Book {
+                    home_branch: Some(branch_id),
+                    ..Book::new(0, "Dom Casmurro".to_string(), "Machado".to_string(), 200)
+                })
+                .expect("Falha ao adicionar exemplar");
+        }
+        assert!(matches!(
+            library.add_book(Book {
+                home_branch: Some(norte),
+                ..Book::new(0, "Dom Casmurro".to_string(), "Machado".to_string(), 200)
+            }),
+            Err(BookError::BookAlreadyExists)
+        ));
+
+        let copies = library.search_books("Dom Casmurro");
+        assert_eq!(copies.len(), 2);
+        library
+            .loan_book_at(centro, 1, copies[0].id, date(2024, 3, 1))
+            .expect("Falha ao emprestar livro");
+
+        let availability = library.branch_availability(&library.search_books("Dom Casmurro"));
+        let at = |branch_id| {
+            let branch = availability
+                .iter()
+                .find(|a| a.branch_id == branch_id)
+                .expect("Filial deveria aparecer");
+            (branch.available, branch.total)
+        };
+        assert_eq!(at(centro), (0, 1));
+        assert_eq!(at(norte), (1, 1));
+    }
+
+    #[test]
+    fn test_found_item_credits_only_unwaived_charge() {
+        let mut library = library_with_two_books();
+        let loan = library
+            .loan_book(1, 1, date(2024, 3, 1))
+            .expect("Falha ao emprestar livro");
+        let charged = library
+            .declare_loan_lost(1, date(2024, 3, 20))
+            .expect("Falha ao declarar perda");
+        library
+            .waive_fine(
+                1,
+                charged / 2,
+                date(2024, 3, 21),
+                Some(loan.id),
+                "Perda abonada".to_string(),
+            )
+            .expect("Falha ao abonar");
+
+        let credit = library
+            .return_found_item(1, date(2024, 3, 25))
+            .expect("Falha ao devolver item encontrado");
+        assert_eq!(credit, charged - charged / 2);
+        assert_eq!(fine_handlers::balance(&library.ledger, 1), 0);
+    }
+
+    #[test]
+    fn test_expire_holds_rolls_back_when_copy_cannot_move() {
+        let mut library = library_with_two_books();
+        for name in ["Bruno", "Carla"] {
+            library
+                .add_user(User::new(0, name.to_string()))
+                .expect("Falha ao adicionar usuário");
+        }
+        library
+            .loan_book(1, 1, date(2024, 3, 1))
+            .expect("Falha ao emprestar livro");
+        let (first, _) = library
+            .place_hold(2, 1, date(2024, 3, 2))
+            .expect("Falha ao reservar");
+        let (second, _) = library
+            .place_hold(3, 1, date(2024, 3, 3))
+            .expect("Falha ao reservar");
+        library
+            .check_in(1, date(2024, 3, 5), None)
+            .expect("Falha ao devolver livro");
+
+        // A copy gone missing from the hold shelf cannot be held for the
+        // next patron, so expiring the first hold must not go through.
+        library.books[0].status = ItemStatus::Missing;
+        assert!(matches!(
+            library.expire_holds(date(2024, 3, 20)),
+            Err(HoldError::BookError(_))
+        ));
+        let status = |id| library.holds.iter().find(|h| h.id == id).unwrap().status;
+        assert_eq!(status(first), HoldStatus::ReadyForPickup);
+        assert_eq!(status(second), HoldStatus::Waiting);
+    }
+
+    #[test]
+    fn test_open_interlibrary_requests_block_deletion() {
+        let mut library = library_with_two_books();
+        let partner_id = library
+            .add_partner_library("Biblioteca Parceira".to_string(), String::new())
+            .expect("Falha ao adicionar parceira");
+        let lent = library
+            .lend_to_partner(partner_id, 1, date(2024, 3, 1))
+            .expect("Falha ao registrar pedido");
+        library
+            .ship_ill_item(lent, date(2024, 3, 2), None)
+            .expect("Falha ao enviar item");
+        library
+            .request_from_partner(
+                partner_id,
+                1,
+                "Livro Raro".to_string(),
+                "Autor".to_string(),
+                date(2024, 3, 1),
+            )
+            .expect("Falha ao pedir item");
+
+        assert!(matches!(
+            library.remove_book(1, DeletePolicy::Anonymize),
+            Err(DeleteError::HasOpenIllRequests(1))
+        ));
+        assert!(matches!(
+            library.remove_user(1, DeletePolicy::Anonymize),
+            Err(DeleteError::HasOpenIllRequests(1))
+        ));
+
+        library
+            .receive_ill_item(lent, date(2024, 3, 4))
+            .expect("Falha ao receber item");
+        library
+            .return_ill_item(lent, date(2024, 3, 20))
+            .expect("Falha ao devolver item");
+        assert!(library.remove_book(1, DeletePolicy::Anonymize).is_ok());
+    }
+
+    #[test]
+    fn test_digital_waitlist_moves_on_during_session_and_skips_blocked_patrons() {
+        let mut library = library_with_two_books();
+        for name in ["Bruno", "Carla"] {
+            library
+                .add_user(User::new(0, name.to_string()))
+                .expect("Falha ao adicionar usuário");
+        }
+        let title_id = library
+            .add_digital_title("E-book".to_string(), "Autor".to_string())
+            .expect("Falha ao cadastrar título");
+        library
+            .add_license(title_id, License::one_copy_one_user(0), date(2024, 3, 1))
+            .expect("Falha ao adicionar licença");
+
+        let loan = library
+            .borrow_digital(1, title_id, date(2024, 3, 1))
+            .expect("Falha ao emprestar e-book");
+        for (user_id, day) in [(2, 2), (3, 3)] {
+            library
+                .join_digital_waitlist(user_id, title_id, date(2024, 3, day))
+                .expect("Falha ao entrar na fila");
+        }
+        library
+            .set_user_status(2, AccountStatus::Suspended)
+            .expect("Falha ao suspender usuário");
+
+        // Alice's loan ran out without anyone expiring it; the next request
+        // frees the license and hands it to Carla, passing over Bruno.
+        assert!(matches!(
+            library.borrow_digital(1, title_id, loan.due_date),
+            Err(DigitalError::NoLicenseAvailable)
+        ));
+        let carla_loans =
+            digital_handlers::get_user_loans(&library.digital.loans, 3, loan.due_date);
+        assert_eq!(carla_loans.len(), 1);
+
+        assert!(matches!(
+            library.remove_user(3, DeletePolicy::Anonymize),
+            Err(DeleteError::HasActiveLoans(1))
+        ));
+        assert!(matches!(
+            library.remove_user(2, DeletePolicy::Anonymize),
+            Err(DeleteError::HasPendingHolds(1))
+        ));
+    }
+
+    #[test]
+    fn test_checked_in_issue_can_be_loaned() {
+        let mut library = library_with_two_books();
+        let serial_id = library
+            .add_serial(
+                "Revista".to_string(),
+                "Editora".to_string(),
+                Frequency::Weekly,
+                date(2024, 3, 1),
+            )
+            .expect("Falha ao adicionar periódico");
+        let issues = library
+            .predict_serial_issues(serial_id, date(2024, 3, 14))
+            .expect("Falha ao prever fascículos");
+        assert_eq!(issues.len(), 2);
+
+        let book_id = library
+            .check_in_issue(issues[0].id, date(2024, 3, 2))
+            .expect("Falha ao registrar fascículo");
+        let book = library.books.iter().find(|b| b.id == book_id).unwrap();
+        assert_eq!(book.title, "Revista (2024-03-01)");
+        assert_eq!(book.details, MaterialDetails::Periodical { pages: 0 });
+        assert!(matches!(
+            library.check_in_issue(issues[0].id, date(2024, 3, 2)),
+            Err(SerialError::IssueAlreadyReceived)
+        ));
+        assert_eq!(library.books.len(), 3);
+
+        assert!(library.loan_book(1, book_id, date(2024, 3, 3)).is_ok());
+    }
+
+    #[test]
+    fn test_removing_issue_copy_detaches_the_issue() {
+        let mut library = library_with_two_books();
+        let serial_id = library
+            .add_serial(
+                "Revista".to_string(),
+                "Editora".to_string(),
+                Frequency::Weekly,
+                date(2024, 3, 1),
+            )
+            .expect("Falha ao adicionar periódico");
+        let issues = library
+            .predict_serial_issues(serial_id, date(2024, 3, 7))
+            .expect("Falha ao prever fascículos");
+        let book_id = library
+            .check_in_issue(issues[0].id, date(2024, 3, 2))
+            .expect("Falha ao registrar fascículo");
+
+        assert!(matches!(
+            library.remove_book(book_id, DeletePolicy::Block),
+            Err(DeleteError::HasHistory { records: 1 })
+        ));
+        library
+            .remove_book(book_id, DeletePolicy::Anonymize)
+            .expect("Falha ao remover exemplar");
+        assert_eq!(library.serials.issues[0].book_id, None);
+        assert!(library.serials.issues[0].received_on.is_some());
+    }
+
+    #[test]
+    fn test_return_at_other_branch_sends_item_home() {
+        let mut library = library_with_two_books();
+        let central = library
+            .add_branch("Central".to_string(), String::new())
+            .expect("Falha ao adicionar filial");
+        let norte = library
+            .add_branch("Norte".to_string(), String::new())
+            .expect("Falha ao adicionar filial");
+        library
+            .set_home_branch(1, central)
+            .expect("Falha ao definir filial");
+
+        assert!(matches!(
+            library.loan_book_at(norte, 1, 1, date(2024, 3, 1)),
+            Err(LoanError::ItemAtAnotherBranch(branch)) if branch == central
+        ));
+        library
+            .loan_book_at(central, 1, 1, date(2024, 3, 1))
+            .expect("Falha ao emprestar");
+
+        let receipt = library
+            .check_in_at(norte, 1, date(2024, 3, 5), None)
+            .expect("Falha ao devolver");
+        assert_eq!(receipt.loan.branch_id, Some(central));
+        assert_eq!(receipt.loan.return_branch_id, Some(norte));
+        assert_eq!(receipt.transfer_to.as_deref(), Some("Central"));
+        assert_eq!(library.books[0].status, ItemStatus::InTransit);
+
+        let transfer = library.branches.transfers[0].clone();
+        assert_eq!((transfer.from_branch, transfer.to_branch), (norte, central));
+        assert!(matches!(
+            library.remove_book(1, DeletePolicy::Anonymize),
+            Err(DeleteError::HasOpenTransfers(1))
+        ));
+        library
+            .receive_transfer(transfer.id, date(2024, 3, 6))
+            .expect("Falha ao receber transferência");
+        assert!(library.books[0].is_available());
+        assert_eq!(library.books[0].current_branch, Some(central));
+        assert_eq!(
+            library.branch_availability(&library.books),
+            vec![BranchAvailability {
+                branch_id: central,
+                name: "Central".to_string(),
+                available: 1,
+                total: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_household_guardian_limit_and_shared_fines() {
+        let mut library = library_with_two_books();
+        library
+            .add_user(User::new(0, "Bruno".to_string()))
+            .expect("Falha ao adicionar usuário");
+        library
+            .add_user(User::new(0, "Carla".to_string()))
+            .expect("Falha ao adicionar usuário");
+        let household = library
+            .create_household("Família".to_string(), 1)
+            .expect("Falha ao criar família");
+        library
+            .add_household_member(household, 2)
+            .expect("Falha ao adicionar membro");
+        library
+            .set_household_loan_limit(household, Some(1))
+            .expect("Falha ao definir limite");
+
+        let loan = library
+            .loan_book(2, 1, date(2024, 3, 1))
+            .expect("Falha ao emprestar");
+        assert!(matches!(
+            library.loan_book(1, 2, date(2024, 3, 1)),
+            Err(LoanError::HouseholdLimitReached {
+                limit: 1,
+                current: 1
+            })
+        ));
+        assert!(library.loan_book(3, 2, date(2024, 3, 1)).is_ok());
+
+        let entries = library
+            .household_loans(1)
+            .expect("Falha ao listar empréstimos");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].borrower, "Bruno");
+        assert!(matches!(
+            library.household_loans(2),
+            Err(HouseholdError::NotGuardian)
+        ));
+        assert!(library
+            .renew_household_loan(1, loan.id, date(2024, 3, 10))
+            .is_ok());
+        assert!(matches!(
+            library.renew_household_loan(1, 2, date(2024, 3, 10)),
+            Err(HouseholdError::NotAMember)
+        ));
+
+        library
+            .set_household_loan_limit(household, None)
+            .expect("Falha ao remover limite");
+        library
+            .check_in(2, date(2024, 3, 20), None)
+            .expect("Falha ao devolver");
+        let receipt = library
+            .check_in(1, date(2024, 6, 1), None)
+            .expect("Falha ao devolver");
+        assert!(receipt.fine_cents > library.policies.fines.block_threshold_cents);
+        assert!(library.loan_book(1, 2, date(2024, 6, 1)).is_ok());
+        library
+            .check_in(2, date(2024, 6, 2), None)
+            .expect("Falha ao devolver");
+
+        library
+            .set_household_shared_fines(household, true)
+            .expect("Falha ao combinar multas");
+        assert_eq!(
+            library.household_balance(household).unwrap(),
+            receipt.fine_cents
+        );
+        assert!(matches!(
+            library.loan_book(1, 2, date(2024, 6, 3)),
+            Err(LoanError::OutstandingFines { .. })
+        ));
+    }
+
+    #[test]
+    fn test_manual_status_change_refused_while_on_loan() {
+        let mut library = library_with_two_books();
+        let loan = library
+            .loan_book(1, 1, date(2024, 3, 1))
+            .expect("Falha ao emprestar");
+
+        assert!(matches!(
+            library.restore_book_to_shelf(1, "Engano", date(2024, 3, 1)),
+            Err(BookError::OnLoan(id)) if id == loan.id
+        ));
+        assert!(matches!(
+            library.declare_book_lost(1, "Não localizado", date(2024, 3, 1)),
+            Err(BookError::OnLoan(_))
+        ));
+        assert!(matches!(
+            library.send_book_to_repair(1, "Capa solta", date(2024, 3, 1)),
+            Err(BookError::OnLoan(_))
+        ));
+        assert_eq!(library.books[0].status, ItemStatus::OnLoan);
+
+        library
+            .check_in(1, date(2024, 3, 5), None)
+            .expect("Falha ao devolver");
+        assert!(library
+            .send_book_to_repair(1, "Capa solta", date(2024, 3, 6))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_transaction_keeps_changes_on_success() {
+        let mut library = library_with_two_books();
+
+        let result = library.transaction(|library| {
+            library.loan_book(1, 1, date(2024, 3, 1))?;
+            library.loan_book(1, 2, date(2024, 3, 1))
+        });
+
+        assert!(result.is_ok());
+        assert_eq!(library.loans.len(), 2);
+        assert!(library.books.iter().all(|b| b.status == ItemStatus::OnLoan));
     }
 }
@@ -0,0 +1,63 @@
+use super::service;
+use crate::library::books::models::MaterialFormat;
+use crate::library::policies::models::{LibraryPolicies, LoanPolicy, PolicyError};
+use crate::library::users::models::PatronCategory;
+
+pub(crate) fn save_policies(policies: &LibraryPolicies) -> Result<(), PolicyError> {
+    service::save_to_json("policies.json", policies)
+}
+
+pub(crate) fn read_policies() -> Result<LibraryPolicies, PolicyError> {
+    service::read_from_json("policies.json")
+}
+
+pub(crate) fn resolve(policies: &LibraryPolicies, category: PatronCategory) -> LoanPolicy {
+    service::resolve(policies, category)
+}
+
+pub(crate) fn resolve_for_format(
+    policies: &LibraryPolicies,
+    category: PatronCategory,
+    format: MaterialFormat,
+) -> LoanPolicy {
+    service::resolve_for_format(policies, category, format)
+}
+
+pub(crate) fn print_policies(policies: &LibraryPolicies) {
+    for (category, policy) in &policies.categories {
+        println!("Category: {}", category);
+        println!("Loan Period: {} days", policy.loan_period_days);
+        println!("Max Concurrent Loans: {}", policy.max_concurrent_loans);
+        println!("Max Renewals: {}", policy.max_renewals);
+        println!(
+            "Renewable Up To: {} day(s) overdue",
+            policy.max_overdue_days_for_renewal
+        );
+        println!(
+            "Daily Fine: {}.{:02}",
+            policy.daily_fine_cents / 100,
+            policy.daily_fine_cents % 100
+        );
+        let formats: Vec<String> = policy
+            .allowed_formats
+            .iter()
+            .map(|f| f.to_string())
+            .collect();
+        println!("Allowed Formats: {}", formats.join(", "));
+        println!();
+    }
+
+    for (format, rules) in &policies.formats {
+        println!("Format: {}", format);
+        if let Some(days) = rules.loan_period_days {
+            println!("Loan Period: {} days", days);
+        }
+        if let Some(renewals) = rules.max_renewals {
+            println!("Max Renewals: {}", renewals);
+        }
+        if let Some(fine) = rules.daily_fine_cents {
+            println!("Daily Fine: {}.{:02}", fine / 100, fine % 100);
+        }
+        println!();
+    }
+}
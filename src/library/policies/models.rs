@@ -0,0 +1,251 @@
+use crate::library::books::models::MaterialFormat;
+use crate::library::digital::models::DigitalRules;
+use crate::library::fines::models::FineRules;
+use crate::library::holds::models::HoldRules;
+use crate::library::interlibrary::models::IllRules;
+use crate::library::loans::models::RecallRules;
+use crate::library::serials::models::SerialRules;
+use crate::library::users::models::PatronCategory;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoanPolicy {
+    pub loan_period_days: u32,
+    pub max_concurrent_loans: u32,
+    pub max_renewals: u32,
+    /// Loans overdue by more than this many days can no longer be renewed.
+    pub max_overdue_days_for_renewal: u32,
+    pub daily_fine_cents: u32,
+    pub allowed_formats: Vec<MaterialFormat>,
+    /// Holds placed by these patrons recall the copy from its borrower.
+    pub can_recall: bool,
+}
+
+impl LoanPolicy {
+    pub fn allows(&self, format: MaterialFormat) -> bool {
+        self.allowed_formats.contains(&format)
+    }
+}
+
+impl Default for LoanPolicy {
+    fn default() -> Self {
+        Self {
+            loan_period_days: 14,
+            max_concurrent_loans: 5,
+            max_renewals: 2,
+            max_overdue_days_for_renewal: 7,
+            daily_fine_cents: 50,
+            allowed_formats: vec![
+                MaterialFormat::Book,
+                MaterialFormat::Periodical,
+                MaterialFormat::Dvd,
+                MaterialFormat::Audiobook,
+                MaterialFormat::BoardGame,
+            ],
+            can_recall: false,
+        }
+    }
+}
+
+/// Per-material overrides of a patron's loan policy, e.g. a shorter loan
+/// period for laptops.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FormatRules {
+    #[serde(default)]
+    pub loan_period_days: Option<u32>,
+    #[serde(default)]
+    pub max_renewals: Option<u32>,
+    #[serde(default)]
+    pub daily_fine_cents: Option<u32>,
+}
+
+impl FormatRules {
+    pub fn apply(&self, policy: &mut LoanPolicy) {
+        if let Some(days) = self.loan_period_days {
+            policy.loan_period_days = days;
+        }
+        if let Some(renewals) = self.max_renewals {
+            policy.max_renewals = renewals;
+        }
+        if let Some(fine) = self.daily_fine_cents {
+            policy.daily_fine_cents = fine;
+        }
+    }
+}
+
+/// Circulation rules, persisted in `policies.json` so they can be tuned
+/// without a rebuild.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryPolicies {
+    #[serde(
+        default = "default_categories",
+        deserialize_with = "deserialize_categories"
+    )]
+    pub categories: BTreeMap<PatronCategory, LoanPolicy>,
+    /// A file written before format rules existed gets the built-in table.
+    #[serde(default = "default_formats")]
+    pub formats: BTreeMap<MaterialFormat, FormatRules>,
+    #[serde(default)]
+    pub fines: FineRules,
+    #[serde(default)]
+    pub holds: HoldRules,
+    #[serde(default)]
+    pub recalls: RecallRules,
+    #[serde(default)]
+    pub interlibrary: IllRules,
+    #[serde(default)]
+    pub serials: SerialRules,
+    #[serde(default)]
+    pub digital: DigitalRules,
+}
+
+fn default_categories() -> BTreeMap<PatronCategory, LoanPolicy> {
+    let mut categories = BTreeMap::new();
+
+    categories.insert(PatronCategory::Student, LoanPolicy::default());
+    categories.insert(
+        PatronCategory::Staff,
+        LoanPolicy {
+            loan_period_days: 30,
+            max_concurrent_loans: 15,
+            max_renewals: 3,
+            max_overdue_days_for_renewal: 14,
+            daily_fine_cents: 25,
+            allowed_formats: vec![
+                MaterialFormat::Book,
+                MaterialFormat::Periodical,
+                MaterialFormat::Dvd,
+                MaterialFormat::Audiobook,
+                MaterialFormat::BoardGame,
+                MaterialFormat::Equipment,
+            ],
+            can_recall: true,
+        },
+    );
+    categories.insert(
+        PatronCategory::Child,
+        LoanPolicy {
+            loan_period_days: 14,
+            max_concurrent_loans: 3,
+            max_renewals: 1,
+            daily_fine_cents: 10,
+            ..LoanPolicy::default()
+        },
+    );
+    categories.insert(
+        PatronCategory::External,
+        LoanPolicy {
+            loan_period_days: 7,
+            max_concurrent_loans: 2,
+            max_renewals: 0,
+            max_overdue_days_for_renewal: 0,
+            daily_fine_cents: 100,
+            allowed_formats: vec![MaterialFormat::Book, MaterialFormat::Periodical],
+            can_recall: false,
+        },
+    );
+
+    categories
+}
+
+/// Reads stored category policies on top of the built-in ones, so a field
+/// added after the file was written gets that category's built-in value.
+fn deserialize_categories<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<BTreeMap<PatronCategory, LoanPolicy>, D::Error> {
+    let stored = BTreeMap::<PatronCategory, Map<String, Value>>::deserialize(deserializer)?;
+    let mut built_in = default_categories();
+
+    stored
+        .into_iter()
+        .map(|(category, fields)| {
+            let base = built_in.remove(&category).unwrap_or_default();
+            let mut policy = match serde_json::to_value(base).map_err(D::Error::custom)? {
+                Value::Object(policy) => policy,
+                _ => unreachable!("a loan policy serializes to an object"),
+            };
+            policy.extend(fields);
+            serde_json::from_value(Value::Object(policy))
+                .map(|policy| (category, policy))
+                .map_err(D::Error::custom)
+        })
+        .collect()
+}
+
+fn default_formats() -> BTreeMap<MaterialFormat, FormatRules> {
+    let mut formats = BTreeMap::new();
+    formats.insert(
+        MaterialFormat::Dvd,
+        FormatRules {
+            loan_period_days: Some(7),
+            max_renewals: Some(1),
+            daily_fine_cents: Some(100),
+        },
+    );
+    formats.insert(
+        MaterialFormat::BoardGame,
+        FormatRules {
+            loan_period_days: Some(21),
+            ..FormatRules::default()
+        },
+    );
+    formats.insert(
+        MaterialFormat::Equipment,
+        FormatRules {
+            loan_period_days: Some(3),
+            max_renewals: Some(0),
+            daily_fine_cents: Some(500),
+        },
+    );
+
+    formats
+}
+
+impl Default for LibraryPolicies {
+    fn default() -> Self {
+        Self {
+            categories: default_categories(),
+            formats: default_formats(),
+            fines: FineRules::default(),
+            holds: HoldRules::default(),
+            recalls: RecallRules::default(),
+            interlibrary: IllRules::default(),
+            serials: SerialRules::default(),
+            digital: DigitalRules::default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum PolicyError {
+    IoError(io::Error),
+    JsonError(serde_json::Error),
+}
+
+impl fmt::Display for PolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyError::IoError(err) => write!(f, "IO Error: {}", err),
+            PolicyError::JsonError(err) => write!(f, "JSON Error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for PolicyError {}
+
+impl From<io::Error> for PolicyError {
+    fn from(err: io::Error) -> Self {
+        PolicyError::IoError(err)
+    }
+}
+
+impl From<serde_json::Error> for PolicyError {
+    fn from(err: serde_json::Error) -> Self {
+        PolicyError::JsonError(err)
+    }
+}
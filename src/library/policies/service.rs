@@ -0,0 +1,185 @@
+use super::models::{LibraryPolicies, LoanPolicy, PolicyError};
+use crate::library::books::models::MaterialFormat;
+use crate::library::users::models::PatronCategory;
+use std::fs::File;
+use std::io::ErrorKind;
+
+pub fn read_from_json(file_path: &str) -> Result<LibraryPolicies, PolicyError> {
+    let file = match File::open(file_path) {
+        Ok(file) => file,
+        Err(err) => {
+            if err.kind() == ErrorKind::NotFound {
+                return Ok(LibraryPolicies::default());
+            } else {
+                return Err(PolicyError::IoError(err));
+            }
+        }
+    };
+
+    let policies: LibraryPolicies =
+        serde_json::from_reader(file).map_err(PolicyError::JsonError)?;
+
+    Ok(policies)
+}
+
+pub fn save_to_json(file_path: &str, policies: &LibraryPolicies) -> Result<(), PolicyError> {
+    let file = File::create(file_path)?;
+    serde_json::to_writer_pretty(file, policies)?;
+    Ok(())
+}
+
+/// The policy for `category`, falling back to the default policy when the
+/// configuration does not mention it.
+pub fn resolve(policies: &LibraryPolicies, category: PatronCategory) -> LoanPolicy {
+    policies
+        .categories
+        .get(&category)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// The policy for `category` with the rules for `format` applied on top.
+pub fn resolve_for_format(
+    policies: &LibraryPolicies,
+    category: PatronCategory,
+    format: MaterialFormat,
+) -> LoanPolicy {
+    let mut policy = resolve(policies, category);
+    if let Some(rules) = policies.formats.get(&format) {
+        rules.apply(&mut policy);
+    }
+    policy
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::fines::models::FineRules;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_resolve_category_policy() {
+        let policies = LibraryPolicies::default();
+        let staff = resolve(&policies, PatronCategory::Staff);
+        let external = resolve(&policies, PatronCategory::External);
+        assert_eq!(staff.loan_period_days, 30);
+        assert!(staff.allows(MaterialFormat::Equipment));
+        assert_eq!(external.max_concurrent_loans, 2);
+        assert!(!external.allows(MaterialFormat::Dvd));
+    }
+
+    #[test]
+    fn test_resolve_for_format_applies_overrides() {
+        let policies = LibraryPolicies::default();
+        let laptop =
+            resolve_for_format(&policies, PatronCategory::Staff, MaterialFormat::Equipment);
+        assert_eq!(laptop.loan_period_days, 3);
+        assert_eq!(laptop.max_renewals, 0);
+        assert_eq!(laptop.max_concurrent_loans, 15);
+
+        let game = resolve_for_format(
+            &policies,
+            PatronCategory::Student,
+            MaterialFormat::BoardGame,
+        );
+        assert_eq!(game.loan_period_days, 21);
+        assert_eq!(game.max_renewals, 2);
+
+        let book = resolve_for_format(&policies, PatronCategory::Student, MaterialFormat::Book);
+        assert_eq!(book, resolve(&policies, PatronCategory::Student));
+    }
+
+    #[test]
+    fn test_resolve_missing_category_uses_default() {
+        let mut policies = LibraryPolicies::default();
+        policies.categories.remove(&PatronCategory::Child);
+        assert_eq!(
+            resolve(&policies, PatronCategory::Child),
+            LoanPolicy::default()
+        );
+    }
+
+    #[test]
+    fn test_save_and_read_policies() {
+        let temp_file = NamedTempFile::new().expect("Não foi possível criar arquivo temporário");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let mut policies = LibraryPolicies::default();
+        policies
+            .categories
+            .get_mut(&PatronCategory::Student)
+            .unwrap()
+            .loan_period_days = 21;
+
+        assert!(save_to_json(file_path, &policies).is_ok());
+
+        let loaded = read_from_json(file_path).expect("Falha ao ler políticas");
+        assert_eq!(
+            resolve(&loaded, PatronCategory::Student).loan_period_days,
+            21
+        );
+    }
+
+    #[test]
+    fn test_read_policies_without_format_rules() {
+        let mut temp_file =
+            NamedTempFile::new().expect("Não foi possível criar arquivo temporário");
+        write!(temp_file, "{{}}").expect("Falha ao escrever no arquivo temporário");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let loaded = read_from_json(file_path).expect("Falha ao ler políticas");
+        assert_eq!(loaded.categories.len(), 4);
+        assert_eq!(
+            resolve_for_format(&loaded, PatronCategory::Student, MaterialFormat::Dvd)
+                .loan_period_days,
+            7
+        );
+    }
+
+    #[test]
+    fn test_read_older_policies_keeps_built_in_values() {
+        let mut temp_file =
+            NamedTempFile::new().expect("Não foi possível criar arquivo temporário");
+        write!(
+            temp_file,
+            r#"{{"categories": {{
+                "Staff": {{"loan_period_days": 28, "max_concurrent_loans": 15, "max_renewals": 3,
+                           "daily_fine_cents": 25, "allowed_formats": ["Book"]}},
+                "Student": {{"loan_period_days": 14, "max_concurrent_loans": 5, "max_renewals": 2,
+                             "daily_fine_cents": 50, "allowed_formats": ["Book"]}}
+            }}, "fines": {{}}}}"#
+        )
+        .expect("Falha ao escrever no arquivo temporário");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let loaded = read_from_json(file_path).expect("Falha ao ler políticas");
+        let staff = &loaded.categories[&PatronCategory::Staff];
+        assert_eq!(staff.loan_period_days, 28);
+        assert_eq!(staff.max_overdue_days_for_renewal, 14);
+        assert!(staff.can_recall);
+        let student = &loaded.categories[&PatronCategory::Student];
+        assert_eq!(student.max_overdue_days_for_renewal, 7);
+        assert!(!student.can_recall);
+        assert_eq!(loaded.fines, FineRules::default());
+    }
+
+    #[test]
+    fn test_read_from_nonexistent_file() {
+        let result = read_from_json("arquivo_que_nao_existe.json");
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().categories.len(), 4);
+    }
+
+    #[test]
+    fn test_read_from_malformed_json() {
+        let mut temp_file =
+            NamedTempFile::new().expect("Não foi possível criar arquivo temporário");
+        writeln!(temp_file, "isto não é um JSON válido")
+            .expect("Falha ao escrever no arquivo temporário");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let result = read_from_json(file_path);
+        assert!(matches!(result, Err(PolicyError::JsonError(_))));
+    }
+}
@@ -0,0 +1,113 @@
+use super::service;
+use crate::library::ids::models::IdAllocator;
+use crate::library::serials::models::{IssueClaim, SerialError, SerialIssue, SerialTitle, Serials};
+use chrono::NaiveDate;
+
+pub(crate) fn save_serials(serials: &Serials) -> Result<(), SerialError> {
+    service::save_to_json("serials.json", serials)
+}
+
+pub(crate) fn read_serials() -> Result<Serials, SerialError> {
+    service::read_from_json("serials.json")
+}
+
+pub(crate) fn add_serial(
+    titles: &mut Vec<SerialTitle>,
+    ids: &mut IdAllocator,
+    serial: SerialTitle,
+) -> Result<u32, SerialError> {
+    service::add_serial(titles, ids, serial)
+}
+
+pub(crate) fn predict_issues(
+    serials: &mut Serials,
+    ids: &mut IdAllocator,
+    serial_id: u32,
+    through: NaiveDate,
+) -> Result<Vec<SerialIssue>, SerialError> {
+    service::predict_issues(serials, ids, serial_id, through)
+}
+
+pub(crate) fn receivable_issue(
+    issues: &[SerialIssue],
+    issue_id: u32,
+) -> Result<&SerialIssue, SerialError> {
+    service::receivable_issue(issues, issue_id)
+}
+
+pub(crate) fn check_in_issue(
+    issues: &mut [SerialIssue],
+    issue_id: u32,
+    date: NaiveDate,
+    book_id: u32,
+) -> Result<SerialIssue, SerialError> {
+    service::check_in_issue(issues, issue_id, date, book_id)
+}
+
+pub(crate) fn claim_issue(
+    issues: &mut [SerialIssue],
+    issue_id: u32,
+    date: NaiveDate,
+) -> Result<SerialIssue, SerialError> {
+    service::claim_issue(issues, issue_id, date)
+}
+
+pub(crate) fn claims_due(
+    issues: &[SerialIssue],
+    as_of: NaiveDate,
+    grace_days: u32,
+) -> Vec<IssueClaim> {
+    service::claims_due(issues, as_of, grace_days)
+}
+
+pub(crate) fn get_issues(issues: &[SerialIssue], serial_id: u32) -> Vec<SerialIssue> {
+    service::get_issues(issues, serial_id)
+}
+
+pub(crate) fn print_serials(titles: &[SerialTitle]) {
+    for serial in titles {
+        println!("Serial ID: {}", serial.id);
+        println!("Title: {}", serial.title);
+        if !serial.publisher.is_empty() {
+            println!("Publisher: {}", serial.publisher);
+        }
+        if let Some(issn) = &serial.issn {
+            println!("ISSN: {}", issn);
+        }
+        println!("Frequency: {}", serial.frequency);
+        if let Some(next) = serial.next_expected() {
+            println!("Next Issue To Predict: {}", next);
+        }
+        println!();
+    }
+}
+
+pub(crate) fn print_issues(issues: &[SerialIssue]) {
+    for issue in issues {
+        print!("Issue {} - {} - {}", issue.id, issue.label(), issue.status);
+        if let Some(date) = issue.received_on {
+            print!(" on {}", date);
+        }
+        if let Some(book_id) = issue.book_id {
+            print!(" (Book ID {})", book_id);
+        }
+        println!();
+    }
+}
+
+pub(crate) fn print_claims(claims: &[IssueClaim], titles: &[SerialTitle]) {
+    for claim in claims {
+        let title = titles
+            .iter()
+            .find(|s| s.id == claim.issue.serial_id)
+            .map(|s| s.title.as_str())
+            .unwrap_or("Unknown serial");
+        println!(
+            "{} - {} (issue {}, expected {})",
+            claim.reason,
+            title,
+            claim.issue.id,
+            claim.issue.label()
+        );
+    }
+}
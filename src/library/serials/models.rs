@@ -0,0 +1,244 @@
+use crate::library::books::models::BookError;
+use crate::library::ids::models::IdError;
+use chrono::{Days, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Frequency {
+    Weekly,
+    Biweekly,
+    Monthly,
+    Bimonthly,
+    Quarterly,
+    Annual,
+}
+
+impl Frequency {
+    /// Publication date of the `n`th issue after `first`. Counting from the
+    /// first issue keeps a monthly serial due on the 31st from drifting to
+    /// the 28th after February.
+    pub fn issue_date(self, first: NaiveDate, n: u32) -> Option<NaiveDate> {
+        match self {
+            Frequency::Weekly => first.checked_add_days(Days::new(7 * u64::from(n))),
+            Frequency::Biweekly => first.checked_add_days(Days::new(14 * u64::from(n))),
+            Frequency::Monthly => first.checked_add_months(Months::new(n)),
+            Frequency::Bimonthly => first.checked_add_months(Months::new(2 * n)),
+            Frequency::Quarterly => first.checked_add_months(Months::new(3 * n)),
+            Frequency::Annual => first.checked_add_months(Months::new(12 * n)),
+        }
+    }
+}
+
+impl fmt::Display for Frequency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Frequency::Weekly => write!(f, "Weekly"),
+            Frequency::Biweekly => write!(f, "Every two weeks"),
+            Frequency::Monthly => write!(f, "Monthly"),
+            Frequency::Bimonthly => write!(f, "Every two months"),
+            Frequency::Quarterly => write!(f, "Quarterly"),
+            Frequency::Annual => write!(f, "Annual"),
+        }
+    }
+}
+
+/// A magazine or journal we subscribe to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerialTitle {
+    pub id: u32,
+    pub title: String,
+    #[serde(default)]
+    pub publisher: String,
+    #[serde(default)]
+    pub issn: Option<String>,
+    pub frequency: Frequency,
+    pub first_issue: NaiveDate,
+    /// Issues predicted so far, counting from the first.
+    #[serde(default)]
+    pub issues_predicted: u32,
+}
+
+impl SerialTitle {
+    pub fn new(
+        id: u32,
+        title: String,
+        publisher: String,
+        frequency: Frequency,
+        first_issue: NaiveDate,
+    ) -> Self {
+        Self {
+            id,
+            title,
+            publisher,
+            issn: None,
+            frequency,
+            first_issue,
+            issues_predicted: 0,
+        }
+    }
+
+    /// Publication date of the next issue not yet predicted.
+    pub fn next_expected(&self) -> Option<NaiveDate> {
+        self.frequency
+            .issue_date(self.first_issue, self.issues_predicted)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IssueStatus {
+    Expected,
+    Received,
+    /// Reported to the publisher as not received.
+    Claimed,
+}
+
+impl fmt::Display for IssueStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IssueStatus::Expected => write!(f, "Expected"),
+            IssueStatus::Received => write!(f, "Received"),
+            IssueStatus::Claimed => write!(f, "Claimed"),
+        }
+    }
+}
+
+/// One issue of a serial. A received issue is catalogued as a periodical
+/// so it can be loaned like any other item.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerialIssue {
+    pub id: u32,
+    pub serial_id: u32,
+    pub expected_on: NaiveDate,
+    pub status: IssueStatus,
+    #[serde(default)]
+    pub received_on: Option<NaiveDate>,
+    #[serde(default)]
+    pub claimed_on: Option<NaiveDate>,
+    #[serde(default)]
+    pub book_id: Option<u32>,
+}
+
+impl SerialIssue {
+    pub fn new(id: u32, serial_id: u32, expected_on: NaiveDate) -> Self {
+        Self {
+            id,
+            serial_id,
+            expected_on,
+            status: IssueStatus::Expected,
+            received_on: None,
+            claimed_on: None,
+            book_id: None,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        self.expected_on.format("%Y-%m-%d").to_string()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClaimReason {
+    /// Not received within the grace period after its expected date.
+    Late,
+    /// A later issue of the same serial has already arrived.
+    Missing,
+}
+
+impl fmt::Display for ClaimReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClaimReason::Late => write!(f, "Late"),
+            ClaimReason::Missing => write!(f, "Missing"),
+        }
+    }
+}
+
+/// An issue that should be claimed from the publisher.
+#[derive(Debug, Clone)]
+pub struct IssueClaim {
+    pub issue: SerialIssue,
+    pub reason: ClaimReason,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SerialRules {
+    /// Days after its expected date before an issue counts as late.
+    pub claim_grace_days: u32,
+}
+
+impl Default for SerialRules {
+    fn default() -> Self {
+        Self {
+            claim_grace_days: 14,
+        }
+    }
+}
+
+/// Serial titles and their issues, persisted in `serials.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Serials {
+    #[serde(default)]
+    pub titles: Vec<SerialTitle>,
+    #[serde(default)]
+    pub issues: Vec<SerialIssue>,
+}
+
+#[derive(Debug)]
+pub enum SerialError {
+    IoError(io::Error),
+    JsonError(serde_json::Error),
+    BookError(BookError),
+    SerialNotFound,
+    SerialAlreadyExists(String),
+    IssueNotFound,
+    IssueAlreadyReceived,
+    InvalidDate,
+    IdError(IdError),
+}
+
+impl fmt::Display for SerialError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SerialError::IoError(err) => write!(f, "IO Error: {}", err),
+            SerialError::JsonError(err) => write!(f, "JSON Error: {}", err),
+            SerialError::IdError(err) => write!(f, "{}", err),
+            SerialError::BookError(err) => write!(f, "{}", err),
+            SerialError::SerialNotFound => write!(f, "Serial not found"),
+            SerialError::SerialAlreadyExists(title) => {
+                write!(f, "Serial '{}' already exists", title)
+            }
+            SerialError::IssueNotFound => write!(f, "Issue not found"),
+            SerialError::IssueAlreadyReceived => write!(f, "Issue has already been received"),
+            SerialError::InvalidDate => write!(f, "Issue date is out of range"),
+        }
+    }
+}
+
+impl std::error::Error for SerialError {}
+
+impl From<io::Error> for SerialError {
+    fn from(err: io::Error) -> Self {
+        SerialError::IoError(err)
+    }
+}
+
+impl From<serde_json::Error> for SerialError {
+    fn from(err: serde_json::Error) -> Self {
+        SerialError::JsonError(err)
+    }
+}
+
+impl From<IdError> for SerialError {
+    fn from(err: IdError) -> Self {
+        SerialError::IdError(err)
+    }
+}
+
+impl From<BookError> for SerialError {
+    fn from(err: BookError) -> Self {
+        SerialError::BookError(err)
+    }
+}
@@ -0,0 +1,298 @@
+use super::models::{
+    ClaimReason, IssueClaim, IssueStatus, SerialError, SerialIssue, SerialTitle, Serials,
+};
+use crate::library::ids::models::IdAllocator;
+use chrono::{Days, NaiveDate};
+use std::fs::File;
+use std::io::ErrorKind;
+
+pub fn read_from_json(file_path: &str) -> Result<Serials, SerialError> {
+    let file = match File::open(file_path) {
+        Ok(file) => file,
+        Err(err) => {
+            if err.kind() == ErrorKind::NotFound {
+                return Ok(Serials::default());
+            } else {
+                return Err(SerialError::IoError(err));
+            }
+        }
+    };
+
+    let serials: Serials = serde_json::from_reader(file).map_err(SerialError::JsonError)?;
+
+    Ok(serials)
+}
+
+pub fn save_to_json(file_path: &str, serials: &Serials) -> Result<(), SerialError> {
+    let file = File::create(file_path)?;
+    serde_json::to_writer_pretty(file, serials)?;
+    Ok(())
+}
+
+/// Adds a serial title and returns its id.
+pub fn add_serial(
+    titles: &mut Vec<SerialTitle>,
+    ids: &mut IdAllocator,
+    mut serial: SerialTitle,
+) -> Result<u32, SerialError> {
+    if titles
+        .iter()
+        .any(|s| s.title.eq_ignore_ascii_case(&serial.title))
+    {
+        return Err(SerialError::SerialAlreadyExists(serial.title));
+    }
+
+    serial.id = ids.allocate()?;
+    let id = serial.id;
+    titles.push(serial);
+    Ok(id)
+}
+
+/// Adds the expected issues of a serial published up to and including
+/// `through`, following its publication frequency. Returns the new issues.
+pub fn predict_issues(
+    serials: &mut Serials,
+    ids: &mut IdAllocator,
+    serial_id: u32,
+    through: NaiveDate,
+) -> Result<Vec<SerialIssue>, SerialError> {
+    let serial = serials
+        .titles
+        .iter_mut()
+        .find(|s| s.id == serial_id)
+        .ok_or(SerialError::SerialNotFound)?;
+
+    let mut predicted = Vec::new();
+    loop {
+        let expected_on = serial.next_expected().ok_or(SerialError::InvalidDate)?;
+        if expected_on > through {
+            break;
+        }
+        predicted.push(SerialIssue::new(ids.allocate()?, serial_id, expected_on));
+        serial.issues_predicted += 1;
+    }
+
+    serials.issues.extend(predicted.iter().cloned());
+    Ok(predicted)
+}
+
+/// Checks in an expected or claimed issue, catalogued as `book_id`.
+pub fn check_in_issue(
+    issues: &mut [SerialIssue],
+    issue_id: u32,
+    date: NaiveDate,
+    book_id: u32,
+) -> Result<SerialIssue, SerialError> {
+    let issue = find_receivable(issues, issue_id)?;
+    issue.status = IssueStatus::Received;
+    issue.received_on = Some(date);
+    issue.book_id = Some(book_id);
+    Ok(issue.clone())
+}
+
+pub fn claim_issue(
+    issues: &mut [SerialIssue],
+    issue_id: u32,
+    date: NaiveDate,
+) -> Result<SerialIssue, SerialError> {
+    let issue = find_receivable(issues, issue_id)?;
+    issue.status = IssueStatus::Claimed;
+    issue.claimed_on = Some(date);
+    Ok(issue.clone())
+}
+
+/// An issue that exists and has not arrived yet.
+pub fn receivable_issue(
+    issues: &[SerialIssue],
+    issue_id: u32,
+) -> Result<&SerialIssue, SerialError> {
+    let issue = issues
+        .iter()
+        .find(|i| i.id == issue_id)
+        .ok_or(SerialError::IssueNotFound)?;
+    if issue.status == IssueStatus::Received {
+        return Err(SerialError::IssueAlreadyReceived);
+    }
+    Ok(issue)
+}
+
+fn find_receivable(
+    issues: &mut [SerialIssue],
+    issue_id: u32,
+) -> Result<&mut SerialIssue, SerialError> {
+    receivable_issue(issues, issue_id)?;
+    issues
+        .iter_mut()
+        .find(|i| i.id == issue_id)
+        .ok_or(SerialError::IssueNotFound)
+}
+
+/// Expected issues that should be claimed as of `as_of`: those skipped by a
+/// later issue that did arrive, and those past the grace period.
+pub fn claims_due(issues: &[SerialIssue], as_of: NaiveDate, grace_days: u32) -> Vec<IssueClaim> {
+    let mut claims: Vec<IssueClaim> = issues
+        .iter()
+        .filter(|i| i.status == IssueStatus::Expected)
+        .filter_map(|issue| {
+            let later_received = issues.iter().any(|other| {
+                other.serial_id == issue.serial_id
+                    && other.status == IssueStatus::Received
+                    && other.expected_on > issue.expected_on
+            });
+            let late = issue
+                .expected_on
+                .checked_add_days(Days::new(grace_days.into()))
+                .is_some_and(|deadline| deadline < as_of);
+
+            let reason = if later_received {
+                ClaimReason::Missing
+            } else if late {
+                ClaimReason::Late
+            } else {
+                return None;
+            };
+            Some(IssueClaim {
+                issue: issue.clone(),
+                reason,
+            })
+        })
+        .collect();
+    claims.sort_by_key(|c| (c.issue.serial_id, c.issue.expected_on));
+    claims
+}
+
+pub fn get_issues(issues: &[SerialIssue], serial_id: u32) -> Vec<SerialIssue> {
+    let mut found: Vec<SerialIssue> = issues
+        .iter()
+        .filter(|i| i.serial_id == serial_id)
+        .cloned()
+        .collect();
+    found.sort_by_key(|i| i.expected_on);
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::serials::models::Frequency;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).expect("Data inválida")
+    }
+
+    fn serials() -> Serials {
+        Serials {
+            titles: vec![SerialTitle::new(
+                1,
+                "Revista Mensal".to_string(),
+                "Editora".to_string(),
+                Frequency::Monthly,
+                date(2024, 1, 31),
+            )],
+            issues: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_predict_issues_follows_frequency() {
+        let mut serials = serials();
+        let mut ids = IdAllocator::default();
+
+        let predicted = predict_issues(&mut serials, &mut ids, 1, date(2024, 4, 30))
+            .expect("Falha ao prever fascículos");
+        let dates: Vec<NaiveDate> = predicted.iter().map(|i| i.expected_on).collect();
+        assert_eq!(
+            dates,
+            vec![
+                date(2024, 1, 31),
+                date(2024, 2, 29),
+                date(2024, 3, 31),
+                date(2024, 4, 30)
+            ]
+        );
+
+        // Predicting again only adds what is new.
+        let again = predict_issues(&mut serials, &mut ids, 1, date(2024, 5, 31))
+            .expect("Falha ao prever fascículos");
+        assert_eq!(again.len(), 1);
+        assert_eq!(serials.issues.len(), 5);
+        assert!(matches!(
+            predict_issues(&mut serials, &mut ids, 9, date(2024, 5, 31)),
+            Err(SerialError::SerialNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_check_in_and_claim_issues() {
+        let mut serials = serials();
+        let mut ids = IdAllocator::default();
+        predict_issues(&mut serials, &mut ids, 1, date(2024, 3, 31))
+            .expect("Falha ao prever fascículos");
+
+        let received = check_in_issue(&mut serials.issues, 2, date(2024, 3, 2), 10)
+            .expect("Falha ao registrar fascículo");
+        assert_eq!(received.book_id, Some(10));
+        assert!(matches!(
+            check_in_issue(&mut serials.issues, 2, date(2024, 3, 2), 11),
+            Err(SerialError::IssueAlreadyReceived)
+        ));
+
+        let claims = claims_due(&serials.issues, date(2024, 4, 5), 14);
+        let reasons: Vec<(u32, ClaimReason)> =
+            claims.iter().map(|c| (c.issue.id, c.reason)).collect();
+        assert_eq!(reasons, vec![(1, ClaimReason::Missing)]);
+
+        let claims = claims_due(&serials.issues, date(2024, 4, 15), 14);
+        assert_eq!(claims.len(), 2);
+        assert_eq!(claims[1].reason, ClaimReason::Late);
+
+        claim_issue(&mut serials.issues, 1, date(2024, 4, 15)).expect("Falha ao reclamar");
+        assert_eq!(claims_due(&serials.issues, date(2024, 4, 15), 14).len(), 1);
+        // A claimed issue can still arrive.
+        assert!(check_in_issue(&mut serials.issues, 1, date(2024, 4, 20), 12).is_ok());
+    }
+
+    #[test]
+    fn test_add_serial_rejects_duplicates() {
+        let mut serials = serials();
+        let mut ids = IdAllocator { next_id: 2 };
+        let serial = SerialTitle::new(
+            0,
+            "revista mensal".to_string(),
+            String::new(),
+            Frequency::Weekly,
+            date(2024, 1, 1),
+        );
+        assert!(matches!(
+            add_serial(&mut serials.titles, &mut ids, serial),
+            Err(SerialError::SerialAlreadyExists(_))
+        ));
+    }
+
+    #[test]
+    fn test_save_and_read_serials() {
+        let temp_file = NamedTempFile::new().expect("Não foi possível criar arquivo temporário");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let serials = serials();
+        assert!(save_to_json(file_path, &serials).is_ok());
+
+        let loaded = read_from_json(file_path).expect("Falha ao ler periódicos");
+        assert_eq!(loaded.titles.len(), 1);
+        assert_eq!(loaded.titles[0].frequency, Frequency::Monthly);
+    }
+
+    #[test]
+    fn test_read_from_malformed_json() {
+        let mut temp_file =
+            NamedTempFile::new().expect("Não foi possível criar arquivo temporário");
+        writeln!(temp_file, "isto não é um JSON válido")
+            .expect("Falha ao escrever no arquivo temporário");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let result = read_from_json(file_path);
+        assert!(matches!(result, Err(SerialError::JsonError(_))));
+    }
+}
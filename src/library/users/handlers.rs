@@ -1,5 +1,8 @@
 use super::service;
-use crate::library::users::models::{User, UserError};
+use crate::library::users::models::{
+    AccountStatus, ContactDetails, PatronCategory, User, UserError,
+};
+use chrono::NaiveDate;
 
 pub(crate) fn save_users(users: &[User]) -> Result<(), UserError> {
     service::save_to_json("users.json", users)
@@ -21,14 +24,79 @@ pub(crate) fn delete_user(users: &mut Vec<User>, id: u32) -> Result<(), UserErro
     service::delete_user(users, id)
 }
 
-pub(crate) fn update_user(users: &mut Vec<User>, id: u32, name: String) -> Result<(), UserError> {
+pub(crate) fn update_user(users: &mut [User], id: u32, name: String) -> Result<(), UserError> {
     service::update_user(users, id, name)
 }
 
-pub(crate) fn print_users(users: &[User]) {
+pub(crate) fn update_contact(
+    users: &mut [User],
+    id: u32,
+    contact: ContactDetails,
+    date_of_birth: Option<NaiveDate>,
+) -> Result<(), UserError> {
+    service::update_contact(users, id, contact, date_of_birth)
+}
+
+pub(crate) fn set_status(
+    users: &mut [User],
+    id: u32,
+    status: AccountStatus,
+) -> Result<(), UserError> {
+    service::set_status(users, id, status)
+}
+
+pub(crate) fn set_category(
+    users: &mut [User],
+    id: u32,
+    category: PatronCategory,
+) -> Result<(), UserError> {
+    service::set_category(users, id, category)
+}
+
+pub(crate) fn set_loan_limit_override(
+    users: &mut [User],
+    id: u32,
+    limit: Option<u32>,
+) -> Result<(), UserError> {
+    service::set_loan_limit_override(users, id, limit)
+}
+
+pub(crate) fn renew_membership(
+    users: &mut [User],
+    id: u32,
+    expiry: NaiveDate,
+) -> Result<(), UserError> {
+    service::renew_membership(users, id, expiry)
+}
+
+pub(crate) fn print_users(users: &[User], today: NaiveDate) {
     for user in users {
         println!("ID: {}", user.id);
         println!("Name: {}", user.name);
+        println!("Category: {}", user.category);
+        println!("Status: {}", user.status_on(today));
+        if let Some(email) = &user.contact.email {
+            println!("Email: {}", email);
+        }
+        if let Some(phone) = &user.contact.phone {
+            println!("Phone: {}", phone);
+        }
+        if let Some(address) = &user.contact.address {
+            println!("Address: {}", address);
+        }
+        if let Some(date_of_birth) = user.date_of_birth {
+            println!("Date of Birth: {}", date_of_birth);
+        }
+        if let Some(limit) = user.max_loans_override {
+            println!("Loan Limit Override: {}", limit);
+        }
+        if let Some(date) = user.registration_date {
+            println!("Registered: {}", date);
+        }
+        match user.membership_expiry {
+            Some(expiry) => println!("Membership Expires: {}", expiry),
+            None => println!("Membership Expires: Never"),
+        }
         println!();
     }
 }
@@ -1,16 +1,113 @@
+use crate::library::ids::models::IdError;
+use crate::library::policies::models::LoanPolicy;
+use chrono::{Local, NaiveDate};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::io;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AccountStatus {
+    #[default]
+    Active,
+    Expired,
+    Suspended,
+    Blocked,
+}
+
+impl fmt::Display for AccountStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccountStatus::Active => write!(f, "Active"),
+            AccountStatus::Expired => write!(f, "Expired"),
+            AccountStatus::Suspended => write!(f, "Suspended"),
+            AccountStatus::Blocked => write!(f, "Blocked"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+pub enum PatronCategory {
+    #[default]
+    Student,
+    Staff,
+    Child,
+    External,
+}
+
+impl fmt::Display for PatronCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatronCategory::Student => write!(f, "Student"),
+            PatronCategory::Staff => write!(f, "Staff"),
+            PatronCategory::Child => write!(f, "Child"),
+            PatronCategory::External => write!(f, "External"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ContactDetails {
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub phone: Option<String>,
+    #[serde(default)]
+    pub address: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct User {
     pub id: u32,
     pub name: String,
+    #[serde(default)]
+    pub category: PatronCategory,
+    #[serde(default)]
+    pub contact: ContactDetails,
+    #[serde(default)]
+    pub date_of_birth: Option<NaiveDate>,
+    /// Unknown for records saved before registration dates were kept.
+    #[serde(default)]
+    pub registration_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub membership_expiry: Option<NaiveDate>,
+    #[serde(default)]
+    pub status: AccountStatus,
+    /// Replaces the category's loan limit for this patron, e.g. for class sets.
+    #[serde(default)]
+    pub max_loans_override: Option<u32>,
+}
+
+fn today() -> NaiveDate {
+    Local::now().date_naive()
 }
 
 impl User {
     pub fn new(id: u32, name: String) -> Self {
-        Self { id, name }
+        Self {
+            id,
+            name,
+            category: PatronCategory::default(),
+            contact: ContactDetails::default(),
+            date_of_birth: None,
+            registration_date: Some(today()),
+            membership_expiry: None,
+            status: AccountStatus::Active,
+            max_loans_override: None,
+        }
+    }
+
+    /// The stored status, reported as `Expired` once the membership expiry date has passed.
+    pub fn status_on(&self, date: NaiveDate) -> AccountStatus {
+        match (self.status, self.membership_expiry) {
+            (AccountStatus::Active, Some(expiry)) if expiry < date => AccountStatus::Expired,
+            (status, _) => status,
+        }
+    }
+
+    /// How many loans the patron may hold at once under `policy`.
+    pub fn loan_limit(&self, policy: &LoanPolicy) -> u32 {
+        self.max_loans_override
+            .unwrap_or(policy.max_concurrent_loans)
     }
 }
 
@@ -20,6 +117,7 @@ pub enum UserError {
     JsonError(serde_json::Error),
     UserNotFound,
     UserAlreadyExists,
+    IdError(IdError),
 }
 
 impl fmt::Display for UserError {
@@ -27,6 +125,7 @@ impl fmt::Display for UserError {
         match self {
             UserError::IoError(err) => write!(f, "IO Error: {}", err),
             UserError::JsonError(err) => write!(f, "JSON Error: {}", err),
+            UserError::IdError(err) => write!(f, "{}", err),
             UserError::UserNotFound => write!(f, "User not found"),
             UserError::UserAlreadyExists => write!(f, "User already exists"),
         }
@@ -46,3 +145,9 @@ impl From<serde_json::Error> for UserError {
         UserError::JsonError(err)
     }
 }
+
+impl From<IdError> for UserError {
+    fn from(err: IdError) -> Self {
+        UserError::IdError(err)
+    }
+}
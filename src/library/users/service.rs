@@ -1,4 +1,5 @@
-use super::models::{User, UserError};
+use super::models::{AccountStatus, ContactDetails, PatronCategory, User, UserError};
+use chrono::NaiveDate;
 use std::fs::File;
 use std::io::ErrorKind;
 
@@ -53,7 +54,7 @@ pub fn delete_user(users: &mut Vec<User>, id: u32) -> Result<(), UserError> {
     }
 }
 
-pub fn update_user(users: &mut Vec<User>, id: u32, name: String) -> Result<(), UserError> {
+pub fn update_user(users: &mut [User], id: u32, name: String) -> Result<(), UserError> {
     let user = users.iter_mut().find(|u| u.id == id);
     match user {
         Some(u) => {
@@ -64,34 +65,95 @@ pub fn update_user(users: &mut Vec<User>, id: u32, name: String) -> Result<(), U
     }
 }
 
+pub fn update_contact(
+    users: &mut [User],
+    id: u32,
+    contact: ContactDetails,
+    date_of_birth: Option<NaiveDate>,
+) -> Result<(), UserError> {
+    match users.iter_mut().find(|u| u.id == id) {
+        Some(u) => {
+            u.contact = contact;
+            u.date_of_birth = date_of_birth;
+            Ok(())
+        }
+        None => Err(UserError::UserNotFound),
+    }
+}
+
+pub fn set_status(users: &mut [User], id: u32, status: AccountStatus) -> Result<(), UserError> {
+    match users.iter_mut().find(|u| u.id == id) {
+        Some(u) => {
+            u.status = status;
+            Ok(())
+        }
+        None => Err(UserError::UserNotFound),
+    }
+}
+
+pub fn set_category(
+    users: &mut [User],
+    id: u32,
+    category: PatronCategory,
+) -> Result<(), UserError> {
+    match users.iter_mut().find(|u| u.id == id) {
+        Some(u) => {
+            u.category = category;
+            Ok(())
+        }
+        None => Err(UserError::UserNotFound),
+    }
+}
+
+pub fn set_loan_limit_override(
+    users: &mut [User],
+    id: u32,
+    limit: Option<u32>,
+) -> Result<(), UserError> {
+    match users.iter_mut().find(|u| u.id == id) {
+        Some(u) => {
+            u.max_loans_override = limit;
+            Ok(())
+        }
+        None => Err(UserError::UserNotFound),
+    }
+}
+
+/// Extends the membership to `expiry`, reactivating an expired account.
+/// Suspended and blocked accounts keep their status.
+pub fn renew_membership(users: &mut [User], id: u32, expiry: NaiveDate) -> Result<(), UserError> {
+    match users.iter_mut().find(|u| u.id == id) {
+        Some(u) => {
+            u.membership_expiry = Some(expiry);
+            if u.status == AccountStatus::Expired {
+                u.status = AccountStatus::Active;
+            }
+            Ok(())
+        }
+        None => Err(UserError::UserNotFound),
+    }
+}
+
 /// Test Cases
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::library::policies::models::LoanPolicy;
     use std::io::Write;
     use tempfile::NamedTempFile;
 
     #[test]
     fn test_add_user_success() {
         let mut users = Vec::new();
-        let user = User {
-            id: 1,
-            name: "Alice".to_string(),
-        };
+        let user = User::new(1, "Alice".to_string());
         assert!(add_user(&mut users, user.clone()).is_ok());
         assert_eq!(users.len(), 1);
     }
 
     #[test]
     fn test_add_user_duplicate_id() {
-        let mut users = vec![User {
-            id: 1,
-            name: "Alice".to_string(),
-        }];
-        let duplicate_user = User {
-            id: 1,
-            name: "Bob".to_string(),
-        };
+        let mut users = vec![User::new(1, "Alice".to_string())];
+        let duplicate_user = User::new(1, "Bob".to_string());
         let result = add_user(&mut users, duplicate_user);
         assert!(matches!(result, Err(UserError::UserAlreadyExists)));
         assert_eq!(users.len(), 1);
@@ -100,14 +162,8 @@ mod tests {
     #[test]
     fn test_delete_user_success() {
         let mut users = vec![
-            User {
-                id: 1,
-                name: "Alice".to_string(),
-            },
-            User {
-                id: 2,
-                name: "Bob".to_string(),
-            },
+            User::new(1, "Alice".to_string()),
+            User::new(2, "Bob".to_string()),
         ];
         assert!(delete_user(&mut users, 1).is_ok());
         assert_eq!(users.len(), 1);
@@ -116,10 +172,7 @@ mod tests {
 
     #[test]
     fn test_delete_user_not_found() {
-        let mut users = vec![User {
-            id: 1,
-            name: "Alice".to_string(),
-        }];
+        let mut users = vec![User::new(1, "Alice".to_string())];
         let result = delete_user(&mut users, 2);
         assert!(matches!(result, Err(UserError::UserNotFound)));
         assert_eq!(users.len(), 1);
@@ -127,10 +180,7 @@ mod tests {
 
     #[test]
     fn test_update_user_success() {
-        let mut users = vec![User {
-            id: 1,
-            name: "Alice".to_string(),
-        }];
+        let mut users = vec![User::new(1, "Alice".to_string())];
         let new_name = "Alice Smith".to_string();
         assert!(update_user(&mut users, 1, new_name.clone()).is_ok());
         assert_eq!(users[0].name, new_name);
@@ -138,29 +188,115 @@ mod tests {
 
     #[test]
     fn test_update_user_not_found() {
-        let mut users = vec![User {
-            id: 1,
-            name: "Alice".to_string(),
-        }];
+        let mut users = vec![User::new(1, "Alice".to_string())];
         let result = update_user(&mut users, 2, "Bob".to_string());
         assert!(matches!(result, Err(UserError::UserNotFound)));
     }
 
+    #[test]
+    fn test_status_on_reports_expired_membership() {
+        let mut user = User::new(1, "Alice".to_string());
+        user.membership_expiry = NaiveDate::from_ymd_opt(2024, 6, 30);
+
+        let before = NaiveDate::from_ymd_opt(2024, 6, 30).unwrap();
+        let after = NaiveDate::from_ymd_opt(2024, 7, 1).unwrap();
+        assert_eq!(user.status_on(before), AccountStatus::Active);
+        assert_eq!(user.status_on(after), AccountStatus::Expired);
+
+        user.status = AccountStatus::Blocked;
+        assert_eq!(user.status_on(after), AccountStatus::Blocked);
+    }
+
+    #[test]
+    fn test_renew_membership_reactivates_expired_account() {
+        let mut users = vec![User {
+            status: AccountStatus::Expired,
+            ..User::new(1, "Alice".to_string())
+        }];
+        let expiry = NaiveDate::from_ymd_opt(2030, 1, 1).unwrap();
+        assert!(renew_membership(&mut users, 1, expiry).is_ok());
+        assert_eq!(users[0].status, AccountStatus::Active);
+        assert_eq!(users[0].membership_expiry, Some(expiry));
+    }
+
+    #[test]
+    fn test_renew_membership_keeps_suspension() {
+        let mut users = vec![User {
+            status: AccountStatus::Suspended,
+            ..User::new(1, "Alice".to_string())
+        }];
+        let expiry = NaiveDate::from_ymd_opt(2030, 1, 1).unwrap();
+        assert!(renew_membership(&mut users, 1, expiry).is_ok());
+        assert_eq!(users[0].status, AccountStatus::Suspended);
+    }
+
+    #[test]
+    fn test_update_contact_and_status() {
+        let mut users = vec![User::new(1, "Alice".to_string())];
+        let contact = ContactDetails {
+            email: Some("alice@example.com".to_string()),
+            phone: None,
+            address: Some("Rua A, 10".to_string()),
+        };
+        assert!(update_contact(&mut users, 1, contact, None).is_ok());
+        assert!(set_status(&mut users, 1, AccountStatus::Suspended).is_ok());
+        assert_eq!(users[0].contact.email.as_deref(), Some("alice@example.com"));
+        assert_eq!(users[0].status, AccountStatus::Suspended);
+        assert!(matches!(
+            set_status(&mut users, 2, AccountStatus::Active),
+            Err(UserError::UserNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_set_category() {
+        let mut users = vec![User::new(1, "Alice".to_string())];
+        assert_eq!(users[0].category, PatronCategory::Student);
+        assert!(set_category(&mut users, 1, PatronCategory::Staff).is_ok());
+        assert_eq!(users[0].category, PatronCategory::Staff);
+        assert!(matches!(
+            set_category(&mut users, 2, PatronCategory::Child),
+            Err(UserError::UserNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_loan_limit_override() {
+        let mut users = vec![User::new(1, "Alice".to_string())];
+        let policy = LoanPolicy::default();
+        assert_eq!(users[0].loan_limit(&policy), policy.max_concurrent_loans);
+
+        assert!(set_loan_limit_override(&mut users, 1, Some(30)).is_ok());
+        assert_eq!(users[0].loan_limit(&policy), 30);
+
+        assert!(set_loan_limit_override(&mut users, 1, None).is_ok());
+        assert_eq!(users[0].loan_limit(&policy), policy.max_concurrent_loans);
+        assert!(matches!(
+            set_loan_limit_override(&mut users, 2, Some(30)),
+            Err(UserError::UserNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_read_legacy_user_without_new_fields() {
+        let mut temp_file =
+            NamedTempFile::new().expect("Não foi possível criar arquivo temporário");
+        write!(temp_file, r#"[{{"id": 1, "name": "Maria Oliveira"}}]"#)
+            .expect("Falha ao escrever no arquivo temporário");
+        let file_path = temp_file.path().to_str().unwrap();
+
+        let users = read_from_json(file_path).expect("Falha ao ler usuários");
+        assert_eq!(users[0].status, AccountStatus::Active);
+        assert!(users[0].membership_expiry.is_none());
+        assert!(users[0].registration_date.is_none());
+    }
+
     #[test]
     fn test_search_users_found() {
         let users = vec![
-            User {
-                id: 1,
-                name: "Alice".to_string(),
-            },
-            User {
-                id: 2,
-                name: "Bob".to_string(),
-            },
-            User {
-                id: 3,
-                name: "Charlie".to_string(),
-            },
+            User::new(1, "Alice".to_string()),
+            User::new(2, "Bob".to_string()),
+            User::new(3, "Charlie".to_string()),
         ];
         let results = search_users(&users, "Bob");
         assert_eq!(results.len(), 1);
@@ -170,14 +306,8 @@ mod tests {
     #[test]
     fn test_search_users_not_found() {
         let users = vec![
-            User {
-                id: 1,
-                name: "Alice".to_string(),
-            },
-            User {
-                id: 2,
-                name: "Bob".to_string(),
-            },
+            User::new(1, "Alice".to_string()),
+            User::new(2, "Bob".to_string()),
         ];
         let results = search_users(&users, "Charlie");
         assert!(results.is_empty());
@@ -189,14 +319,8 @@ mod tests {
         let file_path = temp_file.path().to_str().unwrap();
 
         let users = vec![
-            User {
-                id: 1,
-                name: "Alice".to_string(),
-            },
-            User {
-                id: 2,
-                name: "Bob".to_string(),
-            },
+            User::new(1, "Alice".to_string()),
+            User::new(2, "Bob".to_string()),
         ];
 
         assert!(save_to_json(file_path, &users).is_ok());
@@ -1,7 +1,13 @@
 mod library;
 
-use crate::library::books::models::Book;
-use crate::library::users::models::User;
+use crate::library::books::classification::{CallNumber, Classification};
+use crate::library::books::models::{Book, ItemStatus, MaterialDetails, MaterialFormat};
+use crate::library::digital::models::License;
+use crate::library::integrity::models::DeletePolicy;
+use crate::library::loans::models::{ConditionCheck, HistoryFilter};
+use crate::library::serials::models::Frequency;
+use crate::library::users::models::{AccountStatus, ContactDetails, PatronCategory, User};
+use chrono::{Local, Months, NaiveDate, Weekday};
 use library::Library;
 use std::io::{self, Write};
 
@@ -10,6 +16,23 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     if let Err(e) = library.load_data() {
         eprintln!("Erro ao carregar os dados: {}", e);
+        eprintln!("Os arquivos que falharam não serão sobrescritos ao salvar.");
+    }
+    if library.set_aside_loans() > 0 {
+        eprintln!(
+            "{} empréstimo(s) ilegível(is) movido(s) para {}.",
+            library.set_aside_loans(),
+            library::loans::handlers::SET_ASIDE_FILE
+        );
+    }
+
+    // Digital loans end on their due date without being returned.
+    let assigned = library.expire_digital_loans(Local::now().date_naive());
+    if !assigned.is_empty() {
+        println!(
+            "{} empréstimo(s) digital(is) atribuído(s) da fila de espera.",
+            assigned.len()
+        );
     }
 
     loop {
@@ -22,7 +45,22 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("6. Listar Usuários");
         println!("7. Listar Empréstimos Ativos");
         println!("8. Pesquisar Livros");
-        println!("9. Sair");
+        println!("9. Alterar Situação de um Livro");
+        println!("10. Lista de Estante");
+        println!("11. Gerenciar Usuário");
+        println!("12. Relatório de Atrasos");
+        println!("13. Multas e Pagamentos");
+        println!("14. Renovar Empréstimo");
+        println!("15. Reservas");
+        println!("16. Calendário");
+        println!("17. Empréstimo de Vários Itens");
+        println!("18. Histórico de Empréstimos");
+        println!("19. Empréstimos Entre Bibliotecas");
+        println!("20. Periódicos");
+        println!("21. Acervo Digital");
+        println!("22. Filiais");
+        println!("23. Famílias");
+        println!("24. Sair");
         print!("Escolha uma opção: ");
         io::stdout().flush()?;
 
@@ -38,7 +76,22 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             "6" => list_users(&library),
             "7" => library.list_active_loans(),
             "8" => search_books(&library)?,
-            "9" => {
+            "9" => change_book_status(&mut library)?,
+            "10" => shelf_list(&library)?,
+            "11" => manage_user(&mut library)?,
+            "12" => overdue_report(&library),
+            "13" => manage_fines(&mut library)?,
+            "14" => renew_loan(&mut library)?,
+            "15" => manage_holds(&mut library)?,
+            "16" => manage_calendar(&mut library)?,
+            "17" => checkout_session(&mut library)?,
+            "18" => loan_history(&library),
+            "19" => manage_interlibrary(&mut library)?,
+            "20" => manage_serials(&mut library)?,
+            "21" => manage_digital(&mut library)?,
+            "22" => manage_branches(&mut library)?,
+            "23" => manage_households(&mut library)?,
+            "24" => {
                 if let Err(e) = library.save_data() {
                     eprintln!("Erro ao salvar: {}", e);
                 }
@@ -55,15 +108,41 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 fn add_book(library: &mut Library) -> Result<(), Box<dyn std::error::Error>> {
     println!("\n--- Adicionar um livro novo ---");
 
-    let id = prompt_for_u32("Insira o ID do Livro: ");
-    let title = prompt_for_string("Insira o Título do Livro: ");
-    let author = prompt_for_string("Insira o Autor do Livro: ");
-    let pages = prompt_for_u32("Insira o Número de Páginas do Livro: ");
+    let id =
+        prompt_for_optional_u32("Insira o ID do Livro (em branco para gerar automaticamente): ");
+    let format = prompt_for_material_format();
+    let title = prompt_for_string("Insira o Título: ");
+    let author = prompt_for_string("Insira o Autor, Diretor ou Fabricante (opcional): ");
+    let details = prompt_for_material_details(format);
 
-    let new_book = Book::new(id, title, author, pages);
+    let mut new_book = Book::with_details(id, title, author, details);
+
+    if let Some(scheme) = prompt_for_classification(true) {
+        let value = prompt_for_string("Insira o Número de Chamada: ");
+        match CallNumber::parse(scheme, &value) {
+            Ok(call_number) => new_book.call_number = Some(call_number),
+            Err(e) => println!("{}. O livro será cadastrado sem número de chamada.", e),
+        }
+    }
+
+    let location = prompt_for_string("Insira a Localização na Estante (opcional): ");
+    if !location.is_empty() {
+        new_book.shelf_location = Some(location);
+    }
+
+    new_book.barcode = prompt_for_optional_string("Insira o Código de Barras (opcional): ");
+    new_book.price_cents = prompt_for_optional_amount("Insira o Preço de Reposição (opcional): ");
+    let branch_id = prompt_for_optional_u32("Insira o ID da Filial de Origem (opcional): ");
 
     match library.add_book(new_book) {
-        Ok(_) => println!("Livro adicionado com sucesso."),
+        Ok(id) => {
+            println!("Livro adicionado com sucesso (ID {}).", id);
+            if branch_id != 0 {
+                if let Err(e) = library.set_home_branch(id, branch_id) {
+                    println!("Erro ao definir a filial: {}", e);
+                }
+            }
+        }
         Err(e) => println!("Erro ao adicionar o livero: {}", e),
     }
 
@@ -73,13 +152,30 @@ fn add_book(library: &mut Library) -> Result<(), Box<dyn std::error::Error>> {
 fn add_user(library: &mut Library) -> Result<(), Box<dyn std::error::Error>> {
     println!("\n--- Adicionar um novo usuário ---");
 
-    let id = prompt_for_u32("Insira o ID do Usuário: ");
+    let id =
+        prompt_for_optional_u32("Insira o ID do Usuário (em branco para gerar automaticamente): ");
     let name = prompt_for_string("Insira o Nome do Usuário: ");
 
-    let new_user = User::new(id, name);
+    let mut new_user = User::new(id, name);
+    new_user.category = prompt_for_category();
+    new_user.contact = ContactDetails {
+        email: prompt_for_optional_string("Insira o E-mail (opcional): "),
+        phone: prompt_for_optional_string("Insira o Telefone (opcional): "),
+        address: prompt_for_optional_string("Insira o Endereço (opcional): "),
+    };
+    new_user.date_of_birth =
+        prompt_for_optional_date("Insira a Data de Nascimento (YYYY-MM-DD, opcional): ");
+    new_user.membership_expiry = prompt_for_optional_date(
+        "Insira a Validade da Matrícula (YYYY-MM-DD, em branco para um ano): ",
+    )
+    .or_else(|| {
+        new_user
+            .registration_date
+            .and_then(|date| date.checked_add_months(Months::new(12)))
+    });
 
     match library.add_user(new_user) {
-        Ok(_) => println!("Usuário adicionado com sucesso."),
+        Ok(id) => println!("Usuário adicionado com sucesso (ID {}).", id),
         Err(e) => println!("Erro ao adicionar o usuário: {}", e),
     }
 
@@ -102,10 +198,10 @@ fn loan_book(library: &mut Library) -> Result<(), Box<dyn std::error::Error>> {
     let book_id = match option {
         "1" => {
             println!("Livros disponíveis para empréstimo:");
-            for book in library.books.iter().filter(|b| !b.is_borrowed) {
+            for book in library.books.iter().filter(|b| can_be_collected(b)) {
                 println!(
-                    "ID: {}, Título: {}, Autor: {}",
-                    book.id, book.title, book.author
+                    "ID: {}, Título: {}, Autor: {} ({})",
+                    book.id, book.title, book.author, book.status
                 );
             }
 
@@ -115,7 +211,8 @@ fn loan_book(library: &mut Library) -> Result<(), Box<dyn std::error::Error>> {
             let query = prompt_for_string("Digite o nome ou autor do livro: ");
             let results = library.search_books(&query);
 
-            let available_books: Vec<&Book> = results.iter().filter(|b| !b.is_borrowed).collect();
+            let available_books: Vec<&Book> =
+                results.iter().filter(|b| can_be_collected(b)).collect();
 
             if available_books.is_empty() {
                 println!("Nenhum livro disponível encontrado com esse termo.");
@@ -124,8 +221,8 @@ fn loan_book(library: &mut Library) -> Result<(), Box<dyn std::error::Error>> {
                 println!("Livros disponíveis encontrados:");
                 for book in &available_books {
                     println!(
-                        "ID: {}, Título: {}, Autor: {}",
-                        book.id, book.title, book.author
+                        "ID: {}, Título: {}, Autor: {} ({})",
+                        book.id, book.title, book.author, book.status
                     );
                 }
             }
@@ -138,12 +235,9 @@ fn loan_book(library: &mut Library) -> Result<(), Box<dyn std::error::Error>> {
         }
     };
 
-    if let Some(book) = library.books.iter().find(|b| b.id == book_id) {
-        if book.is_borrowed {
-            println!("Este livro já está emprestado.");
-            return Ok(());
-        }
-    } else {
+    // Whether the copy may go to this patron, e.g. a hold waiting on the
+    // shelf for them, is left to `Library::loan_book`.
+    if !library.books.iter().any(|b| b.id == book_id) {
         println!("Livro não encontrado.");
         return Ok(());
     }
@@ -160,38 +254,1008 @@ fn loan_book(library: &mut Library) -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
 
-    let loan_date = prompt_for_string("Digite a data do empréstimo (YYYY-MM-DD): ");
+    let loan_date =
+        prompt_for_date("Digite a data do empréstimo (YYYY-MM-DD, em branco para hoje): ");
+
+    let checklist_items = library.equipment_checklist(book_id);
+    let result = if checklist_items.is_empty() {
+        match prompt_for_optional_u32("Digite o ID da filial (opcional): ") {
+            0 => library.loan_book(user_id, book_id, loan_date),
+            branch_id => library.loan_book_at(branch_id, user_id, book_id, loan_date),
+        }
+    } else {
+        println!("Checklist de condição do equipamento:");
+        let checklist = checklist_items
+            .into_iter()
+            .map(|item| {
+                let ok =
+                    prompt_for_string(&format!("{} está presente e funcionando? (s/n): ", item))
+                        .eq_ignore_ascii_case("s");
+                let note = prompt_for_optional_string("Observação (opcional): ");
+                ConditionCheck { item, ok, note }
+            })
+            .collect();
+        library.loan_equipment(user_id, book_id, loan_date, checklist)
+    };
 
-    match library.loan_book(user_id, book_id, loan_date) {
+    match result {
         Ok(_) => println!("Livro emprestado com sucesso."),
         Err(e) => println!("Erro ao emprestar livro: {}", e),
     }
 
     Ok(())
 }
+/// Copies worth offering at the loan desk: shelved ones, and ones waiting
+/// on the hold shelf for whoever placed the hold.
+fn can_be_collected(book: &Book) -> bool {
+    book.is_available() || book.status == ItemStatus::OnHoldShelf
+}
+
+fn checkout_session(library: &mut Library) -> Result<(), Box<dyn std::error::Error>> {
+    println!("\n--- Empréstimo de Vários Itens ---");
+
+    let user_id = prompt_for_u32("Insira o ID do Usuário: ");
+    let date = prompt_for_date("Data do empréstimo (YYYY-MM-DD, em branco para hoje): ");
+    let mut session = match library.start_checkout(user_id, date) {
+        Ok(session) => session,
+        Err(e) => {
+            println!("Este usuário não pode emprestar: {}", e);
+            return Ok(());
+        }
+    };
+
+    loop {
+        println!(
+            "Itens na sessão: {} (pode emprestar mais {})",
+            session.book_ids.len(),
+            session.remaining()
+        );
+        let identifier =
+            prompt_for_string("Leia o código de barras ou insira o ID (em branco para concluir): ");
+        if identifier.is_empty() {
+            break;
+        }
+
+        let Some(book_id) = library.find_book_id(&identifier) else {
+            println!("Livro não encontrado.");
+            continue;
+        };
+        match library.add_to_checkout(&mut session, book_id) {
+            Ok(_) => println!("Item adicionado."),
+            Err(e) => println!("Item recusado: {}", e),
+        }
+    }
+
+    if session.book_ids.is_empty() {
+        println!("Nenhum item emprestado.");
+        return Ok(());
+    }
+
+    match library.commit_checkout(session) {
+        Ok(receipt) => Library::print_checkout_receipt(&receipt),
+        Err(e) => println!("Erro ao emprestar os itens: {}", e),
+    }
+
+    Ok(())
+}
+
 fn return_book(library: &mut Library) -> Result<(), Box<dyn std::error::Error>> {
     println!("\n--- Devolução de Livros ---");
 
+    let identifier = prompt_for_string("Leia o código de barras ou insira o ID do Livro: ");
+    let Some(book_id) = library.find_book_id(&identifier) else {
+        println!("Livro não encontrado.");
+        return Ok(());
+    };
+
+    println!("1. Devolução normal");
+    println!("2. Devolução com dano");
+    println!("3. Declarar perda pelo usuário");
+    println!("4. Item perdido encontrado");
+    let option = prompt_for_string("Escolha uma opção: ");
+    let date = prompt_for_date("Insira a Data (YYYY-MM-DD, em branco para hoje): ");
+
+    match option.as_str() {
+        "1" => {
+            let note = prompt_for_optional_string("Observação sobre o estado (opcional): ");
+            let result = match prompt_for_optional_u32("ID da filial (opcional): ") {
+                0 => library.check_in(book_id, date, note),
+                branch_id => library.check_in_at(branch_id, book_id, date, note),
+            };
+            match result {
+                Ok(receipt) => Library::print_return_receipt(&receipt),
+                Err(e) => println!("Erro ao devolver o livro: {}", e),
+            }
+        }
+        "2" => match library.return_damaged(book_id, date) {
+            Ok(charged) => println!(
+                "Devolução com dano registrada. Valor cobrado: {}",
+                Library::format_amount(charged)
+            ),
+            Err(e) => println!("Erro ao devolver o livro: {}", e),
+        },
+        "3" => match library.declare_loan_lost(book_id, date) {
+            Ok(charged) => println!(
+                "Perda registrada. Custo de reposição cobrado: {}",
+                Library::format_amount(charged)
+            ),
+            Err(e) => println!("Erro ao declarar a perda: {}", e),
+        },
+        "4" => match library.return_found_item(book_id, date) {
+            Ok(credit) => println!(
+                "Item devolvido ao acervo. Crédito ao usuário: {}",
+                Library::format_amount(credit)
+            ),
+            Err(e) => println!("Erro ao devolver o item: {}", e),
+        },
+        _ => println!("Opção inválida."),
+    }
+
+    Ok(())
+}
+
+fn change_book_status(library: &mut Library) -> Result<(), Box<dyn std::error::Error>> {
+    println!("\n--- Alterar Situação de um Livro ---");
+
     let book_id = prompt_for_u32("Insira o ID do Livro: ");
-    let return_date = prompt_for_string("Insira a Data de Devolução (YYYY-MM-DD): ");
 
-    match library.return_book(book_id, return_date) {
-        Ok(_) => println!("Livro devolvido com sucesso."),
-        Err(e) => println!("Erro ao devolver o livro: {}", e),
+    println!("1. Enviar para reparo");
+    println!("2. Declarar extraviado");
+    println!("3. Retirar do acervo");
+    println!("4. Devolver à estante");
+    println!("5. Excluir do cadastro");
+    println!("6. Alterar número de chamada e localização");
+    println!("7. Corrigir título e autor");
+    let option = prompt_for_string("Escolha uma opção: ");
+
+    match option.as_str() {
+        "5" => {
+            match library.remove_book(book_id, prompt_for_delete_policy()) {
+                Ok(_) => println!("Livro excluído com sucesso."),
+                Err(e) => println!("Erro ao excluir o livro: {}", e),
+            }
+            return Ok(());
+        }
+        "6" => {
+            let call_number = match prompt_for_classification(true) {
+                Some(scheme) => {
+                    let value = prompt_for_string("Insira o Número de Chamada: ");
+                    match CallNumber::parse(scheme, &value) {
+                        Ok(call_number) => Some(call_number),
+                        Err(e) => {
+                            println!("Erro: {}", e);
+                            return Ok(());
+                        }
+                    }
+                }
+                None => None,
+            };
+            let location = prompt_for_optional_string("Localização na Estante (opcional): ");
+            match library.set_book_location(book_id, call_number, location) {
+                Ok(_) => println!("Localização atualizada."),
+                Err(e) => println!("Erro ao atualizar a localização: {}", e),
+            }
+            return Ok(());
+        }
+        "7" => {
+            let title = prompt_for_optional_string("Novo título (em branco para manter): ");
+            let author = prompt_for_optional_string("Novo autor (em branco para manter): ");
+            match library.update_book(book_id, title, author, None) {
+                Ok(_) => println!("Livro atualizado."),
+                Err(e) => println!("Erro ao atualizar o livro: {}", e),
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    let reason = prompt_for_string("Motivo: ");
+    let today = Local::now().date_naive();
+
+    let result = match option.as_str() {
+        "1" => library.send_book_to_repair(book_id, &reason, today),
+        "2" => library.declare_book_lost(book_id, &reason, today),
+        "3" => library.withdraw_book(book_id, &reason, today),
+        "4" => library.restore_book_to_shelf(book_id, &reason, today),
+        _ => {
+            println!("Opção inválida.");
+            return Ok(());
+        }
+    };
+
+    match result {
+        Ok(_) => println!("Situação do livro atualizada com sucesso."),
+        Err(e) => println!("Erro ao atualizar a situação do livro: {}", e),
+    }
+
+    Ok(())
+}
+
+fn shelf_list(library: &Library) -> Result<(), Box<dyn std::error::Error>> {
+    println!("\n--- Lista de Estante ---");
+
+    let Some(scheme) = prompt_for_classification(false) else {
+        println!("Opção inválida.");
+        return Ok(());
+    };
+    let from = prompt_for_string("Número de chamada inicial: ");
+    let to = prompt_for_string("Número de chamada final: ");
+
+    if let Err(e) = library.print_shelf_list(scheme, &from, &to) {
+        println!("Erro ao gerar a lista de estante: {}", e);
+    }
+
+    Ok(())
+}
+
+fn overdue_report(library: &Library) {
+    println!("\n--- Relatório de Atrasos ---");
+
+    let as_of = prompt_for_date("Data de referência (YYYY-MM-DD, em branco para hoje): ");
+    library.print_overdue_report(as_of);
+}
+
+fn manage_fines(library: &mut Library) -> Result<(), Box<dyn std::error::Error>> {
+    println!("\n--- Multas e Pagamentos ---");
+
+    let user_id = prompt_for_u32("Insira o ID do Usuário: ");
+    library.print_statement(user_id);
+
+    println!("1. Registrar pagamento");
+    println!("2. Abonar valor");
+    println!("3. Voltar");
+    let option = prompt_for_string("Escolha uma opção: ");
+
+    let result = match option.as_str() {
+        "1" => {
+            let amount = prompt_for_amount("Valor pago: ");
+            let date = prompt_for_date("Data (YYYY-MM-DD, em branco para hoje): ");
+            library.pay_fine(user_id, amount, date)
+        }
+        "2" => {
+            let amount = prompt_for_amount("Valor abonado: ");
+            let date = prompt_for_date("Data (YYYY-MM-DD, em branco para hoje): ");
+            let loan_id = match prompt_for_optional_u32("ID do empréstimo (opcional): ") {
+                0 => None,
+                loan_id => Some(loan_id),
+            };
+            let note = prompt_for_string("Motivo: ");
+            library.waive_fine(user_id, amount, date, loan_id, note)
+        }
+        _ => return Ok(()),
+    };
+
+    match result {
+        Ok(_) => library.print_statement(user_id),
+        Err(e) => println!("Erro ao registrar o lançamento: {}", e),
+    }
+
+    Ok(())
+}
+
+fn renew_loan(library: &mut Library) -> Result<(), Box<dyn std::error::Error>> {
+    println!("\n--- Renovar Empréstimo ---");
+
+    library.list_active_loans();
+    let loan_id = prompt_for_u32("Insira o ID do Empréstimo: ");
+    let date = prompt_for_date("Data da renovação (YYYY-MM-DD, em branco para hoje): ");
+
+    match library.renew_loan(loan_id, date) {
+        Ok(loan) => println!(
+            "Empréstimo renovado. Nova data de devolução: {}",
+            loan.due_date
+        ),
+        Err(e) => println!("Erro ao renovar o empréstimo: {}", e),
+    }
+
+    Ok(())
+}
+
+fn manage_holds(library: &mut Library) -> Result<(), Box<dyn std::error::Error>> {
+    println!("\n--- Reservas ---");
+
+    println!("1. Reservar livro");
+    println!("2. Cancelar reserva");
+    println!("3. Reservas de um usuário");
+    println!("4. Expirar reservas não retiradas");
+    println!("5. Recolher empréstimo");
+    println!("6. Fila de reservas de um livro");
+    let option = prompt_for_string("Escolha uma opção: ");
+
+    match option.as_str() {
+        "1" => {
+            let user_id = prompt_for_u32("Insira o ID do Usuário: ");
+            let book_id = prompt_for_u32("Insira o ID do Livro: ");
+            let date = prompt_for_date("Data (YYYY-MM-DD, em branco para hoje): ");
+            match library.place_hold(user_id, book_id, date) {
+                Ok((hold_id, recall)) => {
+                    println!(
+                        "Reserva {} registrada. Posição na fila: {}",
+                        hold_id,
+                        library.hold_queue_position(hold_id).unwrap_or_default()
+                    );
+                    if let Some(notice) = recall {
+                        Library::print_recall_notice(&notice);
+                    }
+                }
+                Err(e) => println!("Erro ao reservar: {}", e),
+            }
+        }
+        "2" => {
+            let hold_id = prompt_for_u32("Insira o ID da Reserva: ");
+            let date = prompt_for_date("Data (YYYY-MM-DD, em branco para hoje): ");
+            match library.cancel_hold(hold_id, date) {
+                Ok(_) => println!("Reserva cancelada."),
+                Err(e) => println!("Erro ao cancelar a reserva: {}", e),
+            }
+        }
+        "3" => {
+            let user_id = prompt_for_u32("Insira o ID do Usuário: ");
+            library.list_user_holds(user_id);
+        }
+        "4" => {
+            let date = prompt_for_date("Data (YYYY-MM-DD, em branco para hoje): ");
+            match library.expire_holds(date) {
+                Ok(expired) => println!("{} reserva(s) expirada(s).", expired.len()),
+                Err(e) => println!("Erro ao expirar as reservas: {}", e),
+            }
+        }
+        "5" => {
+            let loan_id = prompt_for_u32("Insira o ID do Empréstimo: ");
+            let date = prompt_for_date("Data (YYYY-MM-DD, em branco para hoje): ");
+            match library.recall_loan(loan_id, date) {
+                Ok(notice) => Library::print_recall_notice(&notice),
+                Err(e) => println!("Erro ao recolher o empréstimo: {}", e),
+            }
+        }
+        "6" => {
+            let book_id = prompt_for_u32("Insira o ID do Livro: ");
+            let queue = library.hold_queue(book_id);
+            if queue.is_empty() {
+                println!("Nenhuma reserva para este livro.");
+            }
+            for (position, hold) in queue.iter().enumerate() {
+                println!(
+                    "{}. Reserva {} - Usuário {} desde {}",
+                    position + 1,
+                    hold.id,
+                    hold.user_id,
+                    hold.placed_on
+                );
+            }
+        }
+        _ => println!("Opção inválida."),
+    }
+
+    Ok(())
+}
+
+fn manage_interlibrary(library: &mut Library) -> Result<(), Box<dyn std::error::Error>> {
+    println!("\n--- Empréstimos Entre Bibliotecas ---");
+
+    println!("1. Cadastrar biblioteca parceira");
+    println!("2. Listar bibliotecas parceiras");
+    println!("3. Solicitar item a uma parceira");
+    println!("4. Registrar pedido de uma parceira");
+    println!("5. Registrar envio");
+    println!("6. Registrar recebimento");
+    println!("7. Registrar devolução");
+    println!("8. Cancelar pedido");
+    println!("9. Listar pedidos em aberto");
+    println!("10. Pedidos atrasados");
+    let option = prompt_for_string("Escolha uma opção: ");
+
+    let result = match option.as_str() {
+        "1" => {
+            let name = prompt_for_string("Nome da biblioteca: ");
+            let contact = prompt_for_string("Contato (opcional): ");
+            library
+                .add_partner_library(name, contact)
+                .map(|id| println!("Biblioteca parceira {} cadastrada.", id))
+        }
+        "2" => {
+            library.list_partner_libraries();
+            Ok(())
+        }
+        "3" => {
+            let partner_id = prompt_for_u32("Insira o ID da Biblioteca Parceira: ");
+            let user_id = prompt_for_u32("Insira o ID do Usuário: ");
+            let title = prompt_for_string("Título: ");
+            let author = prompt_for_string("Autor: ");
+            let date = prompt_for_date("Data (YYYY-MM-DD, em branco para hoje): ");
+            library
+                .request_from_partner(partner_id, user_id, title, author, date)
+                .map(|id| println!("Pedido {} registrado.", id))
+        }
+        "4" => {
+            let partner_id = prompt_for_u32("Insira o ID da Biblioteca Parceira: ");
+            let book_id = prompt_for_u32("Insira o ID do Livro: ");
+            let date = prompt_for_date("Data (YYYY-MM-DD, em branco para hoje): ");
+            library
+                .lend_to_partner(partner_id, book_id, date)
+                .map(|id| println!("Pedido {} registrado.", id))
+        }
+        "5" => {
+            let request_id = prompt_for_u32("Insira o ID do Pedido: ");
+            let date = prompt_for_date("Data (YYYY-MM-DD, em branco para hoje): ");
+            let due_date = prompt_for_optional_date(
+                "Data de devolução definida pela biblioteca que empresta (YYYY-MM-DD, em branco para o prazo padrão): ",
+            );
+            library
+                .ship_ill_item(request_id, date, due_date)
+                .map(|request| {
+                    if let Some(due_date) = request.due_date {
+                        println!("Envio registrado. Devolução até {}.", due_date);
+                    }
+                })
+        }
+        "6" => {
+            let request_id = prompt_for_u32("Insira o ID do Pedido: ");
+            let date = prompt_for_date("Data (YYYY-MM-DD, em branco para hoje): ");
+            library
+                .receive_ill_item(request_id, date)
+                .map(|_| println!("Recebimento registrado."))
+        }
+        "7" => {
+            let request_id = prompt_for_u32("Insira o ID do Pedido: ");
+            let date = prompt_for_date("Data (YYYY-MM-DD, em branco para hoje): ");
+            library
+                .return_ill_item(request_id, date)
+                .map(|_| println!("Devolução registrada."))
+        }
+        "8" => {
+            let request_id = prompt_for_u32("Insira o ID do Pedido: ");
+            let date = prompt_for_date("Data (YYYY-MM-DD, em branco para hoje): ");
+            library
+                .cancel_ill_request(request_id, date)
+                .map(|_| println!("Pedido cancelado."))
+        }
+        "9" => {
+            library.list_open_ill_requests();
+            Ok(())
+        }
+        "10" => {
+            let as_of = prompt_for_date("Data de referência (YYYY-MM-DD, em branco para hoje): ");
+            library.list_overdue_ill_requests(as_of);
+            Ok(())
+        }
+        _ => {
+            println!("Opção inválida.");
+            Ok(())
+        }
+    };
+
+    if let Err(e) = result {
+        println!("Erro: {}", e);
+    }
+
+    Ok(())
+}
+
+fn manage_serials(library: &mut Library) -> Result<(), Box<dyn std::error::Error>> {
+    println!("\n--- Periódicos ---");
+
+    println!("1. Cadastrar periódico");
+    println!("2. Listar periódicos");
+    println!("3. Prever fascículos");
+    println!("4. Registrar chegada de fascículo");
+    println!("5. Fascículos de um periódico");
+    println!("6. Fascículos a reclamar");
+    println!("7. Registrar reclamação");
+    let option = prompt_for_string("Escolha uma opção: ");
+
+    let result = match option.as_str() {
+        "1" => {
+            let title = prompt_for_string("Título: ");
+            let publisher = prompt_for_string("Editora: ");
+            let frequency = prompt_for_frequency();
+            let first_issue = prompt_for_date("Data do primeiro fascículo (YYYY-MM-DD): ");
+            library
+                .add_serial(title, publisher, frequency, first_issue)
+                .map(|id| println!("Periódico {} cadastrado.", id))
+        }
+        "2" => {
+            library.list_serials();
+            Ok(())
+        }
+        "3" => {
+            let serial_id = prompt_for_u32("Insira o ID do Periódico: ");
+            let through = prompt_for_date("Prever até (YYYY-MM-DD): ");
+            library
+                .predict_serial_issues(serial_id, through)
+                .map(|issues| println!("{} fascículo(s) previsto(s).", issues.len()))
+        }
+        "4" => {
+            let issue_id = prompt_for_u32("Insira o ID do Fascículo: ");
+            let date = prompt_for_date("Data (YYYY-MM-DD, em branco para hoje): ");
+            library
+                .check_in_issue(issue_id, date)
+                .map(|book_id| println!("Fascículo registrado como livro {}.", book_id))
+        }
+        "5" => {
+            let serial_id = prompt_for_u32("Insira o ID do Periódico: ");
+            library.list_serial_issues(serial_id);
+            Ok(())
+        }
+        "6" => {
+            let date = prompt_for_date("Data (YYYY-MM-DD, em branco para hoje): ");
+            library.print_serial_claims(date);
+            Ok(())
+        }
+        "7" => {
+            let issue_id = prompt_for_u32("Insira o ID do Fascículo: ");
+            let date = prompt_for_date("Data (YYYY-MM-DD, em branco para hoje): ");
+            library
+                .claim_issue(issue_id, date)
+                .map(|_| println!("Reclamação registrada."))
+        }
+        _ => {
+            println!("Opção inválida.");
+            Ok(())
+        }
+    };
+
+    if let Err(e) = result {
+        println!("Erro: {}", e);
+    }
+
+    Ok(())
+}
+
+fn manage_branches(library: &mut Library) -> Result<(), Box<dyn std::error::Error>> {
+    println!("\n--- Filiais ---");
+
+    println!("1. Cadastrar filial");
+    println!("2. Listar filiais");
+    println!("3. Definir filial de origem de um item");
+    println!("4. Enviar item para outra filial");
+    println!("5. Receber transferência");
+    println!("6. Itens em trânsito");
+    let option = prompt_for_string("Escolha uma opção: ");
+
+    let result = match option.as_str() {
+        "1" => {
+            let name = prompt_for_string("Nome: ");
+            let address = prompt_for_string("Endereço (opcional): ");
+            library
+                .add_branch(name, address)
+                .map(|id| println!("Filial {} cadastrada.", id))
+        }
+        "2" => {
+            library.list_branches();
+            Ok(())
+        }
+        "3" => {
+            let book_id = prompt_for_u32("Insira o ID do Livro: ");
+            let branch_id = prompt_for_u32("Insira o ID da Filial: ");
+            library
+                .set_home_branch(book_id, branch_id)
+                .map(|_| println!("Filial de origem definida."))
+        }
+        "4" => {
+            let book_id = prompt_for_u32("Insira o ID do Livro: ");
+            let branch_id = prompt_for_u32("Insira o ID da Filial de destino: ");
+            let date = prompt_for_date("Data de envio (YYYY-MM-DD, em branco para hoje): ");
+            let reason = prompt_for_string("Motivo (opcional): ");
+            library
+                .send_transfer(book_id, branch_id, date, reason)
+                .map(|id| println!("Transferência {} enviada; item em trânsito.", id))
+        }
+        "5" => {
+            let transfer_id = prompt_for_u32("Insira o ID da Transferência: ");
+            let date = prompt_for_date("Data de recebimento (YYYY-MM-DD, em branco para hoje): ");
+            library
+                .receive_transfer(transfer_id, date)
+                .map(|_| println!("Transferência recebida."))
+        }
+        "6" => {
+            library.list_in_transit();
+            Ok(())
+        }
+        _ => {
+            println!("Opção inválida.");
+            Ok(())
+        }
+    };
+
+    if let Err(e) = result {
+        println!("Erro: {}", e);
+    }
+
+    Ok(())
+}
+
+fn manage_households(library: &mut Library) -> Result<(), Box<dyn std::error::Error>> {
+    println!("\n--- Famílias ---");
+
+    println!("1. Criar família");
+    println!("2. Listar famílias");
+    println!("3. Adicionar membro");
+    println!("4. Remover membro");
+    println!("5. Empréstimos da família");
+    println!("6. Renovar empréstimo da família");
+    println!("7. Definir limite de empréstimos da família");
+    println!("8. Combinar multas da família");
+    println!("9. Saldo da família");
+    let option = prompt_for_string("Escolha uma opção: ");
+
+    let result = match option.as_str() {
+        "1" => {
+            let name = prompt_for_string("Nome da família: ");
+            let guardian_id = prompt_for_u32("Insira o ID do Responsável: ");
+            library
+                .create_household(name, guardian_id)
+                .map(|id| println!("Família {} criada.", id))
+        }
+        "2" => {
+            library.list_households();
+            Ok(())
+        }
+        "3" => {
+            let household_id = prompt_for_u32("Insira o ID da Família: ");
+            let user_id = prompt_for_u32("Insira o ID do Usuário: ");
+            library
+                .add_household_member(household_id, user_id)
+                .map(|_| println!("Membro adicionado."))
+        }
+        "4" => {
+            let household_id = prompt_for_u32("Insira o ID da Família: ");
+            let user_id = prompt_for_u32("Insira o ID do Usuário: ");
+            library
+                .remove_household_member(household_id, user_id)
+                .map(|_| println!("Membro removido."))
+        }
+        "5" => {
+            let guardian_id = prompt_for_u32("Insira o ID do Responsável: ");
+            library.household_loans(guardian_id).map(|entries| {
+                if entries.is_empty() {
+                    println!("Nenhum empréstimo ativo na família.");
+                } else {
+                    Library::print_history(&entries);
+                }
+            })
+        }
+        "6" => {
+            let guardian_id = prompt_for_u32("Insira o ID do Responsável: ");
+            let loan_id = prompt_for_u32("Insira o ID do Empréstimo: ");
+            let date = prompt_for_date("Data da renovação (YYYY-MM-DD, em branco para hoje): ");
+            library
+                .renew_household_loan(guardian_id, loan_id, date)
+                .map(|loan| println!("Empréstimo renovado até {}.", loan.due_date))
+        }
+        "7" => {
+            let household_id = prompt_for_u32("Insira o ID da Família: ");
+            let limit = match prompt_for_optional_u32("Novo limite (em branco para remover): ") {
+                0 => None,
+                limit => Some(limit),
+            };
+            library
+                .set_household_loan_limit(household_id, limit)
+                .map(|_| println!("Limite atualizado."))
+        }
+        "8" => {
+            let household_id = prompt_for_u32("Insira o ID da Família: ");
+            let shared = prompt_for_string("Combinar as multas dos membros? (s/n): ")
+                .eq_ignore_ascii_case("s");
+            library
+                .set_household_shared_fines(household_id, shared)
+                .map(|_| println!("Configuração de multas atualizada."))
+        }
+        "9" => {
+            let household_id = prompt_for_u32("Insira o ID da Família: ");
+            library
+                .household_balance(household_id)
+                .map(|balance| println!("Saldo combinado: {}", Library::format_amount(balance)))
+        }
+        _ => {
+            println!("Opção inválida.");
+            Ok(())
+        }
+    };
+
+    if let Err(e) = result {
+        println!("Erro: {}", e);
+    }
+
+    Ok(())
+}
+
+fn manage_digital(library: &mut Library) -> Result<(), Box<dyn std::error::Error>> {
+    println!("\n--- Acervo Digital ---");
+
+    println!("1. Cadastrar título digital");
+    println!("2. Adicionar licença");
+    println!("3. Listar títulos digitais");
+    println!("4. Emprestar título digital");
+    println!("5. Entrar na fila de espera");
+    println!("6. Sair da fila de espera");
+    println!("7. Devolver antes do prazo");
+    println!("8. Empréstimos digitais de um usuário");
+    let option = prompt_for_string("Escolha uma opção: ");
+
+    let result = match option.as_str() {
+        "1" => {
+            let title = prompt_for_string("Título: ");
+            let author = prompt_for_string("Autor: ");
+            library
+                .add_digital_title(title, author)
+                .map(|id| println!("Título digital {} cadastrado.", id))
+        }
+        "2" => {
+            let title_id = prompt_for_u32("Insira o ID do Título Digital: ");
+            println!("1. Um exemplar, um usuário");
+            println!("2. Licença medida (N simultâneos, expira após M empréstimos ou numa data)");
+            let license = match prompt_for_string("Escolha uma opção: ").as_str() {
+                "2" => License::metered(
+                    0,
+                    prompt_for_u32("Empréstimos simultâneos: "),
+                    match prompt_for_optional_u32(
+                        "Máximo de empréstimos (em branco para ilimitado): ",
+                    ) {
+                        0 => None,
+                        max => Some(max),
+                    },
+                    prompt_for_optional_date("Data de expiração (YYYY-MM-DD, opcional): "),
+                ),
+                _ => License::one_copy_one_user(0),
+            };
+            let date = prompt_for_date("Data (YYYY-MM-DD, em branco para hoje): ");
+            library
+                .add_license(title_id, license, date)
+                .map(|assigned| {
+                    println!("Licença adicionada.");
+                    if !assigned.is_empty() {
+                        println!("{} usuário(s) da fila receberam o título.", assigned.len());
+                    }
+                })
+        }
+        "3" => {
+            let date = prompt_for_date("Data (YYYY-MM-DD, em branco para hoje): ");
+            library.list_digital_titles(date);
+            Ok(())
+        }
+        "4" => {
+            let user_id = prompt_for_u32("Insira o ID do Usuário: ");
+            let title_id = prompt_for_u32("Insira o ID do Título Digital: ");
+            let date = prompt_for_date("Data (YYYY-MM-DD, em branco para hoje): ");
+            library
+                .borrow_digital(user_id, title_id, date)
+                .map(|loan| println!("Empréstimo {} até {}.", loan.id, loan.due_date))
+        }
+        "5" => {
+            let user_id = prompt_for_u32("Insira o ID do Usuário: ");
+            let title_id = prompt_for_u32("Insira o ID do Título Digital: ");
+            let date = prompt_for_date("Data (YYYY-MM-DD, em branco para hoje): ");
+            library
+                .join_digital_waitlist(user_id, title_id, date)
+                .map(|id| println!("Entrada {} na fila de espera.", id))
+        }
+        "6" => {
+            let hold_id = prompt_for_u32("Insira o ID da Entrada na Fila: ");
+            library
+                .leave_digital_waitlist(hold_id)
+                .map(|_| println!("Entrada removida da fila."))
+        }
+        "7" => {
+            let loan_id = prompt_for_u32("Insira o ID do Empréstimo Digital: ");
+            let date = prompt_for_date("Data (YYYY-MM-DD, em branco para hoje): ");
+            library.return_digital(loan_id, date).map(|assigned| {
+                println!("Empréstimo encerrado.");
+                if !assigned.is_empty() {
+                    println!("{} usuário(s) da fila receberam o título.", assigned.len());
+                }
+            })
+        }
+        "8" => {
+            let user_id = prompt_for_u32("Insira o ID do Usuário: ");
+            let date = prompt_for_date("Data (YYYY-MM-DD, em branco para hoje): ");
+            library.list_user_digital_loans(user_id, date);
+            Ok(())
+        }
+        _ => {
+            println!("Opção inválida.");
+            Ok(())
+        }
+    };
+
+    if let Err(e) = result {
+        println!("Erro: {}", e);
+    }
+
+    Ok(())
+}
+
+fn manage_calendar(library: &mut Library) -> Result<(), Box<dyn std::error::Error>> {
+    println!("\n--- Calendário ---");
+
+    library.print_calendar();
+    println!();
+    println!("1. Definir dias de funcionamento");
+    println!("2. Adicionar fechamento");
+    println!("3. Remover fechamento");
+    println!("4. Voltar");
+    let option = prompt_for_string("Escolha uma opção: ");
+
+    let result = match option.as_str() {
+        "1" => {
+            let input = prompt_for_string("Dias de funcionamento (ex.: seg,ter,qua,qui,sex,sab): ");
+            let weekdays: Option<Vec<Weekday>> = input
+                .split(',')
+                .map(|day| parse_weekday(day.trim()))
+                .collect();
+            match weekdays {
+                Some(weekdays) => library.set_opening_days(weekdays),
+                None => {
+                    println!("Dia da semana inválido.");
+                    return Ok(());
+                }
+            }
+        }
+        "2" => match prompt_for_optional_date("Data do fechamento (YYYY-MM-DD): ") {
+            Some(date) => {
+                let reason = prompt_for_string("Motivo (opcional): ");
+                library.add_closure(date, reason)
+            }
+            None => {
+                println!("Data obrigatória.");
+                return Ok(());
+            }
+        },
+        "3" => match prompt_for_optional_date("Data do fechamento (YYYY-MM-DD): ") {
+            Some(date) => library.remove_closure(date),
+            None => {
+                println!("Data obrigatória.");
+                return Ok(());
+            }
+        },
+        "4" => return Ok(()),
+        _ => {
+            println!("Opção inválida.");
+            return Ok(());
+        }
+    };
+
+    match result {
+        Ok(_) => println!("Calendário atualizado com sucesso."),
+        Err(e) => println!("Erro ao atualizar o calendário: {}", e),
     }
 
     Ok(())
 }
 
+fn parse_weekday(input: &str) -> Option<Weekday> {
+    match input.to_lowercase().as_str() {
+        "seg" => Some(Weekday::Mon),
+        "ter" => Some(Weekday::Tue),
+        "qua" => Some(Weekday::Wed),
+        "qui" => Some(Weekday::Thu),
+        "sex" => Some(Weekday::Fri),
+        "sab" | "sáb" => Some(Weekday::Sat),
+        "dom" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn loan_history(library: &Library) {
+    println!("\n--- Histórico de Empréstimos ---");
+
+    println!("1. Histórico de um usuário");
+    println!("2. Circulação de um livro");
+    let option = prompt_for_string("Escolha uma opção: ");
+    if option != "1" && option != "2" {
+        println!("Opção inválida.");
+        return;
+    }
+
+    let id = prompt_for_u32("Insira o ID: ");
+    let filter = HistoryFilter {
+        active_only: prompt_for_string("Somente empréstimos ativos? (s/N): ")
+            .eq_ignore_ascii_case("s"),
+        from: prompt_for_optional_date("A partir de (YYYY-MM-DD, opcional): "),
+        to: prompt_for_optional_date("Até (YYYY-MM-DD, opcional): "),
+    };
+
+    let entries = if option == "1" {
+        library.loan_history(id, &filter)
+    } else {
+        library.book_circulation(id, &filter)
+    };
+
+    if entries.is_empty() {
+        println!("Nenhum empréstimo encontrado.");
+    } else {
+        Library::print_history(&entries);
+    }
+}
+
 fn list_users(library: &Library) {
     println!("\n--- Lista de usuários ---");
     if library.users.is_empty() {
         println!("Nenhum usuário cadastrado.");
-    } else {
-        for user in &library.users {
-            println!("ID: {}, Nome: {}", user.id, user.name);
+        return;
+    }
+
+    let query = prompt_for_string("Filtrar por nome (em branco para todos): ");
+    if query.is_empty() {
+        library.list_users();
+        return;
+    }
+    let results = library.search_users(&query);
+    if results.is_empty() {
+        println!("Nenhum usuário encontrado com esse termo.");
+    }
+    for user in results {
+        println!("ID: {}, Nome: {}", user.id, user.name);
+    }
+}
+
+fn manage_user(library: &mut Library) -> Result<(), Box<dyn std::error::Error>> {
+    println!("\n--- Gerenciar Usuário ---");
+
+    let user_id = prompt_for_u32("Insira o ID do Usuário: ");
+
+    println!("1. Renovar matrícula");
+    println!("2. Suspender");
+    println!("3. Bloquear");
+    println!("4. Reativar");
+    println!("5. Alterar categoria");
+    println!("6. Limite de empréstimos especial");
+    println!("7. Excluir usuário");
+    println!("8. Alterar nome");
+    println!("9. Atualizar contato");
+    let option = prompt_for_string("Escolha uma opção: ");
+
+    if option == "7" {
+        match library.remove_user(user_id, prompt_for_delete_policy()) {
+            Ok(_) => println!("Usuário excluído com sucesso."),
+            Err(e) => println!("Erro ao excluir o usuário: {}", e),
         }
+        return Ok(());
     }
+
+    let result = match option.as_str() {
+        "1" => match prompt_for_optional_date("Nova validade (YYYY-MM-DD): ") {
+            Some(expiry) => library.renew_membership(user_id, expiry),
+            None => {
+                println!("Data obrigatória.");
+                return Ok(());
+            }
+        },
+        "2" => library.set_user_status(user_id, AccountStatus::Suspended),
+        "3" => library.set_user_status(user_id, AccountStatus::Blocked),
+        "4" => library.set_user_status(user_id, AccountStatus::Active),
+        "5" => {
+            library.list_policies();
+            library.set_user_category(user_id, prompt_for_category())
+        }
+        "6" => {
+            let limit =
+                prompt_for_optional_u32("Novo limite (em branco para usar o da categoria): ");
+            library.set_user_loan_limit(user_id, Some(limit).filter(|&l| l > 0))
+        }
+        "8" => library.rename_user(user_id, prompt_for_string("Novo nome: ")),
+        "9" => {
+            let contact = ContactDetails {
+                email: prompt_for_optional_string("E-mail (opcional): "),
+                phone: prompt_for_optional_string("Telefone (opcional): "),
+                address: prompt_for_optional_string("Endereço (opcional): "),
+            };
+            let date_of_birth =
+                prompt_for_optional_date("Data de Nascimento (YYYY-MM-DD, opcional): ");
+            library.update_user_contact(user_id, contact, date_of_birth)
+        }
+        _ => {
+            println!("Opção inválida.");
+            return Ok(());
+        }
+    };
+
+    match result {
+        Ok(_) => println!("Usuário atualizado com sucesso."),
+        Err(e) => println!("Erro ao atualizar o usuário: {}", e),
+    }
+
+    Ok(())
 }
 
 fn search_books(library: &Library) -> Result<(), Box<dyn std::error::Error>> {
@@ -204,11 +1268,28 @@ fn search_books(library: &Library) -> Result<(), Box<dyn std::error::Error>> {
         println!("Nenhum livro encontrado com esse termo.");
     } else {
         println!("Livros encontrados:");
-        for book in results {
+        for book in &results {
             println!(
-                "ID: {}, Titulo: {}, Autor: {}, Paginas: {}",
-                book.id, book.title, book.author, book.pages
+                "ID: {}, Titulo: {}, Autor: {}, Tipo: {}, Detalhes: {}",
+                book.id,
+                book.title,
+                book.author,
+                book.format(),
+                book.details
             );
+            if let Some(branch_id) = book.current_branch {
+                println!(
+                    "  Filial: {} ({})",
+                    library.branch_name(branch_id),
+                    book.status
+                );
+            }
+        }
+
+        let availability = library.branch_availability(&results);
+        if !availability.is_empty() {
+            println!("Disponibilidade por filial:");
+            Library::print_branch_availability(&availability);
         }
     }
 
@@ -225,6 +1306,188 @@ fn prompt_for_string(prompt: &str) -> String {
     input.trim().to_string()
 }
 
+fn prompt_for_delete_policy() -> DeletePolicy {
+    println!("O que fazer com o histórico?");
+    println!("1. Impedir a exclusão se houver histórico");
+    println!("2. Arquivar o histórico");
+    println!("3. Anonimizar o histórico");
+    loop {
+        match prompt_for_string("Escolha uma opção: ").as_str() {
+            "1" => break DeletePolicy::Block,
+            "2" => break DeletePolicy::CascadeArchive,
+            "3" => break DeletePolicy::Anonymize,
+            _ => println!("Opção inválida. Tente novamente."),
+        }
+    }
+}
+
+fn prompt_for_optional_string(prompt: &str) -> Option<String> {
+    let input = prompt_for_string(prompt);
+    if input.is_empty() {
+        None
+    } else {
+        Some(input)
+    }
+}
+
+/// Reads an amount such as "12.50" and returns it in cents.
+fn prompt_for_amount(prompt: &str) -> i64 {
+    loop {
+        let input = prompt_for_string(prompt).replace(',', ".");
+        match input.parse::<f64>() {
+            Ok(value) if value >= 0.0 => break (value * 100.0).round() as i64,
+            _ => println!("Valor inválido. Tente novamente."),
+        }
+    }
+}
+
+fn prompt_for_optional_amount(prompt: &str) -> Option<i64> {
+    loop {
+        let input = prompt_for_string(prompt).replace(',', ".");
+        if input.is_empty() {
+            break None;
+        }
+
+        match input.parse::<f64>() {
+            Ok(value) if value >= 0.0 => break Some((value * 100.0).round() as i64),
+            _ => println!("Valor inválido. Tente novamente."),
+        }
+    }
+}
+
+fn prompt_for_date(prompt: &str) -> NaiveDate {
+    loop {
+        match Library::parse_date(&prompt_for_string(prompt)) {
+            Ok(date) => break date,
+            Err(e) => println!("{}. Tente novamente.", e),
+        }
+    }
+}
+
+fn prompt_for_optional_date(prompt: &str) -> Option<NaiveDate> {
+    loop {
+        let input = prompt_for_string(prompt);
+        if input.is_empty() {
+            break None;
+        }
+
+        match NaiveDate::parse_from_str(&input, "%Y-%m-%d") {
+            Ok(date) => break Some(date),
+            Err(_) => println!("Data inválida. Use o formato YYYY-MM-DD."),
+        }
+    }
+}
+
+fn prompt_for_frequency() -> Frequency {
+    println!("Periodicidade:");
+    println!("1. Semanal");
+    println!("2. Quinzenal");
+    println!("3. Mensal");
+    println!("4. Bimestral");
+    println!("5. Trimestral");
+    println!("6. Anual");
+
+    loop {
+        match prompt_for_string("Escolha uma opção: ").as_str() {
+            "1" => break Frequency::Weekly,
+            "2" => break Frequency::Biweekly,
+            "3" => break Frequency::Monthly,
+            "4" => break Frequency::Bimonthly,
+            "5" => break Frequency::Quarterly,
+            "6" => break Frequency::Annual,
+            _ => println!("Opção inválida. Tente novamente."),
+        }
+    }
+}
+
+fn prompt_for_material_format() -> MaterialFormat {
+    println!("Tipo de material:");
+    println!("1. Livro");
+    println!("2. Periódico");
+    println!("3. DVD");
+    println!("4. Audiolivro");
+    println!("5. Jogo de tabuleiro");
+    println!("6. Equipamento");
+
+    loop {
+        match prompt_for_string("Escolha uma opção: ").as_str() {
+            "1" => break MaterialFormat::Book,
+            "2" => break MaterialFormat::Periodical,
+            "3" => break MaterialFormat::Dvd,
+            "4" => break MaterialFormat::Audiobook,
+            "5" => break MaterialFormat::BoardGame,
+            "6" => break MaterialFormat::Equipment,
+            _ => println!("Opção inválida. Tente novamente."),
+        }
+    }
+}
+
+fn prompt_for_material_details(format: MaterialFormat) -> MaterialDetails {
+    match format {
+        MaterialFormat::Book => MaterialDetails::Book {
+            pages: prompt_for_u32("Insira o Número de Páginas: "),
+        },
+        MaterialFormat::Periodical => MaterialDetails::Periodical {
+            pages: prompt_for_u32("Insira o Número de Páginas: "),
+        },
+        MaterialFormat::Dvd => MaterialDetails::Dvd {
+            runtime_minutes: prompt_for_u32("Insira a Duração (minutos): "),
+            discs: prompt_for_u32("Insira o Número de Discos: "),
+        },
+        MaterialFormat::Audiobook => MaterialDetails::Audiobook {
+            runtime_minutes: prompt_for_u32("Insira a Duração (minutos): "),
+            discs: prompt_for_u32("Insira o Número de Discos: "),
+        },
+        MaterialFormat::BoardGame => MaterialDetails::BoardGame {
+            min_players: prompt_for_u32("Insira o Número Mínimo de Jogadores: "),
+            max_players: prompt_for_u32("Insira o Número Máximo de Jogadores: "),
+        },
+        MaterialFormat::Equipment => MaterialDetails::Equipment {
+            serial_number: prompt_for_string("Insira o Número de Série: "),
+            accessories: prompt_for_string(
+                "Insira os Acessórios Incluídos (separados por vírgula): ",
+            )
+            .split(',')
+            .map(|a| a.trim().to_string())
+            .filter(|a| !a.is_empty())
+            .collect(),
+        },
+    }
+}
+
+fn prompt_for_category() -> PatronCategory {
+    println!("Categoria do usuário:");
+    println!("1. Estudante");
+    println!("2. Funcionário");
+    println!("3. Criança");
+    println!("4. Externo");
+
+    loop {
+        match prompt_for_string("Escolha uma opção: ").as_str() {
+            "1" => break PatronCategory::Student,
+            "2" => break PatronCategory::Staff,
+            "3" => break PatronCategory::Child,
+            "4" => break PatronCategory::External,
+            _ => println!("Opção inválida. Tente novamente."),
+        }
+    }
+}
+
+fn prompt_for_classification(optional: bool) -> Option<Classification> {
+    println!("Sistema de classificação:");
+    println!("1. Dewey (CDD)");
+    println!("2. Library of Congress (LCC)");
+    if optional {
+        println!("Deixe em branco para não informar.");
+    }
+
+    match prompt_for_string("Escolha uma opção: ").as_str() {
+        "1" => Some(Classification::Dewey),
+        "2" => Some(Classification::LibraryOfCongress),
+        _ => None,
+    }
+}
+
 fn prompt_for_u32(prompt: &str) -> u32 {
     loop {
         print!("{}", prompt);
@@ -239,3 +1502,18 @@ fn prompt_for_u32(prompt: &str) -> u32 {
         }
     }
 }
+
+/// Returns 0 when the input is left blank, meaning "allocate an id".
+fn prompt_for_optional_u32(prompt: &str) -> u32 {
+    loop {
+        let input = prompt_for_string(prompt);
+        if input.is_empty() {
+            break 0;
+        }
+
+        match input.parse() {
+            Ok(num) => break num,
+            Err(_) => println!("Entrada inválida. Tente novamente."),
+        }
+    }
+}